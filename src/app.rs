@@ -23,13 +23,14 @@ use std::{
 use crate::{
     camera::{self, Camera},
     commands::command::{get_commands, Command},
-    components::component::HoverElement,
+    components::component::{ComponentType, HoverElement},
+    dispatchers::dispatcher::{DispatcherEvent, TOOL_ID},
     project::{self, Project, ProjectState},
     rendering::{
         buffer_reader::{execute, BufferReader},
         renderer::{self, Renderer},
     },
-    ui::{main_menu::draw_commands, tabcontrol},
+    ui::{main_menu::draw_commands, properties::show_properties, tabcontrol},
 };
 
 pub struct AppState {
@@ -131,6 +132,11 @@ pub async fn update_async(ctx: &egui::Context, renderstate: &RenderState) {
         let project = &mut appstate.projects[appstate.selected_project];
 
         draw_commands(ui, project, &appstate.commands);
+        ui.horizontal(|ui| {
+            for dispatcher in project.dispatchers.values_mut() {
+                dispatcher.draw2d_nointeract(&mut project.state, ui);
+            }
+        });
     }
 
     let (rect, response) = ui.allocate_at_least(ui.available_size(), egui::Sense::drag());
@@ -141,6 +147,13 @@ pub async fn update_async(ctx: &egui::Context, renderstate: &RenderState) {
         let project = &mut appstate.projects[appstate.selected_project];
 
         update_camera(&mut project.state, rect, response, ctx);
+        run_dispatchers(project, ctx);
+        show_properties(ctx, &mut project.state);
+        if project.state.parameters.dirty {
+            let state = &mut project.state;
+            state.parameters.update(&state.units, &mut state.components);
+        }
+        update_fills(&mut project.state, &appstate.renderer);
         flush_buffer(project, renderstate, rect, ctx);
     }
     run_compute_pass(renderstate);
@@ -214,6 +227,50 @@ impl eframe::App for App {
     }
 }
 
+fn run_dispatchers(project: &mut Project, ctx: &egui::Context) {
+    while let Ok(event) = project.receiver.try_recv() {
+        match event {
+            DispatcherEvent::Add(id, dispatcher) => {
+                if let Some(mut old) = project.dispatchers.insert(id, dispatcher) {
+                    old.close(&mut project.state);
+                }
+            }
+            DispatcherEvent::Remove(id) => {
+                if let Some(mut old) = project.dispatchers.remove(&id) {
+                    old.close(&mut project.state);
+                }
+            }
+        }
+    }
+
+    for dispatcher in project.dispatchers.values_mut() {
+        dispatcher.prepare(&mut project.state);
+    }
+    // the tool runs last, so the components of its preview stay at the end of the arrays
+    for (_, dispatcher) in project.dispatchers.iter_mut().filter(|(id, _)| **id != TOOL_ID) {
+        dispatcher.interact(&mut project.state);
+        dispatcher.draw2d(&mut project.state, ctx);
+    }
+    if let Some(dispatcher) = project.dispatchers.get_mut(&TOOL_ID) {
+        dispatcher.interact(&mut project.state);
+        dispatcher.draw2d(&mut project.state, ctx);
+    }
+}
+
+fn update_fills(state: &mut ProjectState, renderer: &Renderer) {
+    let tolerance = state.tessellation.tolerance;
+    state.fills.update(&mut state.components, &state.camera.plane, tolerance);
+    // the curves past the limit are left out of the drawing
+    if let Err(error) = state.components.reserve_segments() {
+        warn!("{}", error);
+    }
+    if state.components.take_resized() {
+        if let Some(buffer) = Arc::get_mut(&mut state.uniform_buffer) {
+            buffer.update_bind_groups(&state.components, renderer);
+        }
+    }
+}
+
 fn run_render_pass(ui: &mut egui::Ui, rect: Rect) {
     ui.painter().add(egui_wgpu::Callback::new_paint_callback(
         rect,
@@ -233,6 +290,7 @@ fn flush_buffer(project: &mut Project, renderstate: &RenderState, rect: Rect, ct
         .state
         .uniform_buffer
         .clear_hover_counter(&renderstate.queue);
+    project.state.components.reset_segments();
     project.state.uniform_buffer.write(
         &renderstate.queue,
         0,
@@ -258,6 +316,21 @@ fn flush_buffer(project: &mut Project, renderstate: &RenderState, rect: Rect, ct
         4 * 16,
         &project.state.camera.projection_view_matrix,
     );
+    project.state.uniform_buffer.write(
+        &renderstate.queue,
+        4 * 16 + 4 * 16,
+        &project.state.grid.uniforms(project.state.camera.target),
+    );
+    project.state.uniform_buffer.write(
+        &renderstate.queue,
+        4 * 16 + 4 * 16 + 4 * 8,
+        &project.state.line_style.uniforms(),
+    );
+    project.state.uniform_buffer.write(
+        &renderstate.queue,
+        4 * 16 + 4 * 16 + 4 * 8 + 4 * 4,
+        &project.state.theme.uniforms(),
+    );
 }
 
 fn run_compute_pass(renderstate: &RenderState) {
@@ -293,6 +366,8 @@ fn tabcontrolheader(renderstate: &RenderState, ui: &mut egui::Ui) {
 }
 
 fn update_camera(project: &mut ProjectState, rect: Rect, response: Response, ctx: &egui::Context) {
+    // clicks in windows or after dragging the camera don't count
+    project.is_mouse_clicked = response.hovered() && ctx.input(|i| i.pointer.primary_clicked());
     if let Some(pos) = response.hover_pos() {
         project.hover_pos = glam::vec2(
             pos.x * ctx.pixels_per_point(),
@@ -319,6 +394,58 @@ fn update_camera(project: &mut ProjectState, rect: Rect, response: Response, ctx
         pos.x * ctx.pixels_per_point(),
         pos.y * ctx.pixels_per_point(),
     ));
+
+    update_grid(project);
+    snap_to_meshes(project, glam::vec2(pos.x, pos.y) * ctx.pixels_per_point(), ctx.pixels_per_point());
+}
+
+// pixels between the mouse and a mesh corner it snaps to
+const MESH_SNAP_DISTANCE: f32 = 10.0;
+
+// the mesh compute shader reports the corner of the hovered triangle next to the mouse
+fn snap_to_meshes(project: &mut ProjectState, mouse: glam::Vec2, pixels_per_point: f32) {
+    let nearest = project
+        .components
+        .hovers
+        .iter()
+        .filter(|h| h.ctype == ComponentType::Mesh)
+        .min_by(|a, b| a.distance.total_cmp(&b.distance));
+    let Some(hover) = nearest else {
+        return;
+    };
+
+    let camera = &project.camera;
+    let clip = camera.projection_view_matrix * hover.position.extend(1.0);
+    if clip.w <= 0.0 {
+        return;
+    }
+    let ndc = clip.truncate() / clip.w;
+    let viewport = camera.viewport;
+    let screen = glam::vec2(
+        viewport.left() + (ndc.x + 1.0) * 0.5 * viewport.width(),
+        viewport.top() + (1.0 - ndc.y) * 0.5 * viewport.height(),
+    );
+    if screen.distance(mouse) < MESH_SNAP_DISTANCE * pixels_per_point {
+        project.camera.world_mouse_position = hover.position;
+    }
+}
+
+fn update_grid(project: &mut ProjectState) {
+    project.grid.update(&project.camera, &project.units);
+    if project.grid.snap {
+        project.camera.world_mouse_position = project
+            .grid
+            .snap(&project.camera.plane, project.camera.world_mouse_position);
+    }
+
+    let plane = project.camera.plane.clone();
+    let grid = &project.components.grids.array[0].data;
+    if grid.position != plane.position || grid.direction != plane.orientation {
+        project.components.grids.update(0, |v| {
+            v.data.position = plane.position;
+            v.data.direction = plane.orientation;
+        });
+    }
 }
 
 #[derive(Clone)]
@@ -1,8 +1,10 @@
+use std::sync::Mutex;
+
 use async_std::channel::Sender;
 use egui::{TextureHandle, Key};
 use crate::{project::Project,  dispatchers::dispatcher::{DispatcherEvent}, ui::icons::IconCollection,};
 
-use super::{view_commands::{RightView, LeftView, BackView, ChangeProjection, HomeView, TopView, BottomView, FrontView}};
+use super::{file_commands::{Export, Import, ReferenceImage}, draw_commands::{ArcCenterStartEnd, ArcThreePoints, ConvertCurves, DrawEllipse, DrawSpline, TangentArc, TextTool, ToggleConstruction}, grid_commands::GridSettings, parameter_commands::ParameterTable, settings_commands::ProjectSettings, solid_commands::{Extrude, Loft, Revolve, Sweep}, view_commands::{RightView, LeftView, BackView, ChangeProjection, HomeView, TopView, BottomView, FrontView}};
 
 
 pub fn get_commands() -> Vec<Command> {
@@ -71,6 +73,174 @@ pub fn get_commands() -> Vec<Command> {
             function: Box::new(RightView),
             get_icon: Box::new(|x| &x.right_view),
         },
+        Command {
+            id: uuid::Uuid::new_v4(),
+            name: "Grid".into(),
+            down_keys: vec![],
+            released_key: Some(Key::G),
+            function: Box::new(GridSettings {
+                window: Mutex::new(None),
+            }),
+            get_icon: Box::new(|x| &x.grid),
+        },
+        Command {
+            id: uuid::Uuid::new_v4(),
+            name: "Project Settings".into(),
+            down_keys: vec![],
+            released_key: None,
+            function: Box::new(ProjectSettings {
+                window: Mutex::new(None),
+            }),
+            get_icon: Box::new(|x| &x.settings),
+        },
+        Command {
+            id: uuid::Uuid::new_v4(),
+            name: "Parameters".into(),
+            down_keys: vec![],
+            released_key: None,
+            function: Box::new(ParameterTable {
+                window: Mutex::new(None),
+            }),
+            get_icon: Box::new(|x| &x.parameters),
+        },
+        Command {
+            id: uuid::Uuid::new_v4(),
+            name: "Arc".into(),
+            down_keys: vec![],
+            released_key: None,
+            function: Box::new(ArcCenterStartEnd),
+            get_icon: Box::new(|x| &x.arc_center),
+        },
+        Command {
+            id: uuid::Uuid::new_v4(),
+            name: "Three Point Arc".into(),
+            down_keys: vec![],
+            released_key: None,
+            function: Box::new(ArcThreePoints),
+            get_icon: Box::new(|x| &x.arc_three_points),
+        },
+        Command {
+            id: uuid::Uuid::new_v4(),
+            name: "Tangent Arc".into(),
+            down_keys: vec![],
+            released_key: None,
+            function: Box::new(TangentArc),
+            get_icon: Box::new(|x| &x.arc_tangent),
+        },
+        Command {
+            id: uuid::Uuid::new_v4(),
+            name: "Ellipse".into(),
+            down_keys: vec![],
+            released_key: None,
+            function: Box::new(DrawEllipse),
+            get_icon: Box::new(|x| &x.ellipse),
+        },
+        Command {
+            id: uuid::Uuid::new_v4(),
+            name: "Spline".into(),
+            down_keys: vec![],
+            released_key: None,
+            function: Box::new(DrawSpline),
+            get_icon: Box::new(|x| &x.spline),
+        },
+        Command {
+            id: uuid::Uuid::new_v4(),
+            name: "Text".into(),
+            down_keys: vec![],
+            released_key: None,
+            function: Box::new(TextTool {
+                window: Mutex::new(None),
+            }),
+            get_icon: Box::new(|x| &x.text),
+        },
+        Command {
+            id: uuid::Uuid::new_v4(),
+            name: "Convert Curves".into(),
+            down_keys: vec![],
+            released_key: None,
+            function: Box::new(ConvertCurves {
+                window: Mutex::new(None),
+            }),
+            get_icon: Box::new(|x| &x.convert_curves),
+        },
+        Command {
+            id: uuid::Uuid::new_v4(),
+            name: "Construction".into(),
+            down_keys: vec![],
+            released_key: None,
+            function: Box::new(ToggleConstruction),
+            get_icon: Box::new(|x| &x.construction),
+        },
+        Command {
+            id: uuid::Uuid::new_v4(),
+            name: "Extrude".into(),
+            down_keys: vec![],
+            released_key: None,
+            function: Box::new(Extrude {
+                window: Mutex::new(None),
+            }),
+            get_icon: Box::new(|x| &x.extrude),
+        },
+        Command {
+            id: uuid::Uuid::new_v4(),
+            name: "Revolve".into(),
+            down_keys: vec![],
+            released_key: None,
+            function: Box::new(Revolve {
+                window: Mutex::new(None),
+            }),
+            get_icon: Box::new(|x| &x.revolve),
+        },
+        Command {
+            id: uuid::Uuid::new_v4(),
+            name: "Sweep".into(),
+            down_keys: vec![],
+            released_key: None,
+            function: Box::new(Sweep {
+                window: Mutex::new(None),
+            }),
+            get_icon: Box::new(|x| &x.sweep),
+        },
+        Command {
+            id: uuid::Uuid::new_v4(),
+            name: "Loft".into(),
+            down_keys: vec![],
+            released_key: None,
+            function: Box::new(Loft {
+                window: Mutex::new(None),
+            }),
+            get_icon: Box::new(|x| &x.loft),
+        },
+        Command {
+            id: uuid::Uuid::new_v4(),
+            name: "Export".into(),
+            down_keys: vec![],
+            released_key: None,
+            function: Box::new(Export {
+                window: Mutex::new(None),
+            }),
+            get_icon: Box::new(|x| &x.export),
+        },
+        Command {
+            id: uuid::Uuid::new_v4(),
+            name: "Import".into(),
+            down_keys: vec![],
+            released_key: None,
+            function: Box::new(Import {
+                window: Mutex::new(None),
+            }),
+            get_icon: Box::new(|x| &x.import),
+        },
+        Command {
+            id: uuid::Uuid::new_v4(),
+            name: "Reference Image".into(),
+            down_keys: vec![],
+            released_key: None,
+            function: Box::new(ReferenceImage {
+                window: Mutex::new(None),
+            }),
+            get_icon: Box::new(|x| &x.image),
+        },
     ];
 
     return commands;
@@ -0,0 +1,429 @@
+use std::sync::Mutex;
+
+use async_std::channel::Sender;
+use egui::Color32;
+use glam::Vec3;
+use uuid::Uuid;
+
+use crate::{
+    component_collection::ComponentCollection,
+    components::{
+        arc::{self, circumcenter},
+        bezier,
+        component::{ComponentType, IComponent, WalkDirection},
+        ellipse, line,
+        nurbs::{self, clamped_knots, from_beziers, push_nurbs, to_beziers},
+        point,
+    },
+    core::{
+        basics::Plane,
+        result::{Error, Result},
+    },
+    dispatchers::dispatcher::{place, remove, stop_tool, tool, window, DispatcherEvent},
+    project::{Project, ProjectState},
+    topology::{
+        chain::chain,
+        text::{Text, TextAlign},
+    },
+    ui::expression_edit::length_edit,
+};
+
+use super::command::CommandFunction;
+
+fn normal(state: &ProjectState) -> Vec3 {
+    state.camera.plane.orientation.normalize()
+}
+
+// the tool dispatcher moves clicks on a hovered point onto it, such a point is shared instead of doubled
+fn point_at(components: &mut ComponentCollection, position: Vec3) -> u32 {
+    let existing = components
+        .points
+        .array
+        .iter()
+        .position(|p| !p.is_deleted() && p.data.position == position);
+    return match existing {
+        Some(index) => index as u32,
+        None => components.points.push(point::new(position)) as u32,
+    };
+}
+
+fn push_line(components: &mut ComponentCollection, a: Vec3, b: Vec3) {
+    let a = point_at(components, a);
+    let b = point_at(components, b);
+    components.lines.push(line::new(a, b));
+}
+
+// center, start and end, the end is moved onto the circle through the start
+pub struct ArcCenterStartEnd;
+
+impl CommandFunction for ArcCenterStartEnd {
+    fn start(&self, sender: Sender<DispatcherEvent>, _: &mut Project) {
+        tool(sender, Some(3), |state, positions| {
+            let normal = normal(state);
+            let components = &mut state.components;
+            match positions {
+                [center, start] => push_line(components, *center, *start),
+                [center, start, end] => {
+                    let radius = center.distance(*start);
+                    let end = *center + (*end - *center).normalize_or_zero() * radius;
+                    let c = point_at(components, *center);
+                    let s = point_at(components, *start);
+                    let e = point_at(components, end);
+                    components.arcs.push(arc::new(c, s, e, normal));
+                }
+                _ => {}
+            }
+        });
+    }
+}
+
+// start, a point on the arc and the end
+pub struct ArcThreePoints;
+
+impl CommandFunction for ArcThreePoints {
+    fn start(&self, sender: Sender<DispatcherEvent>, _: &mut Project) {
+        tool(sender, Some(3), |state, positions| {
+            let normal = normal(state);
+            let components = &mut state.components;
+            match positions {
+                [start, through] => push_line(components, *start, *through),
+                [start, through, end] => match circumcenter(*start, *through, *end) {
+                    Some(center) => {
+                        // the arc runs counter clockwise around its orientation through the middle point
+                        let turn = (*through - *start).cross(*end - *start).dot(normal);
+                        let orientation = if turn > 0. { normal } else { -normal };
+                        let c = components.points.push(point::new(center)) as u32;
+                        let s = point_at(components, *start);
+                        let e = point_at(components, *end);
+                        components.arcs.push(arc::new(c, s, e, orientation));
+                    }
+                    None => push_line(components, *start, *end),
+                },
+                _ => {}
+            }
+        });
+    }
+}
+
+// continues the selected line from its end point without a kink
+pub struct TangentArc;
+
+impl CommandFunction for TangentArc {
+    fn start(&self, sender: Sender<DispatcherEvent>, project: &mut Project) {
+        let components = &project.state.components;
+        let lines: Vec<u32> = components
+            .selected
+            .iter()
+            .filter(|s| s.ctype == ComponentType::Line)
+            .map(|s| s.index)
+            .collect();
+        let [line] = lines[..] else {
+            return;
+        };
+        let line = components.lines.array[line as usize].data.clone();
+
+        tool(sender, Some(1), move |state, positions| {
+            let normal = normal(state);
+            let components = &mut state.components;
+            let start = components.points.array[line.point_b as usize].data.position;
+            let a = components.points.array[line.point_a as usize].data.position;
+            let direction = start - a;
+            let tangent = (direction - normal * direction.dot(normal)).normalize_or_zero();
+            let side = normal.cross(tangent);
+
+            let end = positions[0];
+            let chord = end - start;
+            let offset = chord.dot(side);
+            if offset.abs() < 1e-6 {
+                push_line(components, start, end);
+                return;
+            }
+            // the center lies on the normal of the line, as far from the start as from the end
+            let radius = chord.length_squared() / (2. * offset);
+            let center = start + side * radius;
+            let orientation = if radius > 0. { normal } else { -normal };
+            let c = components.points.push(point::new(center)) as u32;
+            let e = point_at(components, end);
+            components.arcs.push(arc::new(c, line.point_b, e, orientation));
+        });
+    }
+}
+
+// center, the end of the major axis and a point the minor radius reaches
+pub struct DrawEllipse;
+
+impl CommandFunction for DrawEllipse {
+    fn start(&self, sender: Sender<DispatcherEvent>, _: &mut Project) {
+        tool(sender, Some(3), |state, positions| {
+            let normal = normal(state);
+            let plane = &state.camera.plane;
+            let (u, v) = plane.basis();
+            let components = &mut state.components;
+            match positions {
+                [center, major] => push_line(components, *center, *major),
+                [center, major, minor] => {
+                    let axis = *major - *center;
+                    let radius_a = axis.length();
+                    let rotation = axis.dot(v).atan2(axis.dot(u));
+                    let minor_axis = normal.cross(axis.normalize_or_zero());
+                    let radius_b = (*minor - *center).dot(minor_axis).abs().max(1e-3);
+                    let c = point_at(components, *center);
+                    components
+                        .ellipses
+                        .push(ellipse::new(c, radius_a, radius_b, plane.orientation, rotation));
+                }
+                _ => {}
+            }
+        });
+    }
+}
+
+// control points until enter is pressed, the curve is cubic once there are enough of them
+pub struct DrawSpline;
+
+impl CommandFunction for DrawSpline {
+    fn start(&self, sender: Sender<DispatcherEvent>, _: &mut Project) {
+        tool(sender, None, |state, positions| {
+            let components = &mut state.components;
+            if positions.len() < 2 {
+                return;
+            }
+            let points: Vec<u32> = positions.iter().map(|p| point_at(components, *p)).collect();
+            let degree = (points.len() - 1).min(3);
+            let weights = vec![1.; points.len()];
+            push_nurbs(components, &points, &weights, &clamped_knots(points.len(), degree), degree as u32);
+        });
+    }
+}
+
+pub struct ConvertCurves {
+    pub window: Mutex<Option<Uuid>>,
+}
+
+// one nurbs through the selected beziers, they have to form a single path
+fn beziers_to_nurbs(components: &mut ComponentCollection) -> Result<()> {
+    let beziers: Vec<_> = components
+        .selected
+        .iter()
+        .filter(|s| s.ctype == ComponentType::Bezier)
+        .cloned()
+        .collect();
+    if beziers.is_empty() {
+        return Err(Error::Geometry("select the beziers to convert".into()));
+    }
+    let segments: Vec<[Vec3; 4]> = chain(components, &beziers)?
+        .iter()
+        .map(|edge| {
+            let mut p = components.beziers.array[edge.index as usize].data.controls(components);
+            if edge.direction == WalkDirection::Down {
+                p.reverse();
+            }
+            p
+        })
+        .collect();
+
+    let (positions, knots) = from_beziers(&segments);
+    let points: Vec<u32> = positions
+        .iter()
+        .map(|p| components.points.push(point::new(*p)) as u32)
+        .collect();
+    push_nurbs(components, &points, &vec![1.; points.len()], &knots, 3);
+    return Ok(());
+}
+
+// cubic beziers along each selected nurbs, sharing their end points
+fn nurbs_to_beziers(components: &mut ComponentCollection) -> Result<()> {
+    let selected: Vec<u32> = components
+        .selected
+        .iter()
+        .filter(|s| s.ctype == ComponentType::Nurbs)
+        .map(|s| s.index)
+        .collect();
+    if selected.is_empty() {
+        return Err(Error::Geometry("select the nurbs to convert".into()));
+    }
+    let curves = selected
+        .iter()
+        .map(|i| {
+            let nurbs: &nurbs::Nurbs = &components.nurbs.array[*i as usize].data;
+            to_beziers(&nurbs.homogeneous(components), &nurbs.knots(components), nurbs.degree as usize)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    for segments in curves.iter() {
+        let mut start = components.points.push(point::new(segments[0][0])) as u32;
+        for p in segments.iter() {
+            let control_a = components.points.push(point::new(p[1])) as u32;
+            let control_b = components.points.push(point::new(p[2])) as u32;
+            let end = components.points.push(point::new(p[3])) as u32;
+            components.beziers.push(bezier::new(start, end, control_a, control_b));
+            start = end;
+        }
+    }
+    return Ok(());
+}
+
+impl CommandFunction for ConvertCurves {
+    fn start(&self, sender: Sender<DispatcherEvent>, _: &mut Project) {
+        let mut window_id = self.window.lock().unwrap();
+        if let Some(id) = window_id.take() {
+            remove(sender, id);
+            return;
+        }
+
+        let error: Mutex<Option<String>> = Mutex::new(None);
+        *window_id = Some(window(sender, "Convert Curves", move |state, ui| {
+            let mut error = error.lock().unwrap();
+            // the original curves are kept, the converted ones are added on top of them
+            if ui.button("Beziers to NURBS").clicked() {
+                *error = beziers_to_nurbs(&mut state.components).err().map(|e| e.to_string());
+            }
+            if ui.button("NURBS to beziers").clicked() {
+                *error = nurbs_to_beziers(&mut state.components).err().map(|e| e.to_string());
+            }
+            if let Some(error) = &*error {
+                ui.colored_label(Color32::RED, error);
+            }
+        }));
+    }
+}
+
+// turns the selected curves into construction geometry or back, all of them follow the first one
+pub struct ToggleConstruction;
+
+impl CommandFunction for ToggleConstruction {
+    fn start(&self, _: Sender<DispatcherEvent>, project: &mut Project) {
+        let components = &mut project.state.components;
+        let curves: Vec<_> = components
+            .selected
+            .iter()
+            .filter(|s| match s.ctype {
+                ComponentType::Line
+                | ComponentType::Circle
+                | ComponentType::Bezier
+                | ComponentType::Arc
+                | ComponentType::Ellipse
+                | ComponentType::Nurbs => true,
+                _ => false,
+            })
+            .cloned()
+            .collect();
+        let Some(first) = curves.first() else {
+            return;
+        };
+        let construction = !components.get_c(first.ctype, first.index as usize, |c| {
+            c.map_or(false, |c| c.is_construction())
+        });
+        for curve in curves.iter() {
+            components.update_c(curve.ctype, curve.index as usize, move |c| c.set_construction(construction));
+        }
+    }
+}
+
+// outlines of a string in the bundled font, placed with a click on the sketch plane
+pub struct TextTool {
+    pub window: Mutex<Option<Uuid>>,
+}
+
+struct TextSettings {
+    selected: Option<usize>,
+    error: Option<String>,
+}
+
+// the text follows the mouse until the next click
+fn place_text(sender: Sender<DispatcherEvent>, index: usize) {
+    place(sender, move |state, position| {
+        if index >= state.texts.texts.len() {
+            return;
+        }
+        state.texts.texts[index].plane = Plane {
+            position,
+            orientation: normal(state),
+        };
+        let _ = state.texts.rebuild(&mut state.components, index);
+    });
+}
+
+impl CommandFunction for TextTool {
+    fn start(&self, sender: Sender<DispatcherEvent>, _: &mut Project) {
+        let mut window_id = self.window.lock().unwrap();
+        if let Some(id) = window_id.take() {
+            remove(sender, id);
+            return;
+        }
+
+        let settings = Mutex::new(TextSettings {
+            selected: None,
+            error: None,
+        });
+        let place_sender = sender.clone();
+        *window_id = Some(window(sender, "Text", move |state, ui| {
+            let mut settings = settings.lock().unwrap();
+            if ui.button("New text").clicked() {
+                let plane = Plane {
+                    position: state.camera.world_mouse_position,
+                    orientation: normal(state),
+                };
+                let text = Text::new("Text".into(), 10., TextAlign::Left, plane);
+                match state.texts.add(&mut state.components, text) {
+                    Ok(index) => {
+                        settings.selected = Some(index);
+                        place_text(place_sender.clone(), index);
+                    }
+                    Err(error) => settings.error = Some(error.to_string()),
+                }
+            }
+            let count = state.texts.texts.len();
+            if settings.selected.map_or(true, |i| i >= count) {
+                settings.selected = count.checked_sub(1);
+            }
+            let Some(index) = settings.selected else {
+                return;
+            };
+
+            egui::ComboBox::from_label("Text")
+                .selected_text(format!("Text {}", index + 1))
+                .show_ui(ui, |ui| {
+                    for i in 0..count {
+                        ui.selectable_value(&mut settings.selected, Some(i), format!("Text {}", i + 1));
+                    }
+                });
+            let units = state.units.clone();
+            let text = &mut state.texts.texts[index];
+            let mut changed = ui.text_edit_multiline(&mut text.content).changed();
+            ui.horizontal(|ui| {
+                ui.label("Size");
+                let mut size = text.size;
+                if length_edit(ui, ui.id().with("text_size"), &units, &mut size) && size > 0. {
+                    text.size = size;
+                    changed = true;
+                }
+            });
+            let align = text.align;
+            egui::ComboBox::from_label("Alignment")
+                .selected_text(text.align.name())
+                .show_ui(ui, |ui| {
+                    for align in TextAlign::ALL {
+                        ui.selectable_value(&mut text.align, align, align.name());
+                    }
+                });
+            changed |= align != text.align;
+            if changed {
+                settings.error = state.texts.rebuild(&mut state.components, index).err().map(|e| e.to_string());
+            }
+
+            ui.horizontal(|ui| {
+                if ui.button("Move").clicked() {
+                    place_text(place_sender.clone(), index);
+                }
+                if ui.button("Remove").clicked() {
+                    stop_tool(place_sender.clone());
+                    state.texts.remove(&mut state.components, index);
+                }
+            });
+            if let Some(error) = &settings.error {
+                ui.colored_label(Color32::RED, error);
+            }
+        }));
+    }
+}
@@ -0,0 +1,728 @@
+use std::sync::{Arc, Mutex};
+
+use async_std::channel::{Receiver, Sender, TryRecvError};
+use egui::{Color32, Ui};
+use glam::Vec3;
+use uuid::Uuid;
+
+use crate::{
+    components::{
+        component::{ComponentFlags, IComponent},
+        image::{self, Image},
+    },
+    core::{
+        result::{Error, Result},
+        units::Unit,
+    },
+    dispatchers::dispatcher::{remove, stop_tool, tool, window, DispatcherEvent},
+    io::{
+        read_file,
+        dxf,
+        gcode::{self, CutSide, GcodeSettings, Machine},
+        gltf,
+        mesh::{export_meshes, read_in_background, warnings, MeshFile, MeshOptions, NamedMesh},
+        obj,
+        raster::read_raster,
+        sketch::{free_points, project, SketchBuilder},
+        step::{self, StepSchema},
+        stl,
+        svg::{self, SvgUnit},
+        threemf, write_file,
+    },
+    project::{Project, ProjectState},
+    solids::mesh::{push_mesh, TriangleMesh},
+    ui::expression_edit::length_edit,
+};
+
+use super::command::CommandFunction;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Svg,
+    Dxf,
+    Gcode,
+    StlBinary,
+    StlAscii,
+    Obj,
+    ThreeMf,
+    Gltf,
+    Glb,
+    Step(StepSchema),
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 11] = [
+        ExportFormat::Svg,
+        ExportFormat::Dxf,
+        ExportFormat::Gcode,
+        ExportFormat::StlBinary,
+        ExportFormat::StlAscii,
+        ExportFormat::Obj,
+        ExportFormat::ThreeMf,
+        ExportFormat::Gltf,
+        ExportFormat::Glb,
+        ExportFormat::Step(StepSchema::Ap214),
+        ExportFormat::Step(StepSchema::Ap242),
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportFormat::Svg => "SVG drawing of the sketch plane",
+            ExportFormat::Dxf => "DXF drawing of the sketch plane",
+            ExportFormat::Gcode => "G-code of the sketch plane",
+            ExportFormat::StlBinary => "Binary STL mesh",
+            ExportFormat::StlAscii => "ASCII STL mesh",
+            ExportFormat::Obj => "Wavefront OBJ mesh",
+            ExportFormat::ThreeMf => "3MF mesh",
+            ExportFormat::Gltf => "glTF scene",
+            ExportFormat::Glb => "Binary glTF scene",
+            ExportFormat::Step(StepSchema::Ap214) => "STEP AP214",
+            ExportFormat::Step(StepSchema::Ap242) => "STEP AP242",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Svg => "svg",
+            ExportFormat::Dxf => "dxf",
+            ExportFormat::Gcode => "nc",
+            ExportFormat::StlBinary | ExportFormat::StlAscii => "stl",
+            ExportFormat::Obj => "obj",
+            ExportFormat::ThreeMf => "3mf",
+            ExportFormat::Gltf => "gltf",
+            ExportFormat::Glb => "glb",
+            ExportFormat::Step(_) => "step",
+        }
+    }
+
+    pub fn is_mesh(&self) -> bool {
+        !matches!(
+            self,
+            ExportFormat::Svg
+                | ExportFormat::Dxf
+                | ExportFormat::Gcode
+                | ExportFormat::Gltf
+                | ExportFormat::Glb
+                | ExportFormat::Step(_)
+        )
+    }
+}
+
+fn export(state: &ProjectState, settings: &ExportSettings, meshes: &[NamedMesh]) -> Result<Vec<u8>> {
+    let tolerance = settings.tolerance;
+    let plane = &state.camera.plane;
+    match settings.format {
+        ExportFormat::Svg => {
+            let shapes = project(&state.components, plane, tolerance);
+            let svg = svg::export(&shapes, &state.components.styles.array, state.units.unit)?;
+            return Ok(svg.into_bytes());
+        }
+        ExportFormat::Dxf => {
+            let shapes = project(&state.components, plane, tolerance);
+            let dxf = dxf::export(&shapes, &free_points(&state.components, plane))?;
+            return Ok(dxf.into_bytes());
+        }
+        ExportFormat::Gcode => {
+            let shapes = project(&state.components, plane, tolerance);
+            let gcode = gcode::export(&shapes, &settings.gcode, tolerance)?;
+            return Ok(gcode.into_bytes());
+        }
+        ExportFormat::Gltf => {
+            let scene = gltf::export(&state.components, &state.camera, &state.theme, tolerance)?;
+            return Ok(gltf::text(&scene).into_bytes());
+        }
+        ExportFormat::Glb => {
+            let scene = gltf::export(&state.components, &state.camera, &state.theme, tolerance)?;
+            return Ok(gltf::binary(&scene));
+        }
+        ExportFormat::Step(schema) => {
+            let step = step::export(&state.components, schema, state.units.unit, tolerance)?;
+            return Ok(step.into_bytes());
+        }
+        _ if meshes.is_empty() => {
+            return Err(Error::Geometry("there are no visible solids to export".into()));
+        }
+        ExportFormat::StlBinary => return Ok(stl::binary(meshes)),
+        ExportFormat::StlAscii => return Ok(stl::ascii(meshes).into_bytes()),
+        ExportFormat::Obj => return Ok(obj::export(meshes).into_bytes()),
+        ExportFormat::ThreeMf => return Ok(threemf::export(meshes, state.units.unit)),
+    }
+}
+
+pub struct Export {
+    pub window: Mutex<Option<Uuid>>,
+}
+
+struct ExportSettings {
+    format: ExportFormat,
+    path: String,
+    tolerance: f32,
+    tubes: bool,
+    tube_radius: f32,
+    gcode: GcodeSettings,
+    // problems of the meshes found before writing, they are written anyway on the next click
+    warnings: Vec<String>,
+    result: Option<Result<String>>,
+}
+
+impl ExportSettings {
+    fn write(&mut self, state: &ProjectState, check: bool) {
+        let meshes = match self.format.is_mesh() {
+            true => export_meshes(
+                &state.components,
+                MeshOptions {
+                    tolerance: self.tolerance,
+                    tubes: self.tubes.then_some(self.tube_radius),
+                },
+            ),
+            false => vec![],
+        };
+        if check {
+            self.warnings = warnings(&meshes);
+            if !self.warnings.is_empty() {
+                self.result = None;
+                return;
+            }
+        }
+        self.warnings.clear();
+        let path = self.path.clone();
+        self.result = Some(
+            export(state, self, &meshes)
+                .and_then(|data| write_file(&path, &data))
+                .map(|_| format!("written to {}", path)),
+        );
+    }
+}
+
+fn drag(ui: &mut Ui, value: &mut f32, label: &str, suffix: &str) {
+    ui.add(
+        egui::DragValue::new(value)
+            .speed(0.1)
+            .clamp_range(0.0..=100000.0)
+            .prefix(label)
+            .suffix(suffix),
+    );
+}
+
+fn gcode_settings(ui: &mut Ui, settings: &mut GcodeSettings) {
+    let machine = settings.machine;
+    egui::ComboBox::from_label("Machine")
+        .selected_text(settings.machine.name())
+        .show_ui(ui, |ui| {
+            for machine in Machine::ALL {
+                ui.selectable_value(&mut settings.machine, machine, machine.name());
+            }
+        });
+    if machine != settings.machine {
+        let (on, off) = settings.machine.commands();
+        settings.tool_on = on.into();
+        settings.tool_off = off.into();
+    }
+    drag(ui, &mut settings.feed, "Feed ", " mm/min");
+    match settings.machine {
+        Machine::Router => {
+            drag(ui, &mut settings.plunge_feed, "Plunge feed ", " mm/min");
+            drag(ui, &mut settings.speed, "Spindle speed ", " rpm");
+        }
+        Machine::Laser => drag(ui, &mut settings.speed, "Power S", ""),
+        Machine::Pen => {}
+    }
+    if settings.machine != Machine::Pen {
+        egui::ComboBox::from_label("Closed profiles")
+            .selected_text(settings.side.name())
+            .show_ui(ui, |ui| {
+                for side in CutSide::ALL {
+                    ui.selectable_value(&mut settings.side, side, side.name());
+                }
+            });
+        let label = match settings.machine {
+            Machine::Laser => "Kerf ",
+            _ => "Tool diameter ",
+        };
+        drag(ui, &mut settings.tool_diameter, label, " mm");
+    }
+    match settings.machine {
+        Machine::Router => {
+            drag(ui, &mut settings.safe_height, "Safe height ", " mm");
+            drag(ui, &mut settings.depth, "Depth ", " mm");
+            drag(ui, &mut settings.step_down, "Step down ", " mm");
+        }
+        Machine::Laser | Machine::Pen => {
+            let (on, off) = match settings.machine {
+                Machine::Laser => ("Laser on", "Laser off"),
+                _ => ("Pen down", "Pen up"),
+            };
+            ui.horizontal(|ui| {
+                ui.label(on);
+                ui.text_edit_singleline(&mut settings.tool_on);
+            });
+            ui.horizontal(|ui| {
+                ui.label(off);
+                ui.text_edit_singleline(&mut settings.tool_off);
+            });
+            ui.add(
+                egui::DragValue::new(&mut settings.passes)
+                    .clamp_range(1..=100)
+                    .prefix("Passes "),
+            );
+        }
+    }
+}
+
+impl CommandFunction for Export {
+    fn start(&self, sender: Sender<DispatcherEvent>, project: &mut Project) {
+        let mut window_id = self.window.lock().unwrap();
+        if let Some(id) = window_id.take() {
+            remove(sender, id);
+            return;
+        }
+
+        let settings = Mutex::new(ExportSettings {
+            format: ExportFormat::Svg,
+            path: "sketch.svg".into(),
+            tolerance: project.state.tessellation.tolerance,
+            tubes: false,
+            tube_radius: 0.5,
+            gcode: GcodeSettings::default(),
+            warnings: vec![],
+            result: None,
+        });
+        *window_id = Some(window(sender, "Export", move |state, ui| {
+            let mut settings = settings.lock().unwrap();
+            let format = settings.format;
+            egui::ComboBox::from_label("Format")
+                .selected_text(settings.format.name())
+                .show_ui(ui, |ui| {
+                    for format in ExportFormat::ALL {
+                        ui.selectable_value(&mut settings.format, format, format.name());
+                    }
+                });
+            // keep the file name, only its extension follows the format
+            if format != settings.format {
+                let stem = settings.path.rsplit_once('.').map_or(settings.path.clone(), |(s, _)| s.into());
+                settings.path = format!("{}.{}", stem, settings.format.extension());
+                settings.warnings.clear();
+            }
+            ui.horizontal(|ui| {
+                ui.label("File");
+                ui.text_edit_singleline(&mut settings.path);
+            });
+            // every format tessellates something, solids and tubes or spirals and rational nurbs
+            ui.add(
+                egui::DragValue::new(&mut settings.tolerance)
+                    .speed(0.001)
+                    .clamp_range(0.0001..=10.0)
+                    .prefix("Tolerance "),
+            );
+            if settings.format.is_mesh() {
+                ui.checkbox(&mut settings.tubes, "Curves as tubes");
+                ui.add_enabled_ui(settings.tubes, |ui| {
+                    ui.add(
+                        egui::DragValue::new(&mut settings.tube_radius)
+                            .speed(0.01)
+                            .clamp_range(0.001..=1000.0)
+                            .prefix("Tube radius "),
+                    );
+                });
+            }
+            if settings.format == ExportFormat::Gcode {
+                gcode_settings(ui, &mut settings.gcode);
+            }
+
+            if !settings.warnings.is_empty() {
+                for warning in settings.warnings.iter() {
+                    ui.colored_label(Color32::YELLOW, warning);
+                }
+                if ui.button("Export anyway").clicked() {
+                    settings.write(state, false);
+                }
+            } else if ui.button("Export").clicked() {
+                settings.write(state, true);
+            }
+            match &settings.result {
+                Some(Ok(message)) => {
+                    ui.label(message);
+                }
+                Some(Err(error)) => {
+                    ui.colored_label(Color32::RED, error.to_string());
+                }
+                None => {}
+            }
+        }));
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ImportFormat {
+    Svg,
+    Dxf,
+    Mesh(MeshFile),
+}
+
+impl ImportFormat {
+    pub const ALL: [ImportFormat; 4] = [
+        ImportFormat::Svg,
+        ImportFormat::Dxf,
+        ImportFormat::Mesh(MeshFile::Stl),
+        ImportFormat::Mesh(MeshFile::Obj),
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ImportFormat::Svg => "SVG outlines onto the sketch plane",
+            ImportFormat::Dxf => "DXF drawing onto the sketch plane",
+            ImportFormat::Mesh(MeshFile::Stl) => "STL reference mesh",
+            ImportFormat::Mesh(MeshFile::Obj) => "OBJ reference mesh",
+        }
+    }
+}
+
+pub struct Import {
+    pub window: Mutex<Option<Uuid>>,
+}
+
+struct ImportSettings {
+    format: ImportFormat,
+    path: String,
+    svg_unit: SvgUnit,
+    scale: f32,
+    // comma separated layers that are left out
+    skipped_layers: String,
+    mesh_unit: Unit,
+    wireframe: bool,
+    // a mesh that is still read
+    loading: Option<Receiver<Result<TriangleMesh>>>,
+    result: Option<Result<String>>,
+}
+
+fn import(state: &mut ProjectState, settings: &mut ImportSettings) -> Result<String> {
+    let plane = state.camera.plane.clone();
+    match settings.format {
+        ImportFormat::Svg => {
+            let data = read_file(&settings.path)?;
+            let mut builder = SketchBuilder::new(&mut state.components, plane);
+            svg::import(&String::from_utf8_lossy(&data), &mut builder, settings.svg_unit, settings.scale)?;
+            return Ok(format!("imported {} curve(s)", builder.count));
+        }
+        ImportFormat::Dxf => {
+            let data = read_file(&settings.path)?;
+            let skipped: Vec<String> = settings
+                .skipped_layers
+                .split(',')
+                .map(|l| l.trim().to_string())
+                .filter(|l| !l.is_empty())
+                .collect();
+            let mut builder = SketchBuilder::new(&mut state.components, plane);
+            let layers = dxf::import(&String::from_utf8_lossy(&data), &mut builder, &skipped)?;
+            return Ok(format!("imported {} curve(s), layers {}", builder.count, layers.join(", ")));
+        }
+        ImportFormat::Mesh(file) => {
+            let scale = settings.mesh_unit.to_mm() as f32;
+            settings.loading = Some(read_in_background(settings.path.clone(), file, scale));
+            return Ok(format!("reading {}", settings.path));
+        }
+    }
+}
+
+// reference meshes are construction geometry, they are left out of exports
+fn add_reference(state: &mut ProjectState, mesh: &TriangleMesh, wireframe: bool) -> Result<String> {
+    let index = push_mesh(&mut state.components, mesh)?;
+    state.components.meshes.update(index, |m| {
+        m.set_construction(true);
+        m.data.wireframe = wireframe as u32;
+    });
+    return Ok(format!("imported {} triangle(s)", mesh.triangle_count()));
+}
+
+impl CommandFunction for Import {
+    fn start(&self, sender: Sender<DispatcherEvent>, _: &mut Project) {
+        let mut window_id = self.window.lock().unwrap();
+        if let Some(id) = window_id.take() {
+            remove(sender, id);
+            return;
+        }
+
+        let settings = Mutex::new(ImportSettings {
+            format: ImportFormat::Svg,
+            path: String::new(),
+            svg_unit: SvgUnit::Document,
+            scale: 1.,
+            skipped_layers: String::new(),
+            mesh_unit: Unit::Millimeter,
+            wireframe: false,
+            loading: None,
+            result: None,
+        });
+        *window_id = Some(window(sender, "Import", move |state, ui| {
+            let mut settings = settings.lock().unwrap();
+            egui::ComboBox::from_label("Format")
+                .selected_text(settings.format.name())
+                .show_ui(ui, |ui| {
+                    for format in ImportFormat::ALL {
+                        ui.selectable_value(&mut settings.format, format, format.name());
+                    }
+                });
+            ui.horizontal(|ui| {
+                ui.label("File");
+                ui.text_edit_singleline(&mut settings.path);
+            });
+            match settings.format {
+                ImportFormat::Svg => {
+                    egui::ComboBox::from_label("Unit")
+                        .selected_text(settings.svg_unit.name())
+                        .show_ui(ui, |ui| {
+                            for unit in SvgUnit::ALL {
+                                ui.selectable_value(&mut settings.svg_unit, unit, unit.name());
+                            }
+                        });
+                    ui.add(
+                        egui::DragValue::new(&mut settings.scale)
+                            .speed(0.01)
+                            .clamp_range(0.001..=1000.0)
+                            .prefix("Scale "),
+                    );
+                }
+                ImportFormat::Dxf => {
+                    ui.horizontal(|ui| {
+                        ui.label("Skip layers");
+                        ui.text_edit_singleline(&mut settings.skipped_layers);
+                    });
+                }
+                ImportFormat::Mesh(_) => {
+                    egui::ComboBox::from_label("Unit")
+                        .selected_text(settings.mesh_unit.name())
+                        .show_ui(ui, |ui| {
+                            for unit in Unit::ALL {
+                                ui.selectable_value(&mut settings.mesh_unit, unit, unit.name());
+                            }
+                        });
+                    ui.checkbox(&mut settings.wireframe, "Wireframe");
+                }
+            }
+
+            if let Some(receiver) = &settings.loading {
+                match receiver.try_recv() {
+                    Ok(mesh) => {
+                        let wireframe = settings.wireframe;
+                        settings.result = Some(mesh.and_then(|mesh| add_reference(state, &mesh, wireframe)));
+                        settings.loading = None;
+                    }
+                    Err(TryRecvError::Empty) => {
+                        ui.spinner();
+                        ui.ctx().request_repaint();
+                    }
+                    Err(TryRecvError::Closed) => settings.loading = None,
+                }
+            } else if ui.button("Import").clicked() {
+                let result = import(state, &mut settings);
+                settings.result = Some(result);
+            }
+            match &settings.result {
+                Some(Ok(message)) => {
+                    ui.label(message);
+                }
+                Some(Err(error)) => {
+                    ui.colored_label(Color32::RED, error.to_string());
+                }
+                None => {}
+            }
+        }));
+    }
+}
+
+// places pictures on the sketch plane to trace over
+pub struct ReferenceImage {
+    pub window: Mutex<Option<Uuid>>,
+}
+
+struct ImageSettings {
+    path: String,
+    selected: Option<usize>,
+    // two clicks on the picture and the distance they are apart in reality
+    measured: Arc<Mutex<Option<(Vec3, Vec3)>>>,
+    known: f32,
+    result: Option<Result<String>>,
+}
+
+// a new picture is centered below the camera target, its longer side 100 mm
+fn add_image(state: &mut ProjectState, path: &str) -> Result<usize> {
+    let raster = read_raster(path)?;
+    let plane = &state.camera.plane;
+    let normal = plane.orientation.normalize();
+    let target = state.camera.target;
+    let center = target - (target - plane.position).dot(normal) * normal;
+    let scale = 100. / raster.width.max(raster.height) as f32;
+    let components = &mut state.components;
+    let first = components.image_pixels.append(&raster.pixels)?;
+    return Ok(components
+        .images
+        .push(image::new(center, normal, raster.width, raster.height, first, scale)));
+}
+
+// scales around the first click so the measured distance becomes the known one
+fn calibrate(image: &mut Image, (a, b): (Vec3, Vec3), known: f32) -> Result<()> {
+    let measured = a.distance(b);
+    if measured < 1e-6 || known <= 0. {
+        return Err(Error::Geometry("the distance has to be longer than zero".into()));
+    }
+    let factor = known / measured;
+    image.position = a + (image.position - a) * factor;
+    image.scale *= factor;
+    return Ok(());
+}
+
+fn show_image(ui: &mut Ui, state: &mut ProjectState, index: usize) {
+    let units = state.units.clone();
+    let plane = state.camera.plane.clone();
+    let component = &state.components.images.array[index];
+    let mut image = component.data.clone();
+    let mut visible = component.flags & ComponentFlags::Visible as i32 != 0;
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label("x");
+        changed |= length_edit(ui, ui.id().with("image_x"), &units, &mut image.position.x);
+        ui.label("y");
+        changed |= length_edit(ui, ui.id().with("image_y"), &units, &mut image.position.y);
+        ui.label("z");
+        changed |= length_edit(ui, ui.id().with("image_z"), &units, &mut image.position.z);
+    });
+    let mut degrees = image.rotation.to_degrees();
+    if ui
+        .add(egui::DragValue::new(&mut degrees).speed(0.5).suffix("°").prefix("Rotation "))
+        .changed()
+    {
+        image.rotation = degrees.to_radians();
+        changed = true;
+    }
+    let (mut width, height) = image.size();
+    ui.horizontal(|ui| {
+        ui.label("Width");
+        if length_edit(ui, ui.id().with("image_width"), &units, &mut width) && width > 0. {
+            image.scale = width / image.width as f32;
+            changed = true;
+        }
+        ui.label(format!("height {}", units.format(height)));
+    });
+    changed |= ui
+        .add(egui::Slider::new(&mut image.opacity, 0.0..=1.0).text("Opacity"))
+        .changed();
+    ui.horizontal(|ui| {
+        let toggled = ui.checkbox(&mut visible, "Visible").changed();
+        changed |= toggled;
+        if ui.button("Onto sketch plane").clicked() {
+            let normal = plane.orientation.normalize();
+            image.position -= (image.position - plane.position).dot(normal) * normal;
+            image.normal = normal;
+            changed = true;
+        }
+    });
+    if changed {
+        state.components.images.update(index, |c| {
+            c.data = image;
+            if visible {
+                c.visible();
+            } else {
+                c.invisible();
+            }
+        });
+    }
+}
+
+impl CommandFunction for ReferenceImage {
+    fn start(&self, sender: Sender<DispatcherEvent>, _: &mut Project) {
+        let mut window_id = self.window.lock().unwrap();
+        if let Some(id) = window_id.take() {
+            remove(sender, id);
+            return;
+        }
+
+        let settings = Mutex::new(ImageSettings {
+            path: String::new(),
+            selected: None,
+            measured: Arc::new(Mutex::new(None)),
+            known: 10.,
+            result: None,
+        });
+        let tool_sender = sender.clone();
+        *window_id = Some(window(sender, "Reference Image", move |state, ui| {
+            let mut settings = settings.lock().unwrap();
+            ui.horizontal(|ui| {
+                ui.label("File");
+                ui.text_edit_singleline(&mut settings.path);
+            });
+            if ui.button("Load").clicked() {
+                let result = add_image(state, &settings.path);
+                settings.selected = result.as_ref().ok().copied().or(settings.selected);
+                settings.result = Some(result.map(|_| format!("loaded {}", settings.path)));
+            }
+            match &settings.result {
+                Some(Ok(message)) => {
+                    ui.label(message);
+                }
+                Some(Err(error)) => {
+                    ui.colored_label(Color32::RED, error.to_string());
+                }
+                None => {}
+            }
+
+            let images: Vec<usize> = (0..state.components.images.array.len())
+                .filter(|i| !state.components.images.array[*i].is_deleted())
+                .collect();
+            if settings.selected.map_or(true, |i| !images.contains(&i)) {
+                settings.selected = images.last().copied();
+            }
+            let Some(index) = settings.selected else {
+                return;
+            };
+            ui.separator();
+            egui::ComboBox::from_label("Image")
+                .selected_text(format!("Image {}", index + 1))
+                .show_ui(ui, |ui| {
+                    for i in images {
+                        ui.selectable_value(&mut settings.selected, Some(i), format!("Image {}", i + 1));
+                    }
+                });
+            show_image(ui, state, index);
+
+            ui.separator();
+            let measured = *settings.measured.lock().unwrap();
+            ui.horizontal(|ui| {
+                if ui.button("Measure").clicked() {
+                    let measured = settings.measured.clone();
+                    tool(tool_sender.clone(), Some(2), move |_, positions| {
+                        if let [a, b] = positions {
+                            *measured.lock().unwrap() = Some((*a, *b));
+                        }
+                    });
+                }
+                match measured {
+                    Some((a, b)) => ui.label(format!("measured {}", state.units.format(a.distance(b)))),
+                    None => ui.label("click two points of the picture"),
+                };
+            });
+            let units = state.units.clone();
+            ui.horizontal(|ui| {
+                ui.label("Known distance");
+                length_edit(ui, ui.id().with("known"), &units, &mut settings.known);
+            });
+            if ui.add_enabled(measured.is_some(), egui::Button::new("Calibrate")).clicked() {
+                let known = settings.known;
+                let result = state
+                    .components
+                    .images
+                    .update(index, |c| calibrate(&mut c.data, measured.unwrap(), known))
+                    .unwrap_or(Err(Error::NotFound));
+                settings.result = Some(result.map(|_| "calibrated".into()));
+                *settings.measured.lock().unwrap() = None;
+                stop_tool(tool_sender.clone());
+            }
+            // only images that aren't deleted are selected, their pixels are released once
+            if ui.button("Remove").clicked() {
+                let image = &state.components.images.array[index].data;
+                let (first, count) = (image.first as usize, (image.width * image.height) as usize);
+                state.components.image_pixels.release(first, count);
+                state.components.images.update(index, |c| c.flags |= ComponentFlags::Deleted as i32);
+            }
+        }));
+    }
+}
@@ -0,0 +1,38 @@
+use std::sync::Mutex;
+
+use crate::{
+    dispatchers::dispatcher::{remove, window, DispatcherEvent},
+    project::Project,
+};
+use async_std::channel::Sender;
+use uuid::Uuid;
+
+use super::command::CommandFunction;
+
+pub struct GridSettings {
+    pub window: Mutex<Option<Uuid>>,
+}
+
+impl CommandFunction for GridSettings {
+    fn start(&self, sender: Sender<DispatcherEvent>, _: &mut Project) {
+        let mut window_id = self.window.lock().unwrap();
+        if let Some(id) = window_id.take() {
+            remove(sender, id);
+            return;
+        }
+
+        *window_id = Some(window(sender, "Grid", |state, ui| {
+            let grid = &mut state.grid;
+            ui.checkbox(&mut grid.visible, "Visible");
+            ui.checkbox(&mut grid.snap, "Snap to grid");
+            ui.checkbox(&mut grid.fade, "Fade toward horizon");
+            ui.add(egui::Slider::new(&mut grid.subdivisions, 1..=20).text("Subdivisions"));
+            ui.add(egui::Slider::new(&mut grid.lines_per_view, 1.0..=20.0).text("Density"));
+            ui.label(format!(
+                "major {} / minor {}",
+                state.units.format(grid.major),
+                state.units.format(grid.minor)
+            ));
+        }));
+    }
+}
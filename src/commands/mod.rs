@@ -1,2 +1,8 @@
 pub mod command;
-pub mod view_commands;
\ No newline at end of file
+pub mod draw_commands;
+pub mod view_commands;
+pub mod grid_commands;
+pub mod settings_commands;
+pub mod parameter_commands;
+pub mod solid_commands;
+pub mod file_commands;
\ No newline at end of file
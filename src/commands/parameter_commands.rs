@@ -0,0 +1,89 @@
+use std::sync::Mutex;
+
+use crate::{
+    core::{expression::Expression, result::Result},
+    dispatchers::dispatcher::{remove, window, DispatcherEvent},
+    project::Project,
+    ui::expression_edit::expression_edit,
+};
+use async_std::channel::Sender;
+use egui::Color32;
+use uuid::Uuid;
+
+use super::command::CommandFunction;
+
+pub struct ParameterTable {
+    pub window: Mutex<Option<Uuid>>,
+}
+
+impl CommandFunction for ParameterTable {
+    fn start(&self, sender: Sender<DispatcherEvent>, _: &mut Project) {
+        let mut window_id = self.window.lock().unwrap();
+        if let Some(id) = window_id.take() {
+            remove(sender, id);
+            return;
+        }
+
+        *window_id = Some(window(sender, "Parameters", |state, ui| {
+            let units = state.units.clone();
+            let parameters = &mut state.parameters;
+            let mut to_remove = None;
+
+            egui::Grid::new("parameters").striped(true).show(ui, |ui| {
+                ui.label("Name");
+                ui.label("Expression");
+                ui.label("Value");
+                ui.end_row();
+
+                for i in 0..parameters.parameters.len() {
+                    let parameter = &parameters.parameters[i];
+                    let name = parameter.name.clone();
+                    let expression = parameter.expression.clone();
+
+                    if let Some((name, _)) = expression_edit(
+                        ui,
+                        ui.id().with(("name", i)),
+                        name.clone(),
+                        |text| -> Result<()> { parameters.check_rename(i, text.trim()) },
+                    ) {
+                        parameters.rename(i, name.trim()).expect("the name was checked by the edit");
+                    }
+                    if let Some((expression, _)) = expression_edit(
+                        ui,
+                        ui.id().with(("expression", i)),
+                        expression,
+                        |text| -> Result<()> { Expression::parse(text).map(|_| ()) },
+                    ) {
+                        let _ = parameters.set(&name, &expression);
+                    }
+                    match &parameters.parameters[i].value {
+                        Ok(value) if value.length == 1 => {
+                            ui.label(units.format(value.value as f32));
+                        }
+                        Ok(value) => {
+                            ui.label(format!("{:.*}", units.precision, value.value));
+                        }
+                        Err(error) => {
+                            ui.colored_label(Color32::RED, error.to_string());
+                        }
+                    }
+                    if ui.button("x").clicked() {
+                        to_remove = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+
+            if let Some(i) = to_remove {
+                parameters.remove(i);
+            }
+            if ui.button("+").clicked() {
+                let mut n = parameters.parameters.len() + 1;
+                while parameters.parameters.iter().any(|p| p.name == format!("p{}", n)) {
+                    n += 1;
+                }
+                let _ = parameters.set(&format!("p{}", n), "0");
+            }
+        }));
+    }
+}
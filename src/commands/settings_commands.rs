@@ -0,0 +1,80 @@
+use std::sync::Mutex;
+
+use crate::{
+    core::units::Unit,
+    dispatchers::dispatcher::{remove, window, DispatcherEvent},
+    project::Project,
+    rendering::theme::Theme,
+    ui::expression_edit::length_edit,
+};
+use async_std::channel::Sender;
+use uuid::Uuid;
+
+use super::command::CommandFunction;
+
+pub struct ProjectSettings {
+    pub window: Mutex<Option<Uuid>>,
+}
+
+impl CommandFunction for ProjectSettings {
+    fn start(&self, sender: Sender<DispatcherEvent>, _: &mut Project) {
+        let mut window_id = self.window.lock().unwrap();
+        if let Some(id) = window_id.take() {
+            remove(sender, id);
+            return;
+        }
+
+        *window_id = Some(window(sender, "Project Settings", |state, ui| {
+            let units = &mut state.units;
+            let unit = units.unit;
+            egui::ComboBox::from_label("Unit")
+                .selected_text(units.unit.name())
+                .show_ui(ui, |ui| {
+                    for unit in Unit::ALL.iter() {
+                        ui.selectable_value(&mut units.unit, *unit, unit.name());
+                    }
+                });
+            ui.add(egui::Slider::new(&mut units.precision, 0..=6).text("Precision"));
+
+            ui.horizontal(|ui| {
+                ui.label("Curve tolerance");
+                let tessellation = &mut state.tessellation;
+                let mut tolerance = tessellation.tolerance;
+                if length_edit(ui, egui::Id::new("curve_tolerance"), units, &mut tolerance) && tolerance > 0. {
+                    tessellation.tolerance = tolerance;
+                }
+            });
+
+            ui.separator();
+            ui.label("Lines");
+            let style = &mut state.line_style;
+            ui.add(egui::Slider::new(&mut style.width, 0.5..=8.0).text("Width (px)"));
+            ui.checkbox(&mut style.hidden, "Dashed hidden lines");
+            ui.add(egui::Slider::new(&mut style.dash, 1.0..=30.0).text("Dash (px)"));
+            ui.add(egui::Slider::new(&mut style.gap, 1.0..=30.0).text("Gap (px)"));
+
+            ui.separator();
+            ui.label("Theme");
+            let theme = &mut state.theme;
+            egui::Grid::new("theme").show(ui, |ui| {
+                ui.label("Normal");
+                ui.color_edit_button_rgba_unmultiplied(&mut theme.normal);
+                ui.end_row();
+                ui.label("Hover");
+                ui.color_edit_button_rgba_unmultiplied(&mut theme.hover);
+                ui.end_row();
+                ui.label("Selected");
+                ui.color_edit_button_rgba_unmultiplied(&mut theme.selected);
+                ui.end_row();
+            });
+            if ui.button("Default theme").clicked() {
+                *theme = Theme::default();
+            }
+
+            // plain numbers in parameters are in the project unit
+            if unit != units.unit {
+                state.parameters.dirty = true;
+            }
+        }));
+    }
+}
@@ -0,0 +1,307 @@
+use std::sync::Mutex;
+
+use crate::{
+    components::component::{ComponentIdentifier, ComponentType, IComponent},
+    core::result::{Error, Result},
+    dispatchers::dispatcher::{remove, window, DispatcherEvent},
+    project::{Project, ProjectState},
+    solids::{
+        extrude::extrude,
+        loft::{loft, LoftMode},
+        mesh::{push_mesh, TriangleMesh},
+        revolve::{revolve_path, revolve_profile, Axis},
+        sweep::sweep,
+    },
+    topology::{
+        chain::{chain, chain_points},
+        profile::Profile,
+    },
+    ui::expression_edit::length_edit,
+};
+use async_std::channel::Sender;
+use egui::{Color32, Id};
+use uuid::Uuid;
+
+use super::command::CommandFunction;
+
+pub struct Extrude {
+    pub window: Mutex<Option<Uuid>>,
+}
+
+struct ExtrudeSettings {
+    front: f32,
+    back: f32,
+    error: Option<String>,
+}
+
+// the profiles of the selected filled faces
+fn selected_profiles(state: &ProjectState) -> Vec<Profile> {
+    state
+        .components
+        .selected
+        .iter()
+        .filter(|s| s.ctype == ComponentType::Face)
+        .filter_map(|s| state.fills.profiles.get(s.index as usize).cloned())
+        .collect()
+}
+
+// selected curves that are not faces, meshes, construction geometry or the excluded line
+fn selected_curves(state: &ProjectState, exclude: Option<u32>) -> Vec<ComponentIdentifier> {
+    let components = &state.components;
+    components
+        .selected
+        .iter()
+        .filter(|s| !components.get_c(s.ctype, s.index as usize, |c| c.map_or(false, |c| c.is_construction())))
+        .filter(|s| match s.ctype {
+            ComponentType::Line => Some(s.index) != exclude,
+            ComponentType::Bezier
+            | ComponentType::Circle
+            | ComponentType::Arc
+            | ComponentType::Ellipse
+            | ComponentType::Nurbs => true,
+            _ => false,
+        })
+        .cloned()
+        .collect()
+}
+
+fn apply(
+    state: &mut ProjectState,
+    profiles: &[Profile],
+    func: impl Fn(&Profile) -> Result<TriangleMesh>,
+) -> Result<()> {
+    if profiles.is_empty() {
+        return Err(Error::Geometry("select a closed profile first".into()));
+    }
+    let meshes = profiles.iter().map(func).collect::<Result<Vec<_>>>()?;
+    for mesh in meshes.iter() {
+        push_mesh(&mut state.components, mesh)?;
+    }
+    return Ok(());
+}
+
+impl CommandFunction for Extrude {
+    fn start(&self, sender: Sender<DispatcherEvent>, _: &mut Project) {
+        let mut window_id = self.window.lock().unwrap();
+        if let Some(id) = window_id.take() {
+            remove(sender, id);
+            return;
+        }
+
+        let settings = Mutex::new(ExtrudeSettings {
+            front: 10.,
+            back: 0.,
+            error: None,
+        });
+        *window_id = Some(window(sender, "Extrude", move |state, ui| {
+            let mut settings = settings.lock().unwrap();
+            let units = state.units.clone();
+            let profiles = selected_profiles(state);
+            ui.label(format!("{} profile(s) selected", profiles.len()));
+
+            egui::Grid::new("extrude").show(ui, |ui| {
+                ui.label("Distance");
+                length_edit(ui, Id::new("extrude_front"), &units, &mut settings.front);
+                ui.end_row();
+                ui.label("Opposite side");
+                length_edit(ui, Id::new("extrude_back"), &units, &mut settings.back);
+                ui.end_row();
+            });
+
+            if ui.button("Extrude").clicked() {
+                let (front, back) = (settings.front, settings.back);
+                settings.error = apply(state, &profiles, |p| extrude(p, front, back))
+                    .err()
+                    .map(|e| e.to_string());
+            }
+            if let Some(error) = &settings.error {
+                ui.colored_label(Color32::RED, error);
+            }
+        }));
+    }
+}
+
+pub struct Revolve {
+    pub window: Mutex<Option<Uuid>>,
+}
+
+struct RevolveSettings {
+    axis: Option<u32>,
+    angle: f32,
+    error: Option<String>,
+}
+
+fn revolve(state: &mut ProjectState, settings: &RevolveSettings) -> Result<()> {
+    let Some(line) = settings.axis else {
+        return Err(Error::Geometry("pick a line as axis first".into()));
+    };
+    let components = &state.components;
+    let gone = || Error::Geometry("the axis line no longer exists".into());
+    let line = components.lines.array.get(line as usize).filter(|l| !l.is_deleted()).ok_or_else(gone)?;
+    let a = components.points.array.get(line.data.point_a as usize).ok_or_else(gone)?.get_center(components);
+    let b = components.points.array.get(line.data.point_b as usize).ok_or_else(gone)?.get_center(components);
+    let axis = Axis::new(a, b)?;
+    let angle = settings.angle.to_radians();
+    let tolerance = state.tessellation.tolerance;
+
+    let profiles = selected_profiles(state);
+    if !profiles.is_empty() {
+        return apply(state, &profiles, |p| revolve_profile(p, &axis, angle, tolerance));
+    }
+
+    // without a filled profile the selected curves are revolved into a surface
+    let edges = chain(&state.components, &selected_curves(state, settings.axis))?;
+    let points = chain_points(&state.components, &edges, tolerance);
+    let mesh = revolve_path(&points, &axis, angle, tolerance)?;
+    push_mesh(&mut state.components, &mesh)?;
+    return Ok(());
+}
+
+impl CommandFunction for Revolve {
+    fn start(&self, sender: Sender<DispatcherEvent>, _: &mut Project) {
+        let mut window_id = self.window.lock().unwrap();
+        if let Some(id) = window_id.take() {
+            remove(sender, id);
+            return;
+        }
+
+        let settings = Mutex::new(RevolveSettings {
+            axis: None,
+            angle: 360.,
+            error: None,
+        });
+        *window_id = Some(window(sender, "Revolve", move |state, ui| {
+            let mut settings = settings.lock().unwrap();
+            let lines: Vec<u32> = state
+                .components
+                .selected
+                .iter()
+                .filter(|s| s.ctype == ComponentType::Line)
+                .map(|s| s.index)
+                .collect();
+
+            ui.horizontal(|ui| {
+                match settings.axis {
+                    Some(line) => ui.label(format!("Axis: line {}", line)),
+                    None => ui.label("Axis: none"),
+                };
+                if ui
+                    .add_enabled(lines.len() == 1, egui::Button::new("Use selected line"))
+                    .clicked()
+                {
+                    settings.axis = Some(lines[0]);
+                }
+            });
+            ui.add(
+                egui::DragValue::new(&mut settings.angle)
+                    .clamp_range(-360.0..=360.0)
+                    .suffix("°"),
+            );
+
+            if ui.button("Revolve").clicked() {
+                settings.error = revolve(state, &settings).err().map(|e| e.to_string());
+            }
+            if let Some(error) = &settings.error {
+                ui.colored_label(Color32::RED, error);
+            }
+        }));
+    }
+}
+
+pub struct Sweep {
+    pub window: Mutex<Option<Uuid>>,
+}
+
+fn sweep_selected(state: &mut ProjectState) -> Result<()> {
+    let profiles = selected_profiles(state);
+    let edges = chain(&state.components, &selected_curves(state, None))?;
+    let points = chain_points(&state.components, &edges, state.tessellation.tolerance);
+    return apply(state, &profiles, |p| sweep(p, &points));
+}
+
+impl CommandFunction for Sweep {
+    fn start(&self, sender: Sender<DispatcherEvent>, _: &mut Project) {
+        let mut window_id = self.window.lock().unwrap();
+        if let Some(id) = window_id.take() {
+            remove(sender, id);
+            return;
+        }
+
+        let error: Mutex<Option<String>> = Mutex::new(None);
+        *window_id = Some(window(sender, "Sweep", move |state, ui| {
+            let mut error = error.lock().unwrap();
+            ui.label(format!("{} profile(s) selected", selected_profiles(state).len()));
+            ui.label(format!("{} path curve(s) selected", selected_curves(state, None).len()));
+
+            if ui.button("Sweep").clicked() {
+                *error = sweep_selected(state).err().map(|e| e.to_string());
+            }
+            if let Some(error) = &*error {
+                ui.colored_label(Color32::RED, error);
+            }
+        }));
+    }
+}
+
+pub struct Loft {
+    pub window: Mutex<Option<Uuid>>,
+}
+
+struct LoftSettings {
+    // fills only exist in the active plane, so the sections are collected one plane at a time
+    profiles: Vec<Profile>,
+    mode: LoftMode,
+    error: Option<String>,
+}
+
+impl CommandFunction for Loft {
+    fn start(&self, sender: Sender<DispatcherEvent>, _: &mut Project) {
+        let mut window_id = self.window.lock().unwrap();
+        if let Some(id) = window_id.take() {
+            remove(sender, id);
+            return;
+        }
+
+        let settings = Mutex::new(LoftSettings {
+            profiles: vec![],
+            mode: LoftMode::Ruled,
+            error: None,
+        });
+        *window_id = Some(window(sender, "Loft", move |state, ui| {
+            let mut settings = settings.lock().unwrap();
+            let selected = selected_profiles(state);
+
+            ui.horizontal(|ui| {
+                ui.label(format!("{} section(s)", settings.profiles.len()));
+                if ui
+                    .add_enabled(!selected.is_empty(), egui::Button::new("Add selected profile"))
+                    .clicked()
+                {
+                    settings.profiles.extend(selected);
+                }
+                if ui.button("Clear").clicked() {
+                    settings.profiles.clear();
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut settings.mode, LoftMode::Ruled, "Ruled");
+                ui.radio_value(&mut settings.mode, LoftMode::Smooth, "Smooth");
+            });
+
+            if ui.button("Loft").clicked() {
+                let pushed = loft(&settings.profiles, settings.mode)
+                    .and_then(|mesh| push_mesh(&mut state.components, &mesh));
+                settings.error = match pushed {
+                    Ok(_) => {
+                        settings.profiles.clear();
+                        None
+                    }
+                    Err(e) => Some(e.to_string()),
+                };
+            }
+            if let Some(error) = &settings.error {
+                ui.colored_label(Color32::RED, error);
+            }
+        }));
+    }
+}
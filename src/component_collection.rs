@@ -1,10 +1,10 @@
-use std::{array, sync::Arc};
+use std::{array, collections::HashMap, sync::Arc};
 
 use eframe::wgpu::{self, BufferUsages, Device, Queue};
 
-use crate::components::{
-    bezier::Bezier, circle::Circle, component::{Component, HoverElement, ComponentType, IComponent, IComponentData, ComponentIdentifier}, line::Line, point::Point, vertex::Vertex,
-};
+use crate::{core::result::Result, rendering::{curve_tessellation::CurveTessellation, index_buffer::IndexBuffer, pixel_buffer::PixelBuffer}, components::{
+    arc, image::Image, bezier::Bezier, circle::Circle, ellipse::Ellipse, nurbs::{Knot, Nurbs, NurbsControl}, face::{Face, FaceVertex}, mesh::{Mesh, MeshVertex}, style::Style, component::{Component, HoverElement, ComponentType, IComponent, IComponentData, ComponentIdentifier}, line::Line, point::Point, vertex::Vertex,
+}};
 
 pub struct ComponentCollection {
     pub hovers: Vec<HoverElement>,
@@ -19,9 +19,127 @@ pub struct ComponentCollection {
     pub lines: ComponentArray<Line>,
     pub beziers: ComponentArray<Bezier>,
     pub circles: ComponentArray<Circle>,
+    pub arcs: ComponentArray<arc::Arc>,
+    pub ellipses: ComponentArray<Ellipse>,
+    pub nurbs: ComponentArray<Nurbs>,
+    pub nurbs_controls: ComponentArray<NurbsControl>,
+    pub nurbs_knots: ComponentArray<Knot>,
+    pub faces: ComponentArray<Face>,
+    pub face_vertices: ComponentArray<FaceVertex>,
+    pub meshes: ComponentArray<Mesh>,
+    pub mesh_vertices: ComponentArray<MeshVertex>,
+    pub mesh_indices: IndexBuffer,
+    pub styles: ComponentArray<Style>,
+    // reference pictures, drawn behind everything and never hovered
+    pub images: ComponentArray<Image>,
+    pub image_pixels: PixelBuffer,
+    pub layers: Layers,
+
+    pub bezier_segments: CurveTessellation,
+    pub circle_segments: CurveTessellation,
+    pub arc_segments: CurveTessellation,
+    pub ellipse_segments: CurveTessellation,
+    pub nurbs_segments: CurveTessellation,
+}
+
+// drawing layers by name, components that were never assigned are on the first layer "0"
+pub struct Layers {
+    pub names: Vec<String>,
+    assigned: HashMap<(ComponentType, u32), usize>,
+}
+
+impl Default for Layers {
+    fn default() -> Self {
+        Layers {
+            names: vec!["0".into()],
+            assigned: HashMap::new(),
+        }
+    }
+}
+
+impl Layers {
+    // the layer with the name, added when there is none yet
+    pub fn index(&mut self, name: &str) -> usize {
+        if let Some(i) = self.names.iter().position(|n| n == name) {
+            return i;
+        }
+        self.names.push(name.to_string());
+        return self.names.len() - 1;
+    }
+
+    pub fn assign(&mut self, ctype: ComponentType, index: u32, layer: usize) {
+        if layer == 0 {
+            self.assigned.remove(&(ctype, index));
+        } else {
+            self.assigned.insert((ctype, index), layer);
+        }
+    }
+
+    pub fn name(&self, ctype: ComponentType, index: u32) -> &str {
+        let layer = self.assigned.get(&(ctype, index)).copied().unwrap_or(0);
+        return &self.names[layer];
+    }
 }
 
 impl ComponentCollection {
+    // grows the segment lists with the curves, every list is reserved even if one is refused
+    pub fn reserve_segments(&mut self) -> Result<()> {
+        let reserved = [
+            self.bezier_segments.reserve(self.beziers.array.len()),
+            self.circle_segments.reserve(self.circles.array.len()),
+            self.arc_segments.reserve(self.arcs.array.len()),
+            self.ellipse_segments.reserve(self.ellipses.array.len()),
+            self.nurbs_segments.reserve(self.nurbs.array.len()),
+        ];
+        return reserved.into_iter().collect();
+    }
+
+    // true if any buffer was recreated since the last call
+    pub fn take_resized(&mut self) -> bool {
+        let resized = [
+            &mut self.axises.resized,
+            &mut self.grids.resized,
+            &mut self.arrows.resized,
+            &mut self.arrow_planes.resized,
+            &mut self.points.resized,
+            &mut self.lines.resized,
+            &mut self.beziers.resized,
+            &mut self.circles.resized,
+            &mut self.arcs.resized,
+            &mut self.ellipses.resized,
+            &mut self.nurbs.resized,
+            &mut self.nurbs_controls.resized,
+            &mut self.nurbs_knots.resized,
+            &mut self.faces.resized,
+            &mut self.face_vertices.resized,
+            &mut self.meshes.resized,
+            &mut self.mesh_vertices.resized,
+            &mut self.mesh_indices.resized,
+            &mut self.styles.resized,
+            &mut self.images.resized,
+            &mut self.image_pixels.resized,
+            &mut self.bezier_segments.resized,
+            &mut self.circle_segments.resized,
+            &mut self.arc_segments.resized,
+            &mut self.ellipse_segments.resized,
+            &mut self.nurbs_segments.resized,
+        ];
+        let mut any = false;
+        for flag in resized {
+            any |= *flag;
+            *flag = false;
+        }
+        return any;
+    }
+
+    pub fn reset_segments(&self) {
+        self.bezier_segments.reset();
+        self.circle_segments.reset();
+        self.arc_segments.reset();
+        self.ellipse_segments.reset();
+        self.nurbs_segments.reset();
+    }
+
     pub fn get_most_hovered(&mut self) -> Option<HoverElement> {
         if let Some(x) = self.hovers.first() {
             let threshold = 0.01;
@@ -154,12 +272,27 @@ impl ComponentCollection {
             ComponentType::Bezier => {
                 (func)(&self.beziers)
             }
+            ComponentType::Arc => {
+                (func)(&self.arcs)
+            }
+            ComponentType::Ellipse => {
+                (func)(&self.ellipses)
+            }
+            ComponentType::Nurbs => {
+                (func)(&self.nurbs)
+            }
             ComponentType::Arrow => {
                 (func)(&self.arrows)
             }
             ComponentType::ArrowPlane => {
                 (func)(&self.arrow_planes)
             }
+            ComponentType::Face => {
+                (func)(&self.faces)
+            }
+            ComponentType::Mesh => {
+                (func)(&self.meshes)
+            }
         }
     }
     
@@ -194,12 +327,27 @@ impl ComponentCollection {
             ComponentType::Bezier => {
                 (func)(&mut self.beziers);
             }
+            ComponentType::Arc => {
+                (func)(&mut self.arcs);
+            }
+            ComponentType::Ellipse => {
+                (func)(&mut self.ellipses);
+            }
+            ComponentType::Nurbs => {
+                (func)(&mut self.nurbs);
+            }
             ComponentType::Arrow => {
                 (func)(&mut self.arrows);
             }
             ComponentType::ArrowPlane => {
                 (func)(&mut self.arrow_planes);
             }
+            ComponentType::Face => {
+                (func)(&mut self.faces);
+            }
+            ComponentType::Mesh => {
+                (func)(&mut self.meshes);
+            }
         };
     }
 }
@@ -232,6 +380,8 @@ pub struct ComponentArray<T> {
     pub array: Vec<Component<T>>,
     pub buffer_size: usize,
     pub buffer: wgpu::Buffer,
+    // set when the buffer was recreated, bind groups using it have to be rebuilt
+    pub resized: bool,
     pub device: Arc<Device>,
     pub queue: Arc<Queue>,
 }
@@ -243,7 +393,9 @@ impl<T> ComponentArray<T> {
         queue: &Arc<Queue>,
     ) -> ComponentArray<T> {
         let mem_size = core::mem::size_of::<Component<T>>();
-        let buffer_size = array.len() * mem_size;
+        let data_size = array.len() * mem_size;
+        // empty arrays still need a buffer that can be bound
+        let buffer_size = array.len().max(1) * mem_size;
 
         let buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: None,
@@ -253,9 +405,9 @@ impl<T> ComponentArray<T> {
         });
 
         unsafe {
-            let data: &[u8] = core::slice::from_raw_parts(array.as_ptr() as *const u8, buffer_size);
+            let data: &[u8] = core::slice::from_raw_parts(array.as_ptr() as *const u8, data_size);
             buffer
-                .slice(0..buffer_size as u64)
+                .slice(0..data_size as u64)
                 .get_mapped_range_mut()
                 .copy_from_slice(data);
         }
@@ -265,6 +417,7 @@ impl<T> ComponentArray<T> {
             array,
             buffer_size,
             buffer,
+            resized: false,
             device: device.clone(),
             queue: queue.clone(),
         };
@@ -324,6 +477,47 @@ impl<T> ComponentArray<T> {
         return index;
     }
 
+    pub fn extend(&mut self, components: Vec<Component<T>>) {
+        let start = self.array.len();
+        self.array.extend(components);
+
+        let needed_buffer_size = self.get_needed_buffer_size();
+        if needed_buffer_size > self.buffer_size {
+            self.resize_buffer(needed_buffer_size);
+        } else {
+            unsafe {
+                let single_size = std::mem::size_of::<Component<T>>();
+                let data = std::slice::from_raw_parts(
+                    self.array[start..].as_ptr() as *const u8,
+                    (self.array.len() - start) * single_size,
+                );
+                self.queue.write_buffer(&self.buffer, (start * single_size) as u64, data);
+            }
+        }
+    }
+
+    // drops everything from len on, the buffer keeps its size
+    pub fn truncate(&mut self, len: usize) {
+        self.array.truncate(len);
+    }
+
+    // swaps the whole content, used for data that is regenerated instead of edited
+    pub fn replace(&mut self, array: Vec<Component<T>>) {
+        self.array = array;
+        let needed_buffer_size = self.get_needed_buffer_size();
+        if needed_buffer_size > self.buffer_size {
+            self.resize_buffer(needed_buffer_size);
+        } else if needed_buffer_size > 0 {
+            unsafe {
+                let data: &[u8] = core::slice::from_raw_parts(
+                    self.array.as_ptr() as *const u8,
+                    needed_buffer_size,
+                );
+                self.queue.write_buffer(&self.buffer, 0, data);
+            }
+        }
+    }
+
     fn resize_buffer(&mut self, new_size: usize) {
         self.buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
             label: None,
@@ -344,5 +538,6 @@ impl<T> ComponentArray<T> {
 
         //println!("update buffer {} -> {}", self.buffer_size, new_size);
         self.buffer_size = new_size;
+        self.resized = true;
     }
 }
@@ -0,0 +1,87 @@
+use std::f32::consts::PI;
+
+use crate::component_collection::ComponentCollection;
+use glam::Vec3;
+
+use super::component::{
+    endpoint, walk, Component, IComponent, IComponentData, IndexPosition, WalkDirection,
+};
+
+// counter clockwise around the orientation from start to end, the radius is given by the start point
+#[derive(Clone)]
+#[repr(C)]
+pub struct Arc {
+    pub center: u32,
+    pub start: u32,
+    pub end: u32,
+    pub orientation: Vec3,
+}
+
+impl Arc {
+    // center, radius, basis starting at the start point and the swept angle in (0, 2pi]
+    pub fn geometry(&self, components: &ComponentCollection) -> (Vec3, f32, Vec3, Vec3, f32) {
+        let point = |i: u32| components.points.array[i as usize].get_center(components);
+        let center = point(self.center);
+        let start = point(self.start) - center;
+        let end = point(self.end) - center;
+        let radius = start.length();
+        let u = start.normalize_or_zero();
+        let v = self.orientation.normalize().cross(u);
+        let mut angle = end.dot(v).atan2(end.dot(u));
+        if angle <= 1e-6 {
+            angle += 2. * PI;
+        }
+        return (center, radius, u, v, angle);
+    }
+
+    // same parametrization as arc_position in common.wgsl
+    pub fn position(&self, components: &ComponentCollection, t: f32) -> Vec3 {
+        let (center, radius, u, v, angle) = self.geometry(components);
+        let a = angle * t;
+        return center + (u * a.cos() + v * a.sin()) * radius;
+    }
+}
+
+// the circle through three points, None when they are collinear
+pub fn circumcenter(a: Vec3, b: Vec3, c: Vec3) -> Option<Vec3> {
+    let ab = b - a;
+    let ac = c - a;
+    let normal = ab.cross(ac);
+    let d = 2. * normal.length_squared();
+    if d < 1e-12 {
+        return None;
+    }
+    let offset = (normal.cross(ab) * ac.length_squared() + ac.cross(normal) * ab.length_squared()) / d;
+    return Some(a + offset);
+}
+
+impl IComponentData for Arc {
+    fn get_center(&self, components: &ComponentCollection) -> Vec3 {
+        self.position(components, 0.5)
+    }
+
+    fn get_orientation(&self, components: &ComponentCollection, direction: WalkDirection) -> Vec3 {
+        let (_, _, u, v, angle) = self.geometry(components);
+        match direction {
+            WalkDirection::Up => v,
+            WalkDirection::Down => u * angle.sin() - v * angle.cos(),
+        }
+    }
+
+    fn get_position(&self, components: &ComponentCollection, direction: WalkDirection, t: f32) -> Vec3 {
+        self.position(components, walk(direction, t))
+    }
+
+    fn get_index(&self, direction: WalkDirection, position: IndexPosition, _: u32) -> Option<u32> {
+        Some(endpoint(self.start, self.end, direction, position))
+    }
+}
+
+pub fn new(center: u32, start: u32, end: u32, orientation: Vec3) -> Component<Arc> {
+    Component::new(Arc {
+        center,
+        start,
+        end,
+        orientation,
+    })
+}
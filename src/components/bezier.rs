@@ -1,7 +1,9 @@
 use crate::component_collection::ComponentCollection;
 use glam::Vec3;
 
-use super::component::{Component, IComponent, IComponentData};
+use super::component::{
+    endpoint, walk, Component, IComponent, IComponentData, IndexPosition, WalkDirection,
+};
 
 #[derive(Clone)]
 #[repr(C)]
@@ -12,9 +14,54 @@ pub struct Bezier {
     pub control_b: u32,
 }
 
+impl Bezier {
+    pub fn controls(&self, components: &ComponentCollection) -> [Vec3; 4] {
+        let point = |i: u32| components.points.array[i as usize].get_center(components);
+        [
+            point(self.point_a),
+            point(self.control_a),
+            point(self.control_b),
+            point(self.point_b),
+        ]
+    }
+}
+
+pub fn position(p: &[Vec3; 4], t: f32) -> Vec3 {
+    let u = 1. - t;
+    return u * u * u * p[0] + 3. * u * u * t * p[1] + 3. * u * t * t * p[2] + t * t * t * p[3];
+}
+
+pub fn derivative(p: &[Vec3; 4], t: f32) -> Vec3 {
+    let u = 1. - t;
+    return 3. * u * u * (p[1] - p[0]) + 6. * u * t * (p[2] - p[1]) + 3. * t * t * (p[3] - p[2]);
+}
+
 impl IComponentData for Bezier {
     fn get_center(&self, components: &ComponentCollection) -> Vec3 {
-        Vec3::ZERO
+        position(&self.controls(components), 0.5)
+    }
+
+    fn get_orientation(&self, components: &ComponentCollection, direction: WalkDirection) -> Vec3 {
+        let p = self.controls(components);
+        // tangent at the start of the walk, falling back when control points coincide
+        let candidates = match direction {
+            WalkDirection::Up => [p[1] - p[0], p[2] - p[0], p[3] - p[0]],
+            WalkDirection::Down => [p[2] - p[3], p[1] - p[3], p[0] - p[3]],
+        };
+        for candidate in candidates.iter() {
+            if candidate.length_squared() > 1e-12 {
+                return candidate.normalize();
+            }
+        }
+        return Vec3::ZERO;
+    }
+
+    fn get_position(&self, components: &ComponentCollection, direction: WalkDirection, t: f32) -> Vec3 {
+        position(&self.controls(components), walk(direction, t))
+    }
+
+    fn get_index(&self, direction: WalkDirection, position: IndexPosition, _: u32) -> Option<u32> {
+        Some(endpoint(self.point_a, self.point_b, direction, position))
     }
 }
 
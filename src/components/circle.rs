@@ -1,7 +1,9 @@
-use crate::component_collection::ComponentCollection;
+use std::f32::consts::PI;
+
+use crate::{component_collection::ComponentCollection, core::basics::Plane};
 use glam::Vec3;
 
-use super::component::{Component, IComponent, IComponentData};
+use super::component::{walk, Component, IComponent, IComponentData, IndexPosition, WalkDirection};
 
 #[derive(Clone)]
 #[repr(C)]
@@ -9,13 +11,69 @@ pub struct Circle {
     pub center: u32,
     pub radius: f32,
     pub orientation: Vec3,
+    // rise along the orientation per turn in mm, a helix when not zero
     pub heightfactor: f32,
+    pub turns: f32,
+    // change of the radius per turn in mm, a spiral when not zero
+    pub growth: f32,
+}
+
+impl Circle {
+    // same parametrization as circle_position in common.wgsl, starting at orthogonal(orientation)
+    pub fn position(&self, center: Vec3, t: f32) -> Vec3 {
+        let (u, v) = Plane {
+            position: center,
+            orientation: self.orientation,
+        }
+        .basis();
+        let turns = self.turns * t;
+        let angle = 2. * PI * turns;
+        let radius = self.radius + self.growth * turns;
+        let height = self.heightfactor * turns;
+        return center + (u * angle.cos() + v * angle.sin()) * radius + self.orientation.normalize() * height;
+    }
+
+    // derivative of position with respect to t
+    fn tangent(&self, t: f32) -> Vec3 {
+        let (u, v) = Plane {
+            position: Vec3::ZERO,
+            orientation: self.orientation,
+        }
+        .basis();
+        let angle = 2. * PI * self.turns * t;
+        let radius = self.radius + self.growth * self.turns * t;
+        let (sin, cos) = angle.sin_cos();
+        return (u * -sin + v * cos) * radius * 2. * PI * self.turns
+            + (u * cos + v * sin) * self.growth * self.turns
+            + self.orientation.normalize() * self.heightfactor * self.turns;
+    }
+
+    // a single flat turn, helices and spirals have open ends
+    pub fn is_closed(&self) -> bool {
+        self.heightfactor == 0. && self.growth == 0. && self.turns == 1.
+    }
 }
 
 impl IComponentData for Circle {
     fn get_center(&self, components: &ComponentCollection) -> Vec3 {
         components.points.array[self.center as usize].get_center(components)
     }
+
+    fn get_orientation(&self, _: &ComponentCollection, direction: WalkDirection) -> Vec3 {
+        match direction {
+            WalkDirection::Up => self.tangent(0.).normalize_or_zero(),
+            WalkDirection::Down => -self.tangent(1.).normalize_or_zero(),
+        }
+    }
+
+    fn get_position(&self, components: &ComponentCollection, direction: WalkDirection, t: f32) -> Vec3 {
+        self.position(self.get_center(components), walk(direction, t))
+    }
+
+    // a circle has no end points, neither have helices since their ends are not points
+    fn get_index(&self, _: WalkDirection, _: IndexPosition, _: u32) -> Option<u32> {
+        None
+    }
 }
 
 pub fn new(center: u32, radius: f32, orientation: Vec3, heightfactor: f32) -> Component<Circle> {
@@ -24,5 +82,7 @@ pub fn new(center: u32, radius: f32, orientation: Vec3, heightfactor: f32) -> Co
         radius,
         orientation,
         heightfactor,
+        turns: 1.,
+        growth: 0.,
     })
 }
\ No newline at end of file
@@ -17,6 +17,30 @@ pub enum WalkDirection {
     Up = 1,
 }
 
+// parameter along a curve when walking it in the given direction
+pub fn walk(direction: WalkDirection, t: f32) -> f32 {
+    match direction {
+        WalkDirection::Up => t,
+        WalkDirection::Down => 1. - t,
+    }
+}
+
+pub fn endpoint(a: u32, b: u32, direction: WalkDirection, position: IndexPosition) -> u32 {
+    match (direction, position) {
+        (WalkDirection::Up, IndexPosition::First) | (WalkDirection::Down, IndexPosition::Last) => a,
+        (WalkDirection::Up, IndexPosition::Last) | (WalkDirection::Down, IndexPosition::First) => b,
+    }
+}
+
+impl WalkDirection {
+    pub fn reverse(&self) -> WalkDirection {
+        match self {
+            WalkDirection::Up => WalkDirection::Down,
+            WalkDirection::Down => WalkDirection::Up,
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq)]
 #[repr(C)]
 pub enum ComponentFlags {
@@ -25,6 +49,8 @@ pub enum ComponentFlags {
     Hover = 2,
     Selected = 4,
     Deleted = 8,
+    // reference geometry, drawn dashed and left out of profiles, solids and exports
+    Construction = 16,
 }
 
 #[derive(Clone, Debug)]
@@ -43,7 +69,7 @@ pub struct HoverElement {
     pub position: Vec3,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(C)]
 pub enum ComponentType {
     Point = 1,
@@ -52,6 +78,11 @@ pub enum ComponentType {
     Bezier = 4,
     Arrow = 5,
     ArrowPlane = 6,
+    Face = 7,
+    Mesh = 8,
+    Arc = 9,
+    Ellipse = 10,
+    Nurbs = 11,
 }
 
 #[derive(Clone, Debug)]
@@ -59,6 +90,8 @@ pub enum ComponentType {
 pub struct Component<T> {
     pub data: T,
     pub flags: i32,
+    // index into the style array, 0 is the default style
+    pub style: u32,
 }
 
 impl<T> Component<T> {
@@ -71,6 +104,7 @@ impl<T> Component<T> {
         Component {
             data,
             flags: ComponentFlags::Visible as i32,
+            style: 0,
         }
     }
 }
@@ -100,6 +134,25 @@ where
     fn is_selected(&self) -> bool {
         (self.flags & (ComponentFlags::Selected as i32)) == (ComponentFlags::Selected as i32)
     }
+    fn is_deleted(&self) -> bool {
+        (self.flags & (ComponentFlags::Deleted as i32)) == (ComponentFlags::Deleted as i32)
+    }
+    fn is_construction(&self) -> bool {
+        (self.flags & (ComponentFlags::Construction as i32)) == (ComponentFlags::Construction as i32)
+    }
+    fn set_construction(&mut self, construction: bool) {
+        if construction {
+            self.flags = self.flags | ComponentFlags::Construction as i32;
+        } else {
+            self.flags = self.flags & (!(ComponentFlags::Construction as i32));
+        }
+    }
+    fn get_style(&self) -> u32 {
+        self.style
+    }
+    fn set_style(&mut self, style: u32) {
+        self.style = style;
+    }
 
     fn get_center(&self, components: &ComponentCollection) -> Vec3 {
         self.data.get_center(components)
@@ -127,6 +180,7 @@ where
         return Box::new(Component {
             data: self.data.clone(),
             flags: self.flags,
+            style: self.style,
         });
     }
 }
@@ -158,6 +212,11 @@ pub trait IComponent {
     fn selected(&mut self);
     fn deselected(&mut self);
     fn is_selected(&self) -> bool;
+    fn is_deleted(&self) -> bool;
+    fn is_construction(&self) -> bool;
+    fn set_construction(&mut self, construction: bool);
+    fn get_style(&self) -> u32;
+    fn set_style(&mut self, style: u32);
     fn get_center(&self, components: &ComponentCollection) -> Vec3;
     fn move_dir(&mut self, dir: Vec3);
     fn get_orientation(&self, components: &ComponentCollection, direction: WalkDirection) -> Vec3;
@@ -0,0 +1,84 @@
+use std::f32::consts::PI;
+
+use crate::{component_collection::ComponentCollection, core::basics::Plane};
+use glam::Vec3;
+
+use super::component::{walk, Component, IComponent, IComponentData, IndexPosition, WalkDirection};
+
+// an ellipse or elliptical arc, angles are in radians
+#[derive(Clone)]
+#[repr(C)]
+pub struct Ellipse {
+    pub center: u32,
+    // along the major axis
+    pub radius_a: f32,
+    pub radius_b: f32,
+    pub orientation: Vec3,
+    // of the major axis against orthogonal(orientation)
+    pub rotation: f32,
+    pub start_angle: f32,
+    pub sweep_angle: f32,
+}
+
+impl Ellipse {
+    pub fn axes(&self) -> (Vec3, Vec3) {
+        let (u, v) = Plane {
+            position: Vec3::ZERO,
+            orientation: self.orientation,
+        }
+        .basis();
+        let (sin, cos) = self.rotation.sin_cos();
+        return (u * cos + v * sin, v * cos - u * sin);
+    }
+
+    // same parametrization as ellipse_position in common.wgsl
+    pub fn position(&self, center: Vec3, t: f32) -> Vec3 {
+        let (a, b) = self.axes();
+        let angle = self.start_angle + self.sweep_angle * t;
+        return center + a * self.radius_a * angle.cos() + b * self.radius_b * angle.sin();
+    }
+
+    fn tangent(&self, t: f32) -> Vec3 {
+        let (a, b) = self.axes();
+        let angle = self.start_angle + self.sweep_angle * t;
+        return (b * self.radius_b * angle.cos() - a * self.radius_a * angle.sin()) * self.sweep_angle;
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.sweep_angle.abs() >= 2. * PI - 1e-4
+    }
+}
+
+impl IComponentData for Ellipse {
+    fn get_center(&self, components: &ComponentCollection) -> Vec3 {
+        components.points.array[self.center as usize].get_center(components)
+    }
+
+    fn get_orientation(&self, _: &ComponentCollection, direction: WalkDirection) -> Vec3 {
+        match direction {
+            WalkDirection::Up => self.tangent(0.).normalize_or_zero(),
+            WalkDirection::Down => -self.tangent(1.).normalize_or_zero(),
+        }
+    }
+
+    fn get_position(&self, components: &ComponentCollection, direction: WalkDirection, t: f32) -> Vec3 {
+        self.position(self.get_center(components), walk(direction, t))
+    }
+
+    // the ends of elliptical arcs are not points, so like circles they can't be chained
+    fn get_index(&self, _: WalkDirection, _: IndexPosition, _: u32) -> Option<u32> {
+        None
+    }
+}
+
+pub fn new(center: u32, radius_a: f32, radius_b: f32, orientation: Vec3, rotation: f32) -> Component<Ellipse> {
+    Component::new(Ellipse {
+        center,
+        radius_a,
+        radius_b,
+        orientation,
+        rotation,
+        start_angle: 0.,
+        sweep_angle: 2. * PI,
+    })
+}
@@ -0,0 +1,44 @@
+use glam::Vec3;
+use crate::component_collection::ComponentCollection;
+
+use super::component::{Component, IComponentData};
+
+// a filled profile, its triangles are the vertices first..first + count of the face vertex buffer
+#[derive(Clone)]
+#[repr(C)]
+pub struct Face {
+    pub first: u32,
+    pub count: u32,
+}
+
+#[derive(Clone)]
+#[repr(C)]
+pub struct FaceVertex {
+    pub position: Vec3,
+    pub face: u32,
+}
+
+impl IComponentData for Face {
+    fn get_center(&self, components: &ComponentCollection) -> Vec3 {
+        let vertices = &components.face_vertices.array[self.first as usize..(self.first + self.count) as usize];
+        if vertices.is_empty() {
+            return Vec3::ZERO;
+        }
+        let sum: Vec3 = vertices.iter().map(|v| v.data.position).sum();
+        return sum / vertices.len() as f32;
+    }
+}
+
+impl IComponentData for FaceVertex {
+    fn get_center(&self, _: &ComponentCollection) -> Vec3 {
+        self.position
+    }
+}
+
+pub fn new(first: u32, count: u32) -> Component<Face> {
+    Component::new(Face { first, count })
+}
+
+pub fn vertex(position: Vec3, face: u32) -> Component<FaceVertex> {
+    Component::new(FaceVertex { position, face })
+}
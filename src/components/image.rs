@@ -0,0 +1,53 @@
+use glam::Vec3;
+use crate::{component_collection::ComponentCollection, core::basics::Plane};
+
+use super::component::{Component, IComponentData};
+
+// a picture lying on a plane for tracing, its texels are the pixels first..first + width * height
+// of the pixel buffer, row by row from the top
+#[derive(Clone)]
+#[repr(C)]
+pub struct Image {
+    pub position: Vec3,
+    pub normal: Vec3,
+    // counter clockwise around the normal in radians
+    pub rotation: f32,
+    // size of a pixel in mm
+    pub scale: f32,
+    pub opacity: f32,
+    pub width: u32,
+    pub height: u32,
+    pub first: u32,
+}
+
+impl IComponentData for Image {
+    fn get_center(&self, _: &ComponentCollection) -> Vec3 {
+        self.position
+    }
+}
+
+impl Image {
+    // directions of the rows and the columns, same as vert_main in image.wgsl
+    pub fn axes(&self) -> (Vec3, Vec3) {
+        let (u, v) = Plane { position: self.position, orientation: self.normal }.basis();
+        let (sin, cos) = self.rotation.sin_cos();
+        return (u * cos + v * sin, v * cos - u * sin);
+    }
+
+    pub fn size(&self) -> (f32, f32) {
+        (self.width as f32 * self.scale, self.height as f32 * self.scale)
+    }
+}
+
+pub fn new(position: Vec3, normal: Vec3, width: u32, height: u32, first: u32, scale: f32) -> Component<Image> {
+    Component::new(Image {
+        position,
+        normal,
+        rotation: 0.,
+        scale,
+        opacity: 0.5,
+        width,
+        height,
+        first,
+    })
+}
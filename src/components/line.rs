@@ -1,7 +1,9 @@
 use glam::Vec3;
 use crate::component_collection::ComponentCollection;
 
-use super::component::{Component, IComponentData, IComponent};
+use super::component::{
+    endpoint, walk, Component, IComponent, IComponentData, IndexPosition, WalkDirection,
+};
 
 #[derive(Clone)]
 #[repr(C)]
@@ -16,6 +18,26 @@ impl IComponentData for Line {
         let b = components.points.array[self.point_b as usize].get_center(components);
         return a + (b - a) / 2.;
     }
+
+    fn get_orientation(&self, components: &ComponentCollection, direction: WalkDirection) -> Vec3 {
+        let a = components.points.array[self.point_a as usize].get_center(components);
+        let b = components.points.array[self.point_b as usize].get_center(components);
+        match direction {
+            WalkDirection::Up => (b - a).normalize_or_zero(),
+            WalkDirection::Down => (a - b).normalize_or_zero(),
+        }
+    }
+
+    fn get_position(&self, components: &ComponentCollection, direction: WalkDirection, t: f32) -> Vec3 {
+        let a = components.points.array[self.point_a as usize].get_center(components);
+        let b = components.points.array[self.point_b as usize].get_center(components);
+        let t = walk(direction, t);
+        return a + (b - a) * t;
+    }
+
+    fn get_index(&self, direction: WalkDirection, position: IndexPosition, _: u32) -> Option<u32> {
+        Some(endpoint(self.point_a, self.point_b, direction, position))
+    }
 }
 
 pub fn new(point_a: u32, point_b: u32) -> Component<Line> {
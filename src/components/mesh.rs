@@ -0,0 +1,48 @@
+use glam::Vec3;
+use crate::component_collection::ComponentCollection;
+
+use super::component::{Component, IComponentData};
+
+// a solid or surface, its triangle corners are the indices first..first + count of the mesh index
+// buffer, they point to vertices the triangles share
+#[derive(Clone)]
+#[repr(C)]
+pub struct Mesh {
+    pub first: u32,
+    pub count: u32,
+    // 1 draws only the triangle edges
+    pub wireframe: u32,
+}
+
+#[derive(Clone)]
+#[repr(C)]
+pub struct MeshVertex {
+    pub position: Vec3,
+    pub normal: Vec3,
+    pub mesh: u32,
+}
+
+impl IComponentData for Mesh {
+    fn get_center(&self, components: &ComponentCollection) -> Vec3 {
+        let indices = &components.mesh_indices.indices[self.first as usize..(self.first + self.count) as usize];
+        if indices.is_empty() {
+            return Vec3::ZERO;
+        }
+        let sum: Vec3 = indices.iter().map(|i| components.mesh_vertices.array[*i as usize].data.position).sum();
+        return sum / indices.len() as f32;
+    }
+}
+
+impl IComponentData for MeshVertex {
+    fn get_center(&self, _: &ComponentCollection) -> Vec3 {
+        self.position
+    }
+}
+
+pub fn new(first: u32, count: u32) -> Component<Mesh> {
+    Component::new(Mesh { first, count, wireframe: 0 })
+}
+
+pub fn vertex(position: Vec3, normal: Vec3, mesh: u32) -> Component<MeshVertex> {
+    Component::new(MeshVertex { position, normal, mesh })
+}
@@ -3,4 +3,11 @@ pub mod vertex;
 pub mod point;
 pub mod line;
 pub mod bezier;
-pub mod circle;
\ No newline at end of file
+pub mod circle;
+pub mod arc;
+pub mod ellipse;
+pub mod nurbs;
+pub mod face;
+pub mod mesh;
+pub mod image;
+pub mod style;
\ No newline at end of file
@@ -0,0 +1,249 @@
+use crate::{
+    component_collection::ComponentCollection,
+    core::result::{Error, Result},
+};
+use glam::{Vec3, Vec4};
+
+use super::component::{endpoint, walk, Component, IComponent, IComponentData, IndexPosition, WalkDirection};
+
+// the shaders evaluate curves up to this degree
+pub const MAX_DEGREE: u32 = 7;
+
+// a clamped nurbs curve, its control points are first..first + count of the control buffer
+// and its count + degree + 1 knots start at first_knot in the knot buffer
+#[derive(Clone)]
+#[repr(C)]
+pub struct Nurbs {
+    pub first: u32,
+    pub count: u32,
+    pub first_knot: u32,
+    pub degree: u32,
+    // the first and last control point, a clamped curve starts and ends there
+    pub point_a: u32,
+    pub point_b: u32,
+}
+
+#[derive(Clone)]
+#[repr(C)]
+pub struct NurbsControl {
+    pub point: u32,
+    pub weight: f32,
+    pub nurbs: u32,
+}
+
+#[derive(Clone)]
+#[repr(C)]
+pub struct Knot {
+    pub value: f32,
+}
+
+impl Nurbs {
+    pub fn controls<'a>(&self, components: &'a ComponentCollection) -> &'a [Component<NurbsControl>] {
+        &components.nurbs_controls.array[self.first as usize..(self.first + self.count) as usize]
+    }
+
+    pub fn knots(&self, components: &ComponentCollection) -> Vec<f32> {
+        let knots = self.first_knot as usize..(self.first_knot + self.count + self.degree + 1) as usize;
+        components.nurbs_knots.array[knots].iter().map(|k| k.data.value).collect()
+    }
+
+    // control points in homogeneous coordinates, xyz multiplied by the weight in w
+    pub fn homogeneous(&self, components: &ComponentCollection) -> Vec<Vec4> {
+        self.controls(components)
+            .iter()
+            .map(|c| {
+                let p = components.points.array[c.data.point as usize].get_center(components);
+                (p * c.data.weight).extend(c.data.weight)
+            })
+            .collect()
+    }
+
+    // same parametrization as nurbs_position in nurbs.wgsl, t runs over the whole knot range
+    pub fn position(&self, components: &ComponentCollection, t: f32) -> Vec3 {
+        let knots = self.knots(components);
+        let degree = self.degree as usize;
+        let u = knots[degree] + (knots[self.count as usize] - knots[degree]) * t;
+        let p = de_boor(&self.homogeneous(components), &knots, degree, u);
+        return p.truncate() / p.w;
+    }
+}
+
+fn span(knots: &[f32], count: usize, degree: usize, u: f32) -> usize {
+    let mut k = degree;
+    while k < count - 1 && knots[k + 1] <= u {
+        k += 1;
+    }
+    return k;
+}
+
+pub fn de_boor(controls: &[Vec4], knots: &[f32], degree: usize, u: f32) -> Vec4 {
+    let k = span(knots, controls.len(), degree, u);
+    let mut d: Vec<Vec4> = (0..=degree).map(|j| controls[j + k - degree]).collect();
+    for r in 1..=degree {
+        for j in (r..=degree).rev() {
+            let i = j + k - degree;
+            let length = knots[i + degree + 1 - r] - knots[i];
+            let alpha = if length > 1e-12 { (u - knots[i]) / length } else { 0. };
+            d[j] = d[j - 1].lerp(d[j], alpha);
+        }
+    }
+    return d[degree];
+}
+
+// boehm's algorithm, the curve keeps its shape
+pub fn insert_knot(controls: &[Vec4], knots: &[f32], degree: usize, u: f32) -> (Vec<Vec4>, Vec<f32>) {
+    let k = span(knots, controls.len(), degree, u);
+    let mut inserted = vec![];
+    for i in 0..=controls.len() {
+        if i + degree <= k {
+            inserted.push(controls[i]);
+        } else if i > k {
+            inserted.push(controls[i - 1]);
+        } else {
+            let alpha = (u - knots[i]) / (knots[i + degree] - knots[i]);
+            inserted.push(controls[i - 1].lerp(controls[i], alpha));
+        }
+    }
+    let mut new_knots = knots.to_vec();
+    new_knots.insert(k + 1, u);
+    return (inserted, new_knots);
+}
+
+// uniform knots with both ends repeated so the curve starts and ends at its end points
+pub fn clamped_knots(count: usize, degree: usize) -> Vec<f32> {
+    let spans = count - degree;
+    let mut knots = vec![0.; degree + 1];
+    knots.extend((1..spans).map(|i| i as f32 / spans as f32));
+    knots.extend(vec![1.; degree + 1]);
+    return knots;
+}
+
+// splits the curve into cubic bezier segments, only polynomial curves up to degree 3 can be split exactly
+pub fn to_beziers(controls: &[Vec4], knots: &[f32], degree: usize) -> Result<Vec<[Vec3; 4]>> {
+    if degree == 0 || degree > 3 {
+        return Err(Error::Geometry("only curves up to degree 3 can be split into beziers".into()));
+    }
+    if controls.iter().any(|c| (c.w - controls[0].w).abs() > 1e-6) {
+        return Err(Error::Geometry("curves with different weights can't be split into beziers".into()));
+    }
+
+    // every inner knot is repeated degree times
+    let (mut controls, mut knots) = (controls.to_vec(), knots.to_vec());
+    let (start, end) = (knots[degree], knots[controls.len()]);
+    let mut u = start;
+    loop {
+        let Some(next) = knots.iter().copied().find(|k| *k > u && *k < end) else {
+            break;
+        };
+        let multiplicity = knots.iter().filter(|k| **k == next).count();
+        for _ in multiplicity..degree {
+            (controls, knots) = insert_knot(&controls, &knots, degree, next);
+        }
+        u = next;
+    }
+
+    let points: Vec<Vec3> = controls.iter().map(|c| c.truncate() / c.w).collect();
+    let segments = points
+        .windows(degree + 1)
+        .step_by(degree)
+        .map(|p| match degree {
+            1 => [p[0], p[0].lerp(p[1], 1. / 3.), p[0].lerp(p[1], 2. / 3.), p[1]],
+            // degree elevation of a quadratic segment
+            2 => [p[0], p[0].lerp(p[1], 2. / 3.), p[2].lerp(p[1], 2. / 3.), p[2]],
+            _ => [p[0], p[1], p[2], p[3]],
+        })
+        .collect();
+    return Ok(segments);
+}
+
+// one cubic curve through connected bezier segments, the knots are the segment borders
+pub fn from_beziers(segments: &[[Vec3; 4]]) -> (Vec<Vec3>, Vec<f32>) {
+    let mut points = vec![segments[0][0]];
+    let mut knots = vec![0.; 4];
+    for (i, segment) in segments.iter().enumerate() {
+        points.extend(&segment[1..]);
+        let border = (i + 1) as f32 / segments.len() as f32;
+        let multiplicity = if i + 1 == segments.len() { 4 } else { 3 };
+        knots.extend(vec![border; multiplicity]);
+    }
+    return (points, knots);
+}
+
+impl IComponentData for Nurbs {
+    fn get_center(&self, components: &ComponentCollection) -> Vec3 {
+        self.position(components, 0.5)
+    }
+
+    fn get_orientation(&self, components: &ComponentCollection, direction: WalkDirection) -> Vec3 {
+        // the end tangents of a clamped curve point along the control polygon
+        let controls = self.controls(components);
+        let point = |i: usize| components.points.array[controls[i].data.point as usize].get_center(components);
+        let n = controls.len();
+        let candidates: Vec<Vec3> = match direction {
+            WalkDirection::Up => (1..n).map(|i| point(i) - point(0)).collect(),
+            WalkDirection::Down => (0..n - 1).rev().map(|i| point(i) - point(n - 1)).collect(),
+        };
+        for candidate in candidates.iter() {
+            if candidate.length_squared() > 1e-12 {
+                return candidate.normalize();
+            }
+        }
+        return Vec3::ZERO;
+    }
+
+    fn get_position(&self, components: &ComponentCollection, direction: WalkDirection, t: f32) -> Vec3 {
+        self.position(components, walk(direction, t))
+    }
+
+    fn get_index(&self, direction: WalkDirection, position: IndexPosition, _: u32) -> Option<u32> {
+        Some(endpoint(self.point_a, self.point_b, direction, position))
+    }
+}
+
+impl IComponentData for NurbsControl {
+    fn get_center(&self, components: &ComponentCollection) -> Vec3 {
+        components.points.array[self.point as usize].get_center(components)
+    }
+}
+
+impl IComponentData for Knot {
+    fn get_center(&self, _: &ComponentCollection) -> Vec3 {
+        Vec3::ZERO
+    }
+}
+
+// adds a curve through the given points, returns its index
+pub fn push_nurbs(
+    components: &mut ComponentCollection,
+    points: &[u32],
+    weights: &[f32],
+    knots: &[f32],
+    degree: u32,
+) -> usize {
+    let index = components.nurbs.array.len() as u32;
+    let first = components.nurbs_controls.array.len() as u32;
+    let first_knot = components.nurbs_knots.array.len() as u32;
+    let controls = points
+        .iter()
+        .zip(weights.iter())
+        .map(|(point, weight)| {
+            Component::new(NurbsControl {
+                point: *point,
+                weight: *weight,
+                nurbs: index,
+            })
+        })
+        .collect();
+    components.nurbs_controls.extend(controls);
+    components
+        .nurbs_knots
+        .extend(knots.iter().map(|value| Component::new(Knot { value: *value })).collect());
+    return components.nurbs.push(Component::new(Nurbs {
+        first,
+        count: points.len() as u32,
+        first_knot,
+        degree,
+        point_a: points[0],
+        point_b: points[points.len() - 1],
+    }));
+}
@@ -1,7 +1,7 @@
 use glam::Vec3;
 use crate::component_collection::ComponentCollection;
 
-use super::component::{Component, IComponentData};
+use super::component::{Component, IComponentData, IndexPosition, WalkDirection};
 
 #[derive(Clone)]
 #[repr(C)]
@@ -13,6 +13,14 @@ impl IComponentData for Point {
     fn get_center(& self, components : &ComponentCollection) -> Vec3 {
         self.position
     }
+
+    fn get_position(&self, _: &ComponentCollection, _: WalkDirection, _: f32) -> Vec3 {
+        self.position
+    }
+
+    fn get_index(&self, _: WalkDirection, _: IndexPosition, self_index: u32) -> Option<u32> {
+        Some(self_index)
+    }
 }
 
 impl From<Vec3> for Point {
@@ -0,0 +1,63 @@
+use glam::Vec3;
+
+use crate::component_collection::ComponentCollection;
+
+use super::component::{Component, IComponentData};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(u32)]
+pub enum PointShape {
+    Circle = 0,
+    Square = 1,
+    Diamond = 2,
+    Cross = 3,
+}
+
+impl PointShape {
+    pub const ALL: [PointShape; 4] = [
+        PointShape::Circle,
+        PointShape::Square,
+        PointShape::Diamond,
+        PointShape::Cross,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PointShape::Circle => "Circle",
+            PointShape::Square => "Square",
+            PointShape::Diamond => "Diamond",
+            PointShape::Cross => "Cross",
+        }
+    }
+}
+
+// how a component is drawn, components point into the style array and style 0 is the default.
+// zero values fall back to the theme and the line settings, sizes are in screen pixels
+#[derive(Clone, Debug, PartialEq)]
+#[repr(C)]
+pub struct Style {
+    // unmultiplied rgba, alpha 0 keeps the theme color
+    pub color: [f32; 4],
+    pub width: f32,
+    pub dash: f32,
+    pub gap: f32,
+    pub point_size: f32,
+    pub point_shape: PointShape,
+}
+
+impl IComponentData for Style {
+    fn get_center(&self, _: &ComponentCollection) -> Vec3 {
+        Vec3::ZERO
+    }
+}
+
+pub fn default() -> Component<Style> {
+    Component::new(Style {
+        color: [0.; 4],
+        width: 0.,
+        dash: 0.,
+        gap: 0.,
+        point_size: 0.,
+        point_shape: PointShape::Circle,
+    })
+}
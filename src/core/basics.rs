@@ -167,6 +167,16 @@ pub struct Plane {
     pub orientation: Vec3,
 }
 
+impl Plane {
+    // same basis as plane_basis in common.wgsl
+    pub fn basis(&self) -> (Vec3, Vec3) {
+        let normal = self.orientation.normalize();
+        let u = orthogonal(normal).normalize();
+        let v = normal.cross(u);
+        return (u, v);
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Ray {
     pub origin: Vec3,
@@ -274,6 +284,14 @@ pub fn double_shift(v: Vec3) -> Vec3 {
     vec3(v.y, v.z, v.x)
 }
 
+pub fn orthogonal(v: Vec3) -> Vec3 {
+    if v.x.abs() > v.y.abs() {
+        vec3(-v.z, 0.0, v.x)
+    } else {
+        vec3(0.0, v.z, -v.y)
+    }
+}
+
 pub fn power(f: f32, power: i32) -> f32 {
     let mut result = 1.0;
     for i in 0..power {
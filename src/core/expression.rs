@@ -0,0 +1,561 @@
+use super::{
+    result::{Error, Result},
+    units::Unit,
+};
+
+// a value together with its length dimension, lengths are stored in millimeters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quantity {
+    pub value: f64,
+    pub length: i32,
+}
+
+impl Quantity {
+    pub fn scalar(value: f64) -> Self {
+        Self { value, length: 0 }
+    }
+
+    pub fn mm(value: f64) -> Self {
+        Self { value, length: 1 }
+    }
+
+    // unitless results are taken as values in the default unit
+    pub fn to_mm(&self, default: Unit) -> Result<f64> {
+        match self.length {
+            0 => Ok(self.value * default.to_mm()),
+            1 => Ok(self.value),
+            _ => Err(Error::Expression(format!(
+                "expected a length, got mm^{}",
+                self.length
+            ))),
+        }
+    }
+
+    pub fn to_scalar(&self) -> Result<f64> {
+        if self.length != 0 {
+            return Err(Error::Expression("expected a value without unit".into()));
+        }
+        return Ok(self.value);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    Number(f64),
+    Length(f64, Unit),
+    Identifier(String),
+    Negate(Box<Expression>),
+    Binary(Operator, Box<Expression>, Box<Expression>),
+    Call(String, Vec<Expression>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Identifier(String),
+    Inch,
+    Operator(char),
+    Open,
+    Close,
+    Comma,
+}
+
+impl Expression {
+    pub fn parse(text: &str) -> Result<Expression> {
+        let tokens = tokenize(text)?;
+        let mut parser = Parser { tokens, index: 0 };
+        let expression = parser.sum()?;
+        if parser.index < parser.tokens.len() {
+            return Err(Error::Expression(format!(
+                "unexpected {:?}",
+                parser.tokens[parser.index]
+            )));
+        }
+        return Ok(expression);
+    }
+
+    pub fn identifiers(&self) -> Vec<String> {
+        let mut result = vec![];
+        self.collect_identifiers(&mut result);
+        return result;
+    }
+
+    fn collect_identifiers(&self, result: &mut Vec<String>) {
+        match self {
+            Expression::Identifier(name) => {
+                if !result.contains(name) {
+                    result.push(name.clone());
+                }
+            }
+            Expression::Negate(a) => a.collect_identifiers(result),
+            Expression::Binary(_, a, b) => {
+                a.collect_identifiers(result);
+                b.collect_identifiers(result);
+            }
+            Expression::Call(_, args) => {
+                for arg in args.iter() {
+                    arg.collect_identifiers(result);
+                }
+            }
+            Expression::Number(_) | Expression::Length(_, _) => {}
+        }
+    }
+
+    // plain numbers added to lengths are taken in the default unit
+    pub fn evaluate(
+        &self,
+        default: Unit,
+        lookup: &dyn Fn(&str) -> Option<Quantity>,
+    ) -> Result<Quantity> {
+        match self {
+            Expression::Number(value) => Ok(Quantity::scalar(*value)),
+            Expression::Length(value, unit) => Ok(Quantity::mm(value * unit.to_mm())),
+            Expression::Identifier(name) => match name.as_str() {
+                "pi" => Ok(Quantity::scalar(std::f64::consts::PI)),
+                _ => lookup(name)
+                    .ok_or_else(|| Error::Expression(format!("unknown parameter '{}'", name))),
+            },
+            Expression::Negate(a) => {
+                let a = a.evaluate(default, lookup)?;
+                Ok(Quantity {
+                    value: -a.value,
+                    length: a.length,
+                })
+            }
+            Expression::Binary(operator, a, b) => {
+                let a = a.evaluate(default, lookup)?;
+                let b = b.evaluate(default, lookup)?;
+                binary(*operator, a, b, default)
+            }
+            Expression::Call(name, args) => {
+                let args = args
+                    .iter()
+                    .map(|arg| arg.evaluate(default, lookup))
+                    .collect::<Result<Vec<_>>>()?;
+                call(name, &args)
+            }
+        }
+    }
+}
+
+fn binary(operator: Operator, a: Quantity, b: Quantity, default: Unit) -> Result<Quantity> {
+    let too_large = || Error::Expression("the power of the unit is too large".into());
+    match operator {
+        Operator::Add | Operator::Sub => {
+            if a.length != b.length && a.length != 0 && b.length != 0 {
+                return Err(Error::Expression("incompatible units".into()));
+            }
+            let a = promote(a, b.length, default);
+            let b = promote(b, a.length, default);
+            let value = if operator == Operator::Add {
+                a.value + b.value
+            } else {
+                a.value - b.value
+            };
+            Ok(Quantity {
+                value,
+                length: a.length.max(b.length),
+            })
+        }
+        Operator::Mul => Ok(Quantity {
+            value: a.value * b.value,
+            length: a.length.checked_add(b.length).ok_or_else(too_large)?,
+        }),
+        Operator::Div => {
+            if b.value == 0.0 {
+                return Err(Error::Expression("division by zero".into()));
+            }
+            Ok(Quantity {
+                value: a.value / b.value,
+                length: a.length.checked_sub(b.length).ok_or_else(too_large)?,
+            })
+        }
+        Operator::Pow => {
+            let exponent = b.to_scalar()?;
+            if a.length != 0 && exponent.fract() != 0.0 {
+                return Err(Error::Expression("lengths need whole exponents".into()));
+            }
+            if a.length != 0 && exponent.abs() > i32::MAX as f64 {
+                return Err(too_large());
+            }
+            let length = a.length.checked_mul(exponent as i32).ok_or_else(too_large)?;
+            Ok(Quantity {
+                value: a.value.powf(exponent),
+                length,
+            })
+        }
+    }
+}
+
+fn promote(a: Quantity, length: i32, default: Unit) -> Quantity {
+    if a.length == 0 && length != 0 {
+        return Quantity {
+            value: a.value * default.to_mm().powi(length),
+            length,
+        };
+    }
+    return a;
+}
+
+// angles are in degrees
+fn call(name: &str, args: &[Quantity]) -> Result<Quantity> {
+    let single = || -> Result<Quantity> {
+        if args.len() != 1 {
+            return Err(Error::Expression(format!("{} expects one argument", name)));
+        }
+        return Ok(args[0]);
+    };
+    match name {
+        "abs" => {
+            let a = single()?;
+            Ok(Quantity {
+                value: a.value.abs(),
+                length: a.length,
+            })
+        }
+        "sqrt" => {
+            let a = single()?;
+            if a.length % 2 != 0 {
+                return Err(Error::Expression("sqrt of a length".into()));
+            }
+            Ok(Quantity {
+                value: a.value.sqrt(),
+                length: a.length / 2,
+            })
+        }
+        "sin" => Ok(Quantity::scalar(single()?.to_scalar()?.to_radians().sin())),
+        "cos" => Ok(Quantity::scalar(single()?.to_scalar()?.to_radians().cos())),
+        "tan" => Ok(Quantity::scalar(single()?.to_scalar()?.to_radians().tan())),
+        "min" | "max" => {
+            let first = *args
+                .first()
+                .ok_or_else(|| Error::Expression(format!("{} expects arguments", name)))?;
+            args[1..].iter().try_fold(first, |acc, x| {
+                if x.length != acc.length {
+                    return Err(Error::Expression("incompatible units".into()));
+                }
+                let pick = if name == "min" {
+                    x.value < acc.value
+                } else {
+                    x.value > acc.value
+                };
+                Ok(if pick { *x } else { acc })
+            })
+        }
+        _ => Err(Error::Expression(format!("unknown function '{}'", name))),
+    }
+}
+
+// the expression text with every reference to a parameter renamed, units, functions and the
+// formatting of the rest stay as they were
+pub fn rename_parameter(text: &str, from: &str, to: &str) -> Result<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let tokens = spans(&chars)?;
+    let mut renamed = String::new();
+    let mut end = 0;
+    for (k, (token, start, stop)) in tokens.iter().enumerate() {
+        let unit = k > 0 && matches!(tokens[k - 1].0, Token::Number(_));
+        let call = matches!(tokens.get(k + 1), Some((Token::Open, _, _)));
+        if *token == Token::Identifier(from.into()) && !unit && !call {
+            renamed.extend(&chars[end..*start]);
+            renamed.push_str(to);
+            end = *stop;
+        }
+    }
+    renamed.extend(&chars[end..]);
+    return Ok(renamed);
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = text.chars().collect();
+    return Ok(spans(&chars)?.into_iter().map(|(token, _, _)| token).collect());
+}
+
+// the tokens with the range of characters they were read from
+fn spans(chars: &[char]) -> Result<Vec<(Token, usize, usize)>> {
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            // exponent like 1e-3, but not a unit starting with e
+            if i + 1 < chars.len()
+                && (chars[i] == 'e' || chars[i] == 'E')
+                && (chars[i + 1].is_ascii_digit()
+                    || ((chars[i + 1] == '-' || chars[i + 1] == '+')
+                        && i + 2 < chars.len()
+                        && chars[i + 2].is_ascii_digit()))
+            {
+                i += 2;
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+            }
+            let literal: String = chars[start..i].iter().collect();
+            let value = literal
+                .parse::<f64>()
+                .map_err(|_| Error::Expression(format!("invalid number '{}'", literal)))?;
+            tokens.push((Token::Number(value), start, i));
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push((Token::Identifier(chars[start..i].iter().collect()), start, i));
+        } else {
+            let token = match c {
+                '+' | '-' | '*' | '/' | '^' => Token::Operator(c),
+                '(' => Token::Open,
+                ')' => Token::Close,
+                ',' => Token::Comma,
+                '"' => Token::Inch,
+                _ => return Err(Error::Expression(format!("unexpected '{}'", c))),
+            };
+            i += 1;
+            tokens.push((token, start, i));
+        }
+    }
+    return Ok(tokens);
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    index: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.index).cloned();
+        self.index += 1;
+        return token;
+    }
+
+    fn sum(&mut self) -> Result<Expression> {
+        let mut left = self.product()?;
+        while let Some(Token::Operator(c)) = self.peek() {
+            let operator = match c {
+                '+' => Operator::Add,
+                '-' => Operator::Sub,
+                _ => break,
+            };
+            self.index += 1;
+            let right = self.product()?;
+            left = Expression::Binary(operator, Box::new(left), Box::new(right));
+        }
+        return Ok(left);
+    }
+
+    fn product(&mut self) -> Result<Expression> {
+        let mut left = self.unary()?;
+        while let Some(Token::Operator(c)) = self.peek() {
+            let operator = match c {
+                '*' => Operator::Mul,
+                '/' => Operator::Div,
+                _ => break,
+            };
+            self.index += 1;
+            let right = self.unary()?;
+            left = Expression::Binary(operator, Box::new(left), Box::new(right));
+        }
+        return Ok(left);
+    }
+
+    fn unary(&mut self) -> Result<Expression> {
+        match self.peek() {
+            Some(Token::Operator('-')) => {
+                self.index += 1;
+                Ok(Expression::Negate(Box::new(self.unary()?)))
+            }
+            Some(Token::Operator('+')) => {
+                self.index += 1;
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<Expression> {
+        let base = self.primary()?;
+        if let Some(Token::Operator('^')) = self.peek() {
+            self.index += 1;
+            let exponent = self.unary()?;
+            return Ok(Expression::Binary(
+                Operator::Pow,
+                Box::new(base),
+                Box::new(exponent),
+            ));
+        }
+        return Ok(base);
+    }
+
+    fn primary(&mut self) -> Result<Expression> {
+        match self.next() {
+            Some(Token::Number(value)) => match self.peek() {
+                Some(Token::Inch) => {
+                    self.index += 1;
+                    Ok(Expression::Length(value, Unit::Inch))
+                }
+                Some(Token::Identifier(name)) => match Unit::from_suffix(name) {
+                    Some(unit) => {
+                        self.index += 1;
+                        Ok(Expression::Length(value, unit))
+                    }
+                    None => Err(Error::Expression(format!("unknown unit '{}'", name))),
+                },
+                _ => Ok(Expression::Number(value)),
+            },
+            Some(Token::Identifier(name)) => {
+                if let Some(Token::Open) = self.peek() {
+                    self.index += 1;
+                    let mut args = vec![];
+                    if let Some(Token::Close) = self.peek() {
+                        self.index += 1;
+                        return Ok(Expression::Call(name, args));
+                    }
+                    loop {
+                        args.push(self.sum()?);
+                        match self.next() {
+                            Some(Token::Comma) => {}
+                            Some(Token::Close) => break,
+                            _ => return Err(Error::Expression("missing ')'".into())),
+                        }
+                    }
+                    return Ok(Expression::Call(name, args));
+                }
+                Ok(Expression::Identifier(name))
+            }
+            Some(Token::Open) => {
+                let inner = self.sum()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(inner),
+                    _ => Err(Error::Expression("missing ')'".into())),
+                }
+            }
+            Some(token) => Err(Error::Expression(format!("unexpected {:?}", token))),
+            None => Err(Error::Expression("unexpected end of input".into())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evaluate(text: &str, default: Unit) -> Result<Quantity> {
+        let lookup = |name: &str| match name {
+            "width" => Some(Quantity::mm(40.)),
+            "count" => Some(Quantity::scalar(3.)),
+            _ => None,
+        };
+        return Expression::parse(text)?.evaluate(default, &lookup);
+    }
+
+    fn length(text: &str) -> f64 {
+        return evaluate(text, Unit::Millimeter).unwrap().to_mm(Unit::Millimeter).unwrap();
+    }
+
+    fn error(text: &str) -> String {
+        return match evaluate(text, Unit::Millimeter) {
+            Err(Error::Expression(message)) => message,
+            other => panic!("{} gave {:?}", text, other),
+        };
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn mixed_units() {
+        assert!(close(length("25.4mm + 1in/2"), 38.1));
+        assert!(close(length("1cm + 2\" - 0.01m"), 50.8));
+        assert!(close(length("width / count + 1mm"), 40. / 3. + 1.));
+        // plain numbers next to lengths are in the default unit
+        let inches = evaluate("1in + 1", Unit::Inch).unwrap();
+        assert_eq!(inches.length, 1);
+        assert!(close(inches.to_mm(Unit::Inch).unwrap(), 50.8));
+        assert!(close(evaluate("2", Unit::Centimeter).unwrap().to_mm(Unit::Centimeter).unwrap(), 20.));
+        // an area divided by a length is a length again
+        assert!(close(length("10mm * 4mm / 2mm"), 20.));
+    }
+
+    #[test]
+    fn precedence_and_unary_minus() {
+        assert!(close(length("2 + 3 * 4"), 14.));
+        assert!(close(length("(2 + 3) * 4"), 20.));
+        assert!(close(length("10 - 4 - 3"), 3.));
+        assert!(close(length("12 / 3 / 2"), 2.));
+        assert!(close(length("2 ^ 3 ^ 2"), 512.));
+        assert!(close(length("2 * 3 ^ 2"), 18.));
+        assert!(close(length("-2 ^ 2"), -4.));
+        assert!(close(length("2 ^ -1"), 0.5));
+        assert!(close(length("-(3mm - 5mm)"), 2.));
+        assert!(close(length("4 * -2"), -8.));
+        assert!(close(length("--3 + +2"), 5.));
+        assert!(close(length("1e-3m + 1.5e1mm"), 16.));
+        assert!(close(length("max(1mm, -2mm, 3mm) + abs(-2)"), 5.));
+    }
+
+    #[test]
+    fn dimension_errors() {
+        let area = evaluate("2mm * 3mm", Unit::Millimeter).unwrap();
+        assert_eq!(area.length, 2);
+        assert!(area.to_mm(Unit::Millimeter).is_err());
+        assert!(evaluate("1mm / 1mm", Unit::Millimeter).unwrap().to_scalar().is_ok());
+        assert_eq!(error("1mm * 1mm + 1mm"), "incompatible units");
+        assert_eq!(error("min(1mm, 2)"), "incompatible units");
+        assert_eq!(error("sin(1mm)"), "expected a value without unit");
+        assert_eq!(error("2 ^ 1mm"), "expected a value without unit");
+        assert_eq!(error("2mm ^ 0.5"), "lengths need whole exponents");
+        assert_eq!(error("sqrt(2mm)"), "sqrt of a length");
+        assert_eq!(error("2mm ^ 1e10"), "the power of the unit is too large");
+    }
+
+    #[test]
+    fn division_by_zero() {
+        assert_eq!(error("1 / 0"), "division by zero");
+        assert_eq!(error("5mm / (2mm - 2mm)"), "division by zero");
+        assert_eq!(error("width / (count - 3)"), "division by zero");
+    }
+
+    #[test]
+    fn malformed_input() {
+        for text in ["", "1 +", "(1 + 2", "1 + 2)", "2 3", "1..2", "3 # 4", "max(1, 2", "5 furlong", "* 2"] {
+            assert!(evaluate(text, Unit::Millimeter).is_err(), "{} was accepted", text);
+        }
+        assert_eq!(error("depth * 2"), "unknown parameter 'depth'");
+        assert_eq!(error("foo(1)"), "unknown function 'foo'");
+        assert_eq!(error("sin(1, 2)"), "sin expects one argument");
+        assert_eq!(error("1 + 2)"), "unexpected Close");
+    }
+
+    #[test]
+    fn renamed_references() {
+        let rename = |text: &str| rename_parameter(text, "w", "width").unwrap();
+        assert_eq!(rename("w"), "width");
+        assert_eq!(rename("2*w + max(w,  3mm)"), "2*width + max(width,  3mm)");
+        assert_eq!(rename("w2 + _w + ww"), "w2 + _w + ww");
+        // functions and units with the same name stay
+        assert_eq!(rename("w(1) + 5 w"), "w(1) + 5 w");
+        assert_eq!(rename_parameter("2in + in", "in", "x").unwrap(), "2in + x");
+        assert!(rename_parameter("w # 2", "w", "x").is_err());
+    }
+}
@@ -1,2 +1,4 @@
 pub mod basics;
-pub mod result;
\ No newline at end of file
+pub mod result;
+pub mod units;
+pub mod expression;
\ No newline at end of file
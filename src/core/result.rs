@@ -1,7 +1,26 @@
+#[derive(Debug, Clone, PartialEq)]
 pub enum Error {
     NotFound,
-    WrongType
+    WrongType,
+    Expression(String),
+    Cycle(Vec<String>),
+    Geometry(String),
+    File(String),
+    Memory(String),
 }
 
-pub type Result<T> = std::result::Result<T, Error>;
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::NotFound => write!(f, "not found"),
+            Error::WrongType => write!(f, "wrong type"),
+            Error::Expression(message) => write!(f, "{}", message),
+            Error::Cycle(names) => write!(f, "circular dependency {}", names.join(" -> ")),
+            Error::Geometry(message) => write!(f, "{}", message),
+            Error::File(message) => write!(f, "{}", message),
+            Error::Memory(message) => write!(f, "{}", message),
+        }
+    }
+}
 
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,91 @@
+use super::{expression::Expression, result::Result};
+
+// world coordinates are stored in millimeters, units only change input and display
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Unit {
+    Millimeter,
+    Centimeter,
+    Meter,
+    Inch,
+}
+
+impl Unit {
+    pub const ALL: [Unit; 4] = [Unit::Millimeter, Unit::Centimeter, Unit::Meter, Unit::Inch];
+
+    pub fn to_mm(&self) -> f64 {
+        match self {
+            Unit::Millimeter => 1.0,
+            Unit::Centimeter => 10.0,
+            Unit::Meter => 1000.0,
+            Unit::Inch => 25.4,
+        }
+    }
+
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            Unit::Millimeter => "mm",
+            Unit::Centimeter => "cm",
+            Unit::Meter => "m",
+            Unit::Inch => "in",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Unit::Millimeter => "Millimeter",
+            Unit::Centimeter => "Centimeter",
+            Unit::Meter => "Meter",
+            Unit::Inch => "Inch",
+        }
+    }
+
+    pub fn from_suffix(suffix: &str) -> Option<Unit> {
+        match suffix {
+            "mm" => Some(Unit::Millimeter),
+            "cm" => Some(Unit::Centimeter),
+            "m" => Some(Unit::Meter),
+            "in" | "inch" | "\"" => Some(Unit::Inch),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UnitSystem {
+    pub unit: Unit,
+    pub precision: usize,
+}
+
+impl Default for UnitSystem {
+    fn default() -> Self {
+        Self {
+            unit: Unit::Millimeter,
+            precision: 2,
+        }
+    }
+}
+
+impl UnitSystem {
+    pub fn to_unit(&self, mm: f32) -> f64 {
+        mm as f64 / self.unit.to_mm()
+    }
+
+    pub fn from_unit(&self, value: f64) -> f32 {
+        (value * self.unit.to_mm()) as f32
+    }
+
+    pub fn format(&self, mm: f32) -> String {
+        format!("{:.*} {}", self.precision, self.to_unit(mm), self.unit.suffix())
+    }
+
+    pub fn format_value(&self, mm: f32) -> String {
+        format!("{:.*}", self.precision, self.to_unit(mm))
+    }
+
+    // parses an expression like "25.4mm + 1in/2", plain numbers use the project unit
+    pub fn parse(&self, text: &str) -> Result<f32> {
+        let expression = Expression::parse(text)?;
+        let value = expression.evaluate(self.unit, &|_| None)?;
+        return Ok(value.to_mm(self.unit)? as f32);
+    }
+}
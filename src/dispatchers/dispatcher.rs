@@ -1,15 +1,16 @@
 use std::sync::Mutex;
 
 use crate::project::ProjectState;
+use crate::ui::expression_edit::expression_edit;
 use crate::{components::point, core::result::Result};
 use async_std::channel::Sender;
-use egui::{Context, Ui};
+use egui::{Context, Id, Ui};
 use glam::Vec3;
 use uuid::Uuid;
 
 use super::{
-    oncedispatcher::OnceDispatcher, uidispatcher::UiDispatcher,
-    waitfordispatcher::WaitForDispatcher, windowdispatcher::WindowDispatcher,
+    oncedispatcher::OnceDispatcher, placedispatcher::PlaceDispatcher, tooldispatcher::ToolDispatcher,
+    uidispatcher::UiDispatcher, waitfordispatcher::WaitForDispatcher, windowdispatcher::WindowDispatcher,
 };
 
 pub enum DispatcherEvent {
@@ -20,11 +21,18 @@ pub enum DispatcherEvent {
 pub type Disp = Box<dyn Dispatcher + Send + Sync>;
 
 pub trait Dispatcher {
+    // called on every dispatcher before any of them interacts in a frame
+    fn prepare(&mut self, _: &mut ProjectState) {}
     fn interact(&mut self, state: &mut ProjectState) {}
     fn draw2d(&mut self, state: &mut ProjectState, ctx: &Context) {}
     fn draw2d_nointeract(&mut self, state: &mut ProjectState, ui: &mut Ui) {}
+    // called when the dispatcher is removed or replaced
+    fn close(&mut self, _: &mut ProjectState) {}
 }
 
+// there is only one drawing tool at a time, starting another one replaces it
+pub const TOOL_ID: Uuid = Uuid::from_u128(1);
+
 pub async fn wait_for_ui<T>(
     sender: Sender<DispatcherEvent>,
     func: impl Fn(&mut ProjectState, &mut Ui) -> Option<Result<T>> + 'static + Send + Sync,
@@ -84,10 +92,41 @@ pub fn window(
     return dispatcher_id;
 }
 
+pub fn tool(
+    sender: Sender<DispatcherEvent>,
+    clicks: Option<usize>,
+    func: impl Fn(&mut ProjectState, &[Vec3]) + 'static + Send + Sync,
+) {
+    let dispatcher = ToolDispatcher::new(TOOL_ID, sender.clone(), clicks, func);
+    let _ = sender.try_send(DispatcherEvent::Add(TOOL_ID, Box::new(dispatcher)));
+}
+
+// takes the place of the drawing tool
+pub fn place(sender: Sender<DispatcherEvent>, func: impl Fn(&mut ProjectState, Vec3) + 'static + Send + Sync) {
+    let dispatcher = PlaceDispatcher::new(TOOL_ID, sender.clone(), func);
+    let _ = sender.try_send(DispatcherEvent::Add(TOOL_ID, Box::new(dispatcher)));
+}
+
+pub fn stop_tool(sender: Sender<DispatcherEvent>) {
+    remove(sender, TOOL_ID);
+}
+
 pub fn remove(sender: Sender<DispatcherEvent>, dispatcher_id: Uuid) {
     let _ = sender.try_send(DispatcherEvent::Remove(dispatcher_id));
 }
 
+pub async fn prompt_length(sender: Sender<DispatcherEvent>, label: &'static str) -> Result<f32> {
+    wait_for_ui(sender, move |state, ui| {
+        ui.label(label);
+        let units = state.units.clone();
+        expression_edit(ui, Id::new(("prompt", label)), String::new(), |text| {
+            units.parse(text)
+        })
+        .map(|(_, value)| Ok(value))
+    })
+    .await
+}
+
 pub async fn select_point(
     sender: Sender<DispatcherEvent>, create_point : bool,
     func: impl Fn(&mut ProjectState, usize, Vec3) -> () + 'static + Send + Sync,
@@ -3,3 +3,5 @@ pub mod waitfordispatcher;
 pub mod windowdispatcher;
 pub mod oncedispatcher;
 pub mod uidispatcher;
+pub mod tooldispatcher;
+pub mod placedispatcher;
@@ -0,0 +1,44 @@
+use crate::project::ProjectState;
+use async_std::channel::Sender;
+use egui::{Context, Key};
+use glam::Vec3;
+use uuid::Uuid;
+
+use super::dispatcher::{Dispatcher, DispatcherEvent};
+
+// moves something along with the mouse on the sketch plane until a click, escape leaves it at
+// the last position
+pub struct PlaceDispatcher {
+    pub id: Uuid,
+    pub sender: Sender<DispatcherEvent>,
+    pub func: Box<dyn Fn(&mut ProjectState, Vec3) + Send + Sync>,
+}
+
+impl Dispatcher for PlaceDispatcher {
+    fn interact(&mut self, state: &mut ProjectState) {
+        (self.func)(state, state.camera.world_mouse_position);
+        if state.is_mouse_clicked {
+            let _ = self.sender.try_send(DispatcherEvent::Remove(self.id));
+        }
+    }
+
+    fn draw2d(&mut self, _: &mut ProjectState, ctx: &Context) {
+        if ctx.input(|i| i.key_pressed(Key::Escape)) {
+            let _ = self.sender.try_send(DispatcherEvent::Remove(self.id));
+        }
+    }
+}
+
+impl PlaceDispatcher {
+    pub fn new(
+        id: Uuid,
+        sender: Sender<DispatcherEvent>,
+        func: impl Fn(&mut ProjectState, Vec3) + 'static + Send + Sync,
+    ) -> Self {
+        Self {
+            id,
+            sender,
+            func: Box::new(func),
+        }
+    }
+}
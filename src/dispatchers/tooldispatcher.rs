@@ -0,0 +1,193 @@
+use crate::{
+    component_collection::{ComponentArray, ComponentCollection},
+    components::component::{ComponentFlags, ComponentType, IComponent},
+    project::ProjectState,
+};
+use async_std::channel::Sender;
+use egui::{Context, Key};
+use glam::Vec3;
+use uuid::Uuid;
+
+use super::dispatcher::{Dispatcher, DispatcherEvent};
+
+// lengths of the arrays a tool can add to
+#[derive(Clone, Copy)]
+struct Lengths {
+    points: usize,
+    lines: usize,
+    beziers: usize,
+    circles: usize,
+    arcs: usize,
+    ellipses: usize,
+    nurbs: usize,
+    nurbs_controls: usize,
+    nurbs_knots: usize,
+}
+
+impl Lengths {
+    fn take(components: &ComponentCollection) -> Lengths {
+        Lengths {
+            points: components.points.array.len(),
+            lines: components.lines.array.len(),
+            beziers: components.beziers.array.len(),
+            circles: components.circles.array.len(),
+            arcs: components.arcs.array.len(),
+            ellipses: components.ellipses.array.len(),
+            nurbs: components.nurbs.array.len(),
+            nurbs_controls: components.nurbs_controls.array.len(),
+            nurbs_knots: components.nurbs_knots.array.len(),
+        }
+    }
+}
+
+// drops the components from start to end, the tool runs after the other dispatchers so the preview is
+// normally the end of the array, components added outside of them in the meantime keep their indices
+// and the preview is only deleted
+fn discard<T>(array: &mut ComponentArray<T>, start: usize, end: usize) {
+    if array.array.len() == end {
+        array.truncate(start);
+        return;
+    }
+    for index in start..end.min(array.array.len()) {
+        array.update(index, |c| c.flags |= ComponentFlags::Deleted as i32);
+    }
+}
+
+// the components the tool built for the last mouse position
+struct Preview {
+    start: Lengths,
+    end: Lengths,
+}
+
+impl Preview {
+    fn remove(&self, components: &mut ComponentCollection) {
+        let (s, e) = (&self.start, &self.end);
+        discard(&mut components.points, s.points, e.points);
+        discard(&mut components.lines, s.lines, e.lines);
+        discard(&mut components.beziers, s.beziers, e.beziers);
+        discard(&mut components.circles, s.circles, e.circles);
+        discard(&mut components.arcs, s.arcs, e.arcs);
+        discard(&mut components.ellipses, s.ellipses, e.ellipses);
+        discard(&mut components.nurbs, s.nurbs, e.nurbs);
+        discard(&mut components.nurbs_controls, s.nurbs_controls, e.nurbs_controls);
+        discard(&mut components.nurbs_knots, s.nurbs_knots, e.nurbs_knots);
+
+        // the preview can't stay selected
+        let lengths = Lengths::take(components);
+        components.selected.retain(|c| {
+            let length = match c.ctype {
+                ComponentType::Point => lengths.points,
+                ComponentType::Line => lengths.lines,
+                ComponentType::Bezier => lengths.beziers,
+                ComponentType::Circle => lengths.circles,
+                ComponentType::Arc => lengths.arcs,
+                ComponentType::Ellipse => lengths.ellipses,
+                ComponentType::Nurbs => lengths.nurbs,
+                _ => usize::MAX,
+            };
+            (c.index as usize) < length
+        });
+    }
+}
+
+// the nearest hovered point the tool didn't add itself, clicks on it take its position so the
+// tools share the point instead of adding one on top of it
+fn hovered_point(state: &ProjectState, below: usize) -> Option<Vec3> {
+    let points = &state.components.points.array;
+    return state
+        .components
+        .hovers
+        .iter()
+        .filter(|h| h.ctype == ComponentType::Point && (h.index as usize) < below)
+        .filter_map(|h| {
+            let point = points.get(h.index as usize).filter(|p| !p.is_deleted())?;
+            Some((h.distance, point.data.position))
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, position)| position);
+}
+
+// collects clicks on the sketch plane, the components are rebuilt from the clicks and the
+// mouse position every frame and kept once enough clicks were made
+pub struct ToolDispatcher {
+    pub id: Uuid,
+    pub sender: Sender<DispatcherEvent>,
+    // None for tools that take clicks until enter is pressed
+    pub clicks: Option<usize>,
+    pub positions: Vec<Vec3>,
+    pub func: Box<dyn Fn(&mut ProjectState, &[Vec3]) + Send + Sync>,
+    preview: Option<Preview>,
+    // the position of the existing point under the mouse, taken before the preview is removed
+    hovered: Option<Vec3>,
+    finish: bool,
+}
+
+impl Dispatcher for ToolDispatcher {
+    // the preview goes before the other dispatchers run, whatever they add can't end up behind it
+    fn prepare(&mut self, state: &mut ProjectState) {
+        let below = match &self.preview {
+            Some(preview) => preview.start.points,
+            None => state.components.points.array.len(),
+        };
+        self.hovered = hovered_point(state, below);
+        if let Some(preview) = self.preview.take() {
+            preview.remove(&mut state.components);
+        }
+    }
+
+    fn interact(&mut self, state: &mut ProjectState) {
+        let mouse = self.hovered.unwrap_or(state.camera.world_mouse_position);
+        if state.is_mouse_clicked {
+            self.positions.push(mouse);
+            self.finish |= Some(self.positions.len()) == self.clicks;
+        }
+        if std::mem::take(&mut self.finish) && !self.positions.is_empty() {
+            (self.func)(state, &self.positions);
+            // the tool stays active for the next curve
+            self.positions.clear();
+            return;
+        }
+
+        let mut positions = self.positions.clone();
+        positions.push(mouse);
+        let start = Lengths::take(&state.components);
+        (self.func)(state, &positions);
+        let end = Lengths::take(&state.components);
+        self.preview = Some(Preview { start, end });
+    }
+
+    fn draw2d(&mut self, _: &mut ProjectState, ctx: &Context) {
+        if ctx.input(|i| i.key_pressed(Key::Escape)) {
+            let _ = self.sender.try_send(DispatcherEvent::Remove(self.id));
+        }
+        if self.clicks.is_none() && ctx.input(|i| i.key_pressed(Key::Enter)) {
+            self.finish = true;
+        }
+    }
+
+    fn close(&mut self, state: &mut ProjectState) {
+        if let Some(preview) = self.preview.take() {
+            preview.remove(&mut state.components);
+        }
+    }
+}
+
+impl ToolDispatcher {
+    pub fn new(
+        id: Uuid,
+        sender: Sender<DispatcherEvent>,
+        clicks: Option<usize>,
+        func: impl Fn(&mut ProjectState, &[Vec3]) + 'static + Send + Sync,
+    ) -> Self {
+        Self {
+            id,
+            sender,
+            clicks,
+            positions: vec![],
+            func: Box::new(func),
+            preview: None,
+            hovered: None,
+            finish: false,
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use glam::Vec3;
+
+use crate::{
+    camera::{Camera, Projection},
+    core::{basics::Plane, units::UnitSystem},
+};
+
+pub struct Grid {
+    pub visible: bool,
+    pub snap: bool,
+    pub subdivisions: u32,
+    pub lines_per_view: f32,
+    pub fade: bool,
+
+    pub major: f32,
+    pub minor: f32,
+    pub minor_alpha: f32,
+    pub extent: f32,
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self {
+            visible: true,
+            snap: false,
+            subdivisions: 10,
+            lines_per_view: 4.0,
+            fade: true,
+            major: 1.0,
+            minor: 0.1,
+            minor_alpha: 1.0,
+            extent: 100.0,
+        }
+    }
+}
+
+impl Grid {
+    // spacing steps in powers of ten of the project unit
+    pub fn update(&mut self, camera: &Camera, units: &UnitSystem) {
+        let unit = units.unit.to_mm() as f32;
+        // visible height of the view around the camera target
+        let view = match camera.projection {
+            Projection::Perspective => camera.perspective_distance,
+            Projection::Orthographics => camera.orthographics_fovy,
+        }
+        .abs()
+        .max(1e-6);
+
+        let exponent = (view / self.lines_per_view / unit).log10();
+        let step = exponent.ceil();
+
+        self.major = 10f32.powf(step) * unit;
+        self.minor = self.major / self.subdivisions.max(1) as f32;
+        // minor lines fade out shortly before the next power of ten takes over
+        self.minor_alpha = (step - exponent).clamp(0.0, 1.0);
+        self.extent = if self.fade {
+            view * 20.0
+        } else {
+            view * 1000.0
+        };
+    }
+
+    pub fn snap(&self, plane: &Plane, position: Vec3) -> Vec3 {
+        let (u, v) = plane.basis();
+        let local = position - plane.position;
+
+        let x = (local.dot(u) / self.minor).round() * self.minor;
+        let y = (local.dot(v) / self.minor).round() * self.minor;
+
+        return plane.position + u * x + v * y;
+    }
+
+    pub fn uniforms(&self, target: Vec3) -> [f32; 8] {
+        [
+            self.major,
+            self.minor,
+            self.minor_alpha,
+            self.extent,
+            target.x,
+            target.y,
+            target.z,
+            if self.fade { 1.0 } else { 0.0 },
+        ]
+    }
+}
@@ -0,0 +1,578 @@
+use std::f32::consts::PI;
+use std::fmt::Write;
+
+use glam::Vec2;
+
+use crate::core::result::{Error, Result};
+
+use super::sketch::{Shape, SketchBuilder, SketchPoint, SketchShape};
+
+// everything is written in millimeters, f32 values are printed with the shortest text that
+// parses back to the same value
+fn pair(dxf: &mut String, code: i32, value: impl std::fmt::Display) {
+    let _ = write!(dxf, "{}\n{}\n", code, value);
+}
+
+fn point(dxf: &mut String, code: i32, p: Vec2) {
+    pair(dxf, code, p.x);
+    pair(dxf, code + 10, p.y);
+    pair(dxf, code + 20, 0.);
+}
+
+fn entity(dxf: &mut String, kind: &str, subclass: &str, layer: &str) {
+    pair(dxf, 0, kind);
+    pair(dxf, 100, "AcDbEntity");
+    pair(dxf, 8, layer);
+    pair(dxf, 100, subclass);
+}
+
+fn degrees(angle: f32) -> f32 {
+    angle.to_degrees().rem_euclid(360.)
+}
+
+fn write_shape(dxf: &mut String, shape: &Shape, layer: &str) {
+    match shape {
+        Shape::Line(a, b) => {
+            entity(dxf, "LINE", "AcDbLine", layer);
+            point(dxf, 10, *a);
+            point(dxf, 11, *b);
+        }
+        Shape::Circle { center, radius } => {
+            entity(dxf, "CIRCLE", "AcDbCircle", layer);
+            point(dxf, 10, *center);
+            pair(dxf, 40, radius);
+        }
+        Shape::Arc {
+            center,
+            radius,
+            start,
+            sweep,
+        } => {
+            // dxf arcs always run counter clockwise
+            let (from, to) = if *sweep > 0. { (*start, start + sweep) } else { (start + sweep, *start) };
+            entity(dxf, "ARC", "AcDbCircle", layer);
+            point(dxf, 10, *center);
+            pair(dxf, 40, radius);
+            pair(dxf, 100, "AcDbArc");
+            pair(dxf, 50, degrees(from));
+            pair(dxf, 51, degrees(to));
+        }
+        Shape::Ellipse {
+            center,
+            major,
+            ratio,
+            start,
+            sweep,
+        } => {
+            // the ratio can't be above 1, the axes swap and the angles start a quarter later
+            let (major, ratio, start) = match *ratio > 1. {
+                true => (major.perp() * *ratio, 1. / ratio, start - PI / 2.),
+                false => (*major, *ratio, *start),
+            };
+            let (from, to) = if *sweep > 0. { (start, start + sweep) } else { (start + sweep, start) };
+            entity(dxf, "ELLIPSE", "AcDbEllipse", layer);
+            point(dxf, 10, *center);
+            point(dxf, 11, major);
+            pair(dxf, 40, ratio);
+            pair(dxf, 41, from);
+            pair(dxf, 42, to);
+        }
+        Shape::Bezier(p) => {
+            entity(dxf, "SPLINE", "AcDbSpline", layer);
+            pair(dxf, 70, 8);
+            pair(dxf, 71, 3);
+            pair(dxf, 72, 8);
+            pair(dxf, 73, 4);
+            pair(dxf, 74, 0);
+            for knot in [0., 0., 0., 0., 1., 1., 1., 1.] {
+                pair(dxf, 40, knot);
+            }
+            for control in p.iter() {
+                point(dxf, 10, *control);
+            }
+        }
+        Shape::Polyline(points) => {
+            entity(dxf, "LWPOLYLINE", "AcDbPolyline", layer);
+            pair(dxf, 90, points.len());
+            pair(dxf, 70, 0);
+            for p in points.iter() {
+                pair(dxf, 10, p.x);
+                pair(dxf, 20, p.y);
+            }
+        }
+    }
+}
+
+// an ascii drawing of the shapes and free points in the frame of the sketch plane
+pub fn export(shapes: &[SketchShape], points: &[SketchPoint]) -> Result<String> {
+    if shapes.is_empty() && points.is_empty() {
+        return Err(Error::Geometry("there is nothing to export on the sketch plane".into()));
+    }
+    let mut dxf = String::new();
+    pair(&mut dxf, 0, "SECTION");
+    pair(&mut dxf, 2, "HEADER");
+    pair(&mut dxf, 9, "$ACADVER");
+    pair(&mut dxf, 1, "AC1015");
+    pair(&mut dxf, 9, "$INSUNITS");
+    pair(&mut dxf, 70, 4);
+    pair(&mut dxf, 0, "ENDSEC");
+
+    pair(&mut dxf, 0, "SECTION");
+    pair(&mut dxf, 2, "TABLES");
+    pair(&mut dxf, 0, "TABLE");
+    pair(&mut dxf, 2, "LAYER");
+    // layer 0 is always there
+    let mut layers = vec!["0"];
+    for layer in shapes.iter().map(|s| &s.layer).chain(points.iter().map(|p| &p.layer)) {
+        if !layers.contains(&layer.as_str()) {
+            layers.push(layer);
+        }
+    }
+    pair(&mut dxf, 70, layers.len());
+    for layer in layers {
+        pair(&mut dxf, 0, "LAYER");
+        pair(&mut dxf, 2, layer);
+        pair(&mut dxf, 70, 0);
+        pair(&mut dxf, 62, 7);
+        pair(&mut dxf, 6, "CONTINUOUS");
+    }
+    pair(&mut dxf, 0, "ENDTAB");
+    pair(&mut dxf, 0, "ENDSEC");
+
+    pair(&mut dxf, 0, "SECTION");
+    pair(&mut dxf, 2, "ENTITIES");
+    for shape in shapes.iter() {
+        write_shape(&mut dxf, &shape.shape, &shape.layer);
+    }
+    for p in points.iter() {
+        entity(&mut dxf, "POINT", "AcDbPoint", &p.layer);
+        point(&mut dxf, 10, p.position);
+    }
+    pair(&mut dxf, 0, "ENDSEC");
+    pair(&mut dxf, 0, "EOF");
+    return Ok(dxf);
+}
+
+struct Entity {
+    kind: String,
+    codes: Vec<(i32, String)>,
+}
+
+impl Entity {
+    fn values(&self, code: i32) -> impl Iterator<Item = f32> + '_ {
+        self.codes
+            .iter()
+            .filter(move |(c, _)| *c == code)
+            .filter_map(|(_, v)| v.parse().ok())
+    }
+
+    fn value(&self, code: i32) -> f32 {
+        self.values(code).next().unwrap_or(0.)
+    }
+
+    fn text(&self, code: i32) -> Option<&str> {
+        self.codes.iter().find(|(c, _)| *c == code).map(|(_, v)| v.as_str())
+    }
+
+    fn point(&self, code: i32) -> Vec2 {
+        Vec2::new(self.value(code), self.value(code + 10))
+    }
+
+    // arcs, circles and polylines are given in their object coordinate system, drawn from
+    // below it is mirrored in x
+    fn mirrored(&self) -> bool {
+        self.values(230).next().map_or(false, |z| z < 0.)
+    }
+}
+
+fn pairs(text: &str) -> Result<Vec<(i32, String)>> {
+    let lines: Vec<&str> = text.lines().collect();
+    return lines
+        .chunks(2)
+        .filter(|pair| pair.len() == 2)
+        .map(|pair| {
+            let code = pair[0].trim().parse().map_err(|_| Error::File(format!("invalid group code '{}'", pair[0])))?;
+            Ok((code, pair[1].trim().to_string()))
+        })
+        .collect();
+}
+
+// millimeters per drawing unit from $INSUNITS, unitless drawings are taken as millimeters
+fn units(pairs: &[(i32, String)]) -> f32 {
+    let Some(i) = pairs.iter().position(|(code, value)| *code == 9 && value == "$INSUNITS") else {
+        return 1.;
+    };
+    match pairs.get(i + 1).map(|(_, v)| v.as_str()) {
+        Some("1") => 25.4,
+        Some("2") => 304.8,
+        Some("5") => 10.,
+        Some("6") => 1000.,
+        _ => 1.,
+    }
+}
+
+fn entities(pairs: &[(i32, String)]) -> Vec<Entity> {
+    let mut entities = vec![];
+    let mut section = "";
+    let mut current: Option<Entity> = None;
+    for (i, (code, value)) in pairs.iter().enumerate() {
+        if *code == 2 && i > 0 && pairs[i - 1] == (0, "SECTION".into()) {
+            section = value;
+        }
+        if *code == 0 && value == "ENDSEC" {
+            section = "";
+        }
+        if *code == 0 {
+            entities.extend(current.take());
+            if section == "ENTITIES" && value != "SECTION" && value != "ENDSEC" {
+                current = Some(Entity {
+                    kind: value.clone(),
+                    codes: vec![],
+                });
+            }
+        } else if let Some(entity) = current.as_mut() {
+            entity.codes.push((*code, value.clone()));
+        }
+    }
+    return entities;
+}
+
+// what an entity adds, read completely before anything is added
+enum Item {
+    Shape(Shape),
+    Point(Vec2),
+    Nurbs {
+        controls: Vec<Vec2>,
+        weights: Vec<f32>,
+        knots: Vec<f32>,
+        degree: u32,
+    },
+}
+
+// the whole turn for arcs that start where they end
+fn sweep(from: f32, to: f32) -> f32 {
+    let sweep = (to - from).rem_euclid(2. * PI);
+    return if sweep < 1e-6 { 2. * PI } else { sweep };
+}
+
+// polyline vertices with the bulge to the next one, the bulge is the tangent of a quarter of
+// the arc angle
+fn polyline(entity: &Entity, scale: f32) -> Vec<Item> {
+    let mirror = if entity.mirrored() { -1. } else { 1. };
+    let mut vertices: Vec<(Vec2, f32)> = vec![];
+    for (code, value) in entity.codes.iter() {
+        let value: f32 = value.parse().unwrap_or(0.);
+        match (code, vertices.last_mut()) {
+            (10, _) => vertices.push((Vec2::new(value * mirror * scale, 0.), 0.)),
+            (20, Some(vertex)) => vertex.0.y = value * scale,
+            (42, Some(vertex)) => vertex.1 = value * mirror,
+            _ => {}
+        }
+    }
+    let closed = (entity.value(70) as i32 & 1) == 1;
+    let count = if closed { vertices.len() } else { vertices.len().saturating_sub(1) };
+    return (0..count)
+        .map(|i| {
+            let (a, bulge) = vertices[i];
+            let b = vertices[(i + 1) % vertices.len()].0;
+            if bulge.abs() < 1e-9 || a.distance(b) < 1e-9 {
+                return Item::Shape(Shape::Line(a, b));
+            }
+            let angle = 4. * bulge.atan();
+            let chord = b - a;
+            let center = (a + b) / 2. + chord.perp().normalize() * (chord.length() / 2.) / (angle / 2.).tan();
+            let start = a - center;
+            Item::Shape(Shape::Arc {
+                center,
+                radius: start.length(),
+                start: start.y.atan2(start.x),
+                sweep: angle,
+            })
+        })
+        .collect();
+}
+
+// cubic pieces through the fit points of splines that come without control points
+fn fit_beziers(points: &[Vec2]) -> Vec<Item> {
+    let n = points.len();
+    let tangent = |i: usize| (points[(i + 1).min(n - 1)] - points[i.saturating_sub(1)]) / 2.;
+    return (0..n.saturating_sub(1))
+        .map(|i| {
+            let (a, b) = (points[i], points[i + 1]);
+            Item::Shape(Shape::Bezier([a, a + tangent(i) / 3., b - tangent(i + 1) / 3., b]))
+        })
+        .collect();
+}
+
+// splines become nurbs, the shaders draw them up to degree 7
+fn spline(entity: &Entity, scale: f32) -> Result<Vec<Item>> {
+    let degree = entity.value(71) as u32;
+    let knots: Vec<f32> = entity.values(40).collect();
+    let controls: Vec<Vec2> = entity
+        .values(10)
+        .zip(entity.values(20))
+        .map(|(x, y)| Vec2::new(x, y) * scale)
+        .collect();
+    let mut weights: Vec<f32> = entity.values(41).collect();
+    if weights.len() != controls.len() {
+        weights = vec![1.; controls.len()];
+    }
+    let valid = controls.len() > degree as usize && knots.len() == controls.len() + degree as usize + 1;
+    if valid && (1..=7).contains(&degree) {
+        return Ok(vec![Item::Nurbs {
+            controls,
+            weights,
+            knots,
+            degree,
+        }]);
+    }
+
+    // approximated through the fit points when the control points can't be used
+    let fit: Vec<Vec2> = entity
+        .values(11)
+        .zip(entity.values(21))
+        .map(|(x, y)| Vec2::new(x, y) * scale)
+        .collect();
+    if fit.len() < 2 {
+        return Err(Error::File("a spline can't be read, it has no usable control or fit points".into()));
+    }
+    return Ok(fit_beziers(&fit));
+}
+
+fn items(entity: &Entity, scale: f32) -> Result<Vec<Item>> {
+    let mirror = Vec2::new(if entity.mirrored() { -1. } else { 1. }, 1.);
+    let shape = match entity.kind.as_str() {
+        "LINE" => Shape::Line(entity.point(10) * scale, entity.point(11) * scale),
+        "POINT" => return Ok(vec![Item::Point(entity.point(10) * scale)]),
+        "CIRCLE" => Shape::Circle {
+            center: entity.point(10) * mirror * scale,
+            radius: entity.value(40) * scale,
+        },
+        "ARC" => {
+            let (from, to) = (entity.value(50).to_radians(), entity.value(51).to_radians());
+            let sweep = sweep(from, to);
+            let (start, sweep) = if entity.mirrored() { (PI - from, -sweep) } else { (from, sweep) };
+            Shape::Arc {
+                center: entity.point(10) * mirror * scale,
+                radius: entity.value(40) * scale,
+                start,
+                sweep,
+            }
+        }
+        // ellipses are in world coordinates, only the direction of the angles follows the normal
+        "ELLIPSE" => {
+            let (from, to) = (entity.value(41), entity.value(42));
+            let sweep = sweep(from, to);
+            let (start, sweep) = if entity.mirrored() { (-from, -sweep) } else { (from, sweep) };
+            Shape::Ellipse {
+                center: entity.point(10) * scale,
+                major: entity.point(11) * scale,
+                ratio: entity.value(40),
+                start,
+                sweep,
+            }
+        }
+        "LWPOLYLINE" => return Ok(polyline(entity, scale)),
+        "SPLINE" => return spline(entity, scale),
+        _ => return Ok(vec![]),
+    };
+    return Ok(vec![Item::Shape(shape)]);
+}
+
+struct Drawing {
+    // the items of the entities that aren't on the skipped layers with their layer
+    items: Vec<(String, Item)>,
+    // the names of all layers of the entities
+    layers: Vec<String>,
+}
+
+fn read(text: &str, skipped: &[String]) -> Result<Drawing> {
+    let pairs = pairs(text)?;
+    let scale = units(&pairs);
+    let mut layers: Vec<String> = vec![];
+    let mut placed = vec![];
+    for entity in entities(&pairs) {
+        let layer = entity.text(8).unwrap_or("0").to_string();
+        if !skipped.contains(&layer) {
+            placed.extend(items(&entity, scale)?.into_iter().map(|item| (layer.clone(), item)));
+        }
+        if !layers.contains(&layer) {
+            layers.push(layer);
+        }
+    }
+    return Ok(Drawing { items: placed, layers });
+}
+
+// adds the entities of the drawing onto the plane of the builder on their layers, entities on
+// the skipped layers are left out, returns the names of all layers of the entities
+pub fn import(text: &str, builder: &mut SketchBuilder<'_>, skipped: &[String]) -> Result<Vec<String>> {
+    let drawing = read(text, skipped)?;
+    for (layer, item) in drawing.items {
+        builder.layer = builder.components.layers.index(&layer);
+        match item {
+            Item::Shape(shape) => builder.shape(&shape),
+            Item::Point(p) => {
+                builder.point(p);
+            }
+            Item::Nurbs {
+                controls,
+                weights,
+                knots,
+                degree,
+            } => builder.nurbs(&controls, &weights, &knots, degree),
+        }
+    }
+    builder.layer = 0;
+    return Ok(drawing.layers);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sketch_shape(shape: Shape, layer: &str) -> SketchShape {
+        SketchShape {
+            shape,
+            style: 0,
+            layer: layer.into(),
+        }
+    }
+
+    fn shape(item: &Item) -> &Shape {
+        match item {
+            Item::Shape(shape) => shape,
+            _ => panic!("expected a shape"),
+        }
+    }
+
+    fn assert_close(a: Vec2, b: Vec2) {
+        assert!(a.distance(b) < 1e-4, "{} != {}", a, b);
+    }
+
+    // arcs are written counter clockwise, so the ends may swap
+    fn assert_same_arc(a: &Shape, b: &Shape) {
+        let (a0, a1, b0, b1) = (a.position(0.), a.position(1.), b.position(0.), b.position(1.));
+        if a0.distance(b0) < a0.distance(b1) {
+            assert_close(a0, b0);
+            assert_close(a1, b1);
+        } else {
+            assert_close(a0, b1);
+            assert_close(a1, b0);
+        }
+        assert_close(a.position(0.5), b.position(0.5));
+    }
+
+    #[test]
+    fn round_trip() {
+        let line = Shape::Line(Vec2::new(1.25, -3.5), Vec2::new(10.123457, 7.3));
+        let circle = Shape::Circle {
+            center: Vec2::new(4.1, 2.2),
+            radius: 3.3,
+        };
+        let arc = Shape::Arc {
+            center: Vec2::new(-2., 0.5),
+            radius: 1.75,
+            start: 0.3,
+            sweep: 1.2,
+        };
+        let clockwise = Shape::Arc {
+            center: Vec2::new(6., -1.),
+            radius: 0.6,
+            start: 2.,
+            sweep: -2.5,
+        };
+        let controls = [Vec2::new(0., 0.), Vec2::new(1.5, 2.), Vec2::new(3.25, -1.), Vec2::new(5., 0.1)];
+        let polyline = Shape::Polyline(vec![Vec2::new(0., 0.), Vec2::new(2., 0.), Vec2::new(2., 3.)]);
+        let shapes = vec![
+            sketch_shape(line.clone(), "0"),
+            sketch_shape(circle.clone(), "cut"),
+            sketch_shape(arc.clone(), "cut"),
+            sketch_shape(clockwise.clone(), "cut"),
+            sketch_shape(Shape::Bezier(controls), "0"),
+            sketch_shape(polyline, "engrave"),
+        ];
+        let points = vec![SketchPoint {
+            position: Vec2::new(0.7, -0.9),
+            layer: "marks".into(),
+        }];
+
+        let text = export(&shapes, &points).unwrap();
+        let Drawing { items, layers } = read(&text, &[]).unwrap();
+        assert_eq!(layers, vec!["0", "cut", "engrave", "marks"]);
+        let table: Vec<String> = pairs(&text)
+            .unwrap()
+            .windows(2)
+            .filter(|p| p[0] == (0, "LAYER".into()) && p[1].0 == 2)
+            .map(|p| p[1].1.clone())
+            .collect();
+        assert_eq!(table, layers);
+
+        let layer_of: Vec<&str> = items.iter().map(|(layer, _)| layer.as_str()).collect();
+        assert_eq!(layer_of, vec!["0", "cut", "cut", "cut", "0", "engrave", "engrave", "marks"]);
+
+        assert_eq!(shape(&items[0].1), &line);
+        assert_eq!(shape(&items[1].1), &circle);
+        match (shape(&items[2].1), &arc) {
+            (Shape::Arc { center, radius, .. }, Shape::Arc { center: c, radius: r, .. }) => {
+                assert_eq!((center, radius), (c, r));
+            }
+            _ => panic!("expected an arc"),
+        }
+        assert_same_arc(shape(&items[2].1), &arc);
+        assert_same_arc(shape(&items[3].1), &clockwise);
+        match &items[4].1 {
+            Item::Nurbs {
+                controls: read,
+                weights,
+                knots,
+                degree,
+            } => {
+                assert_eq!(read.as_slice(), controls.as_slice());
+                assert_eq!(weights, &vec![1.; 4]);
+                assert_eq!(knots, &vec![0., 0., 0., 0., 1., 1., 1., 1.]);
+                assert_eq!(*degree, 3);
+            }
+            _ => panic!("expected a spline"),
+        }
+        assert_eq!(shape(&items[5].1), &Shape::Line(Vec2::new(0., 0.), Vec2::new(2., 0.)));
+        assert_eq!(shape(&items[6].1), &Shape::Line(Vec2::new(2., 0.), Vec2::new(2., 3.)));
+        match &items[7].1 {
+            Item::Point(p) => assert_eq!(*p, Vec2::new(0.7, -0.9)),
+            _ => panic!("expected a point"),
+        }
+    }
+
+    #[test]
+    fn polyline_bulge_round_trip() {
+        // a half circle below the chord from (0, 0) to (10, 0), then a straight segment up
+        let text = "0\nSECTION\n2\nENTITIES\n0\nLWPOLYLINE\n8\nholes\n90\n3\n70\n0\n\
+                    10\n0\n20\n0\n42\n1\n10\n10\n20\n0\n10\n10\n20\n5\n0\nENDSEC\n0\nEOF\n";
+        let items = read(text, &[]).unwrap().items;
+        assert_eq!(items.len(), 2);
+        let arc = shape(&items[0].1);
+        assert_close(arc.position(0.), Vec2::new(0., 0.));
+        assert_close(arc.position(0.5), Vec2::new(5., -5.));
+        assert_close(arc.position(1.), Vec2::new(10., 0.));
+        assert_eq!(shape(&items[1].1), &Shape::Line(Vec2::new(10., 0.), Vec2::new(10., 5.)));
+
+        let shapes: Vec<SketchShape> =
+            items.iter().map(|(layer, item)| sketch_shape(shape(item).clone(), layer)).collect();
+        let again = read(&export(&shapes, &[]).unwrap(), &[]).unwrap();
+        assert_eq!(again.layers, vec!["holes"]);
+        let again = again.items;
+        assert_same_arc(shape(&again[0].1), arc);
+        assert_eq!(shape(&again[1].1), shape(&items[1].1));
+    }
+
+    #[test]
+    fn skipped_layers() {
+        let shapes = vec![
+            sketch_shape(Shape::Line(Vec2::ZERO, Vec2::X), "keep"),
+            sketch_shape(Shape::Line(Vec2::ZERO, Vec2::Y), "skip"),
+        ];
+        let Drawing { items, layers } = read(&export(&shapes, &[]).unwrap(), &["skip".into()]).unwrap();
+        assert_eq!(layers, vec!["keep", "skip"]);
+        assert_eq!(items.len(), 1);
+        assert_eq!(shape(&items[0].1), &Shape::Line(Vec2::ZERO, Vec2::X));
+    }
+}
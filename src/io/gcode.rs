@@ -0,0 +1,1004 @@
+use std::f32::consts::PI;
+use std::fmt::Write;
+
+use glam::Vec2;
+
+use crate::{
+    core::result::{Error, Result},
+    topology::{
+        profile::signed_area,
+        tessellation::{arc_segments, tessellate},
+    },
+};
+
+use super::{
+    sketch::{Shape, SketchShape, MERGE_TOLERANCE},
+    svg::number,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Machine {
+    Router,
+    Laser,
+    Pen,
+}
+
+impl Machine {
+    pub const ALL: [Machine; 3] = [Machine::Router, Machine::Laser, Machine::Pen];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Machine::Router => "Router or mill",
+            Machine::Laser => "Laser",
+            Machine::Pen => "Pen plotter",
+        }
+    }
+
+    // the commands that switch the tool on and off
+    pub fn commands(&self) -> (&'static str, &'static str) {
+        match self {
+            Machine::Router => ("M3", "M5"),
+            Machine::Laser => ("M4", "M5"),
+            Machine::Pen => ("M3 S90", "M5"),
+        }
+    }
+}
+
+// where the tool runs on closed profiles, the drawn shapes are the finished parts for outside
+// cuts and the openings for inside cuts
+#[derive(Clone, Copy, PartialEq)]
+pub enum CutSide {
+    On,
+    Outside,
+    Inside,
+}
+
+impl CutSide {
+    pub const ALL: [CutSide; 3] = [CutSide::On, CutSide::Outside, CutSide::Inside];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CutSide::On => "On the line",
+            CutSide::Outside => "Outside",
+            CutSide::Inside => "Inside",
+        }
+    }
+}
+
+// lengths in mm and feeds in mm per minute, z zero is the top of the stock on the sketch plane
+#[derive(Clone)]
+pub struct GcodeSettings {
+    pub machine: Machine,
+    pub feed: f32,
+    pub plunge_feed: f32,
+    // spindle speed or laser power, the s word
+    pub speed: f32,
+    // the cutter or the laser kerf
+    pub tool_diameter: f32,
+    pub side: CutSide,
+    pub safe_height: f32,
+    pub depth: f32,
+    pub step_down: f32,
+    pub tool_on: String,
+    pub tool_off: String,
+    // laser and pen repeat every path, the router steps down instead
+    pub passes: u32,
+}
+
+impl Default for GcodeSettings {
+    fn default() -> Self {
+        let (on, off) = Machine::Router.commands();
+        GcodeSettings {
+            machine: Machine::Router,
+            feed: 600.,
+            plunge_feed: 200.,
+            speed: 10000.,
+            tool_diameter: 3.175,
+            side: CutSide::On,
+            safe_height: 5.,
+            depth: 3.,
+            step_down: 1.,
+            tool_on: on.into(),
+            tool_off: off.into(),
+            passes: 1,
+        }
+    }
+}
+
+// counter clockwise arcs have a positive sweep
+#[derive(Clone, Copy, Debug)]
+enum Segment {
+    Line(Vec2, Vec2),
+    Arc {
+        center: Vec2,
+        radius: f32,
+        start: f32,
+        sweep: f32,
+    },
+}
+
+impl Segment {
+    fn start(&self) -> Vec2 {
+        match self {
+            Segment::Line(a, _) => *a,
+            Segment::Arc {
+                center, radius, start, ..
+            } => *center + Vec2::from_angle(*start) * *radius,
+        }
+    }
+
+    fn end(&self) -> Vec2 {
+        match self {
+            Segment::Line(_, b) => *b,
+            Segment::Arc {
+                center,
+                radius,
+                start,
+                sweep,
+            } => *center + Vec2::from_angle(start + sweep) * *radius,
+        }
+    }
+
+    fn tangent(&self, angle: f32) -> Vec2 {
+        match self {
+            Segment::Line(a, b) => (*b - *a).normalize(),
+            Segment::Arc { sweep, .. } => Vec2::from_angle(angle).perp() * sweep.signum(),
+        }
+    }
+
+    fn start_tangent(&self) -> Vec2 {
+        match self {
+            Segment::Arc { start, .. } => self.tangent(*start),
+            Segment::Line(..) => self.tangent(0.),
+        }
+    }
+
+    fn end_tangent(&self) -> Vec2 {
+        match self {
+            Segment::Arc { start, sweep, .. } => self.tangent(start + sweep),
+            Segment::Line(..) => self.tangent(0.),
+        }
+    }
+
+    fn length(&self) -> f32 {
+        match self {
+            Segment::Line(a, b) => a.distance(*b),
+            Segment::Arc { radius, sweep, .. } => radius * sweep.abs(),
+        }
+    }
+
+    fn reversed(&self) -> Segment {
+        match *self {
+            Segment::Line(a, b) => Segment::Line(b, a),
+            Segment::Arc {
+                center,
+                radius,
+                start,
+                sweep,
+            } => Segment::Arc {
+                center,
+                radius,
+                start: start + sweep,
+                sweep: -sweep,
+            },
+        }
+    }
+
+    // the parallel segment the distance to the left of the direction of travel
+    fn offset(&self, distance: f32) -> Option<Segment> {
+        match *self {
+            Segment::Line(a, b) => {
+                let normal = (b - a).normalize().perp() * distance;
+                return Some(Segment::Line(a + normal, b + normal));
+            }
+            Segment::Arc {
+                center,
+                radius,
+                start,
+                sweep,
+            } => {
+                let radius = radius - distance * sweep.signum();
+                if radius <= MERGE_TOLERANCE {
+                    return None;
+                }
+                return Some(Segment::Arc {
+                    center,
+                    radius,
+                    start,
+                    sweep,
+                });
+            }
+        }
+    }
+
+    fn with_start(&self, p: Vec2) -> Segment {
+        match *self {
+            Segment::Line(_, b) => Segment::Line(p, b),
+            Segment::Arc {
+                center,
+                radius,
+                start,
+                sweep,
+            } => {
+                let angle = (p - center).y.atan2((p - center).x);
+                Segment::Arc {
+                    center,
+                    radius,
+                    start: angle,
+                    sweep: closest_sweep(start + sweep - angle, sweep),
+                }
+            }
+        }
+    }
+
+    fn with_end(&self, p: Vec2) -> Segment {
+        match *self {
+            Segment::Line(a, _) => Segment::Line(a, p),
+            Segment::Arc {
+                center,
+                radius,
+                start,
+                sweep,
+            } => {
+                let angle = (p - center).y.atan2((p - center).x);
+                Segment::Arc {
+                    center,
+                    radius,
+                    start,
+                    sweep: closest_sweep(angle - start, sweep),
+                }
+            }
+        }
+    }
+
+    // true if trimming turned the segment around
+    fn flipped(&self, original: &Segment) -> bool {
+        match (self, original) {
+            (Segment::Line(a, b), Segment::Line(c, d)) => (*b - *a).dot(*d - *c) <= 0.,
+            (Segment::Arc { sweep, .. }, Segment::Arc { sweep: original, .. }) => sweep * original <= 0.,
+            _ => true,
+        }
+    }
+
+    // the points along the segment without its end
+    fn polygon(&self, tolerance: f32, points: &mut Vec<Vec2>) {
+        match *self {
+            Segment::Line(a, _) => points.push(a),
+            Segment::Arc {
+                center,
+                radius,
+                start,
+                sweep,
+            } => {
+                let segments = arc_segments(radius, sweep, tolerance);
+                for i in 0..segments {
+                    let angle = start + sweep * i as f32 / segments as f32;
+                    points.push(center + Vec2::from_angle(angle) * radius);
+                }
+            }
+        }
+    }
+}
+
+// the angle that differs from the old sweep by whole turns the least
+fn closest_sweep(angle: f32, sweep: f32) -> f32 {
+    let turns = ((sweep - angle) / (2. * PI)).round();
+    return angle + turns * 2. * PI;
+}
+
+fn segments(shape: &Shape, tolerance: f32) -> Vec<Segment> {
+    let lines = |points: Vec<Vec2>| {
+        points
+            .windows(2)
+            .filter(|w| w[0].distance(w[1]) > MERGE_TOLERANCE)
+            .map(|w| Segment::Line(w[0], w[1]))
+            .collect()
+    };
+    match shape {
+        Shape::Line(a, b) => return lines(vec![*a, *b]),
+        Shape::Circle { center, radius } => {
+            return vec![Segment::Arc {
+                center: *center,
+                radius: *radius,
+                start: 0.,
+                sweep: 2. * PI,
+            }]
+        }
+        Shape::Arc {
+            center,
+            radius,
+            start,
+            sweep,
+        } => {
+            return vec![Segment::Arc {
+                center: *center,
+                radius: *radius,
+                start: *start,
+                sweep: *sweep,
+            }]
+        }
+        Shape::Polyline(points) => return lines(points.clone()),
+        Shape::Bezier(_) | Shape::Ellipse { .. } => {
+            let parameters = tessellate(|t| shape.position(t).extend(0.), tolerance);
+            return lines(parameters.iter().map(|t| shape.position(*t)).collect());
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Path {
+    segments: Vec<Segment>,
+}
+
+impl Path {
+    fn start(&self) -> Vec2 {
+        self.segments[0].start()
+    }
+
+    fn end(&self) -> Vec2 {
+        self.segments[self.segments.len() - 1].end()
+    }
+
+    fn is_closed(&self) -> bool {
+        self.start().distance(self.end()) <= MERGE_TOLERANCE
+    }
+
+    fn reversed(&self) -> Path {
+        Path {
+            segments: self.segments.iter().rev().map(|s| s.reversed()).collect(),
+        }
+    }
+
+    fn length(&self) -> f32 {
+        self.segments.iter().map(|s| s.length()).sum()
+    }
+
+    fn polygon(&self, tolerance: f32) -> Vec<Vec2> {
+        let mut points = vec![];
+        for segment in self.segments.iter() {
+            segment.polygon(tolerance, &mut points);
+        }
+        return points;
+    }
+}
+
+// joins the shapes that share end points into paths, branches start new paths
+fn chain(shapes: &[SketchShape], tolerance: f32) -> Vec<Path> {
+    let mut pieces: Vec<Option<Vec<Segment>>> = shapes
+        .iter()
+        .map(|s| segments(&s.shape, tolerance))
+        .filter(|s| !s.is_empty())
+        .map(Some)
+        .collect();
+    let mut paths = vec![];
+    for i in 0..pieces.len() {
+        let Some(segments) = pieces[i].take() else {
+            continue;
+        };
+        let mut path = Path { segments };
+        for _ in 0..2 {
+            while !path.is_closed() {
+                let end = path.end();
+                let next = pieces.iter().enumerate().find_map(|(j, p)| {
+                    let p = p.as_ref()?;
+                    if p[0].start().distance(end) <= MERGE_TOLERANCE {
+                        return Some((j, false));
+                    } else if p[p.len() - 1].end().distance(end) <= MERGE_TOLERANCE {
+                        return Some((j, true));
+                    }
+                    return None;
+                });
+                let Some((j, backwards)) = next else {
+                    break;
+                };
+                let mut next = Path {
+                    segments: pieces[j].take().unwrap(),
+                };
+                if backwards {
+                    next = next.reversed();
+                }
+                path.segments.extend(next.segments);
+            }
+            // grow the other end the same way
+            path = path.reversed();
+        }
+        paths.push(path);
+    }
+    return paths;
+}
+
+// the intersections of the full lines and circles behind two segments
+fn intersections(a: &Segment, b: &Segment) -> Vec<Vec2> {
+    let line_circle = |p: Vec2, q: Vec2, center: Vec2, radius: f32| {
+        let d = (q - p).normalize();
+        let t = (center - p).dot(d);
+        let closest = p + d * t;
+        let h = radius * radius - closest.distance_squared(center);
+        if h < 0. {
+            return vec![];
+        }
+        let h = h.sqrt();
+        return vec![closest - d * h, closest + d * h];
+    };
+    match (*a, *b) {
+        (Segment::Line(p, q), Segment::Line(r, s)) => {
+            let (d, e) = (q - p, s - r);
+            let denominator = d.perp_dot(e);
+            if denominator.abs() < 1e-9 {
+                return vec![];
+            }
+            return vec![p + d * ((r - p).perp_dot(e) / denominator)];
+        }
+        (Segment::Line(p, q), Segment::Arc { center, radius, .. })
+        | (Segment::Arc { center, radius, .. }, Segment::Line(p, q)) => return line_circle(p, q, center, radius),
+        (
+            Segment::Arc {
+                center: c0, radius: r0, ..
+            },
+            Segment::Arc {
+                center: c1, radius: r1, ..
+            },
+        ) => {
+            let d = c0.distance(c1);
+            if d < 1e-9 || d > r0 + r1 || d < (r0 - r1).abs() {
+                return vec![];
+            }
+            let a = (r0 * r0 - r1 * r1 + d * d) / (2. * d);
+            let h = (r0 * r0 - a * a).max(0.).sqrt();
+            let direction = (c1 - c0) / d;
+            let middle = c0 + direction * a;
+            return vec![middle + direction.perp() * h, middle - direction.perp() * h];
+        }
+    }
+}
+
+// moves a closed path the distance to its left, concave corners are trimmed and convex ones are
+// rounded around the original corner
+fn offset(path: &Path, distance: f32, tolerance: f32) -> Result<Path> {
+    let too_large = || Error::Geometry("the tool is too large for a closed profile".into());
+    let mut original = path.segments.clone();
+    loop {
+        let n = original.len();
+        let mut moved = original
+            .iter()
+            .map(|s| s.offset(distance))
+            .collect::<Option<Vec<Segment>>>()
+            .ok_or_else(too_large)?;
+        // the corners of the path, segments that were left out leave a gap
+        let corners: Vec<Option<Vec2>> = (0..n)
+            .map(|i| {
+                let (end, start) = (original[i].end(), original[(i + 1) % n].start());
+                (end.distance(start) <= MERGE_TOLERANCE).then_some(end)
+            })
+            .collect();
+        let mut rounded = vec![false; n];
+        for i in 0..n {
+            let j = (i + 1) % n;
+            let (a, b) = (moved[i].end(), moved[j].start());
+            if a.distance(b) <= MERGE_TOLERANCE {
+                continue;
+            }
+            let corner = corners[i].unwrap_or((a + b) / 2.);
+            let turn = original[i].end_tangent().perp_dot(original[j].start_tangent());
+            if turn > 1e-6 || corners[i].is_none() {
+                let closest = intersections(&moved[i], &moved[j])
+                    .into_iter()
+                    .min_by(|p, q| p.distance(corner).total_cmp(&q.distance(corner)));
+                if let Some(p) = closest {
+                    moved[i] = moved[i].with_end(p);
+                    moved[j] = moved[j].with_start(p);
+                    continue;
+                }
+            }
+            rounded[i] = turn < -1e-6 || original[i].end_tangent().dot(original[j].start_tangent()) < 0.;
+        }
+        // segments shorter than the trim at a concave corner vanish from the offset
+        let flipped: Vec<bool> = (0..n)
+            .map(|i| moved[i].flipped(&original[i].offset(distance).unwrap()))
+            .collect();
+        if flipped.iter().any(|f| *f) {
+            if flipped.iter().filter(|f| !**f).count() < 2 {
+                return Err(too_large());
+            }
+            original = (0..n).filter(|i| !flipped[*i]).map(|i| original[i]).collect();
+            continue;
+        }
+        let mut segments = vec![];
+        for i in 0..n {
+            segments.push(moved[i]);
+            let j = (i + 1) % n;
+            let (a, b) = (moved[i].end(), moved[j].start());
+            if a.distance(b) <= MERGE_TOLERANCE {
+                continue;
+            }
+            let corner = corners[i].unwrap_or((a + b) / 2.);
+            let start = (a - corner).y.atan2((a - corner).x);
+            let end = (b - corner).y.atan2((b - corner).x);
+            let sweep = -(start - end).rem_euclid(2. * PI);
+            // small turns of tessellated curves stay straight
+            if !rounded[i] || corners[i].is_none() || distance * (1. - (sweep / 2.).cos()) <= tolerance {
+                segments.push(Segment::Line(a, b));
+            } else {
+                segments.push(Segment::Arc {
+                    center: corner,
+                    radius: distance,
+                    start,
+                    sweep,
+                });
+            }
+        }
+        return Ok(Path { segments });
+    }
+}
+
+// narrow parts make offsets cross themselves or each other, the tool would cut into a profile
+fn gouges(path: &Path, polygons: &[Option<Vec<Vec2>>], limit: f32) -> bool {
+    return path.segments.iter().any(|s| {
+        let p = s.end();
+        polygons
+            .iter()
+            .flatten()
+            .any(|polygon| polygon_distance(polygon, p) < limit)
+    });
+}
+
+fn polygon_distance(polygon: &[Vec2], point: Vec2) -> f32 {
+    let n = polygon.len();
+    let mut distance = f32::MAX;
+    for i in 0..n {
+        let a = polygon[i];
+        let ab = polygon[(i + 1) % n] - a;
+        let t = ((point - a).dot(ab) / ab.length_squared().max(1e-12)).clamp(0., 1.);
+        distance = distance.min(point.distance(a + ab * t));
+    }
+    return distance;
+}
+
+fn contains(polygon: &[Vec2], point: Vec2) -> bool {
+    let mut inside = false;
+    let n = polygon.len();
+    for i in 0..n {
+        let a = polygon[i];
+        let b = polygon[(i + 1) % n];
+        if (a.y > point.y) != (b.y > point.y) && point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x {
+            inside = !inside;
+        }
+    }
+    return inside;
+}
+
+// a path to cut and the paths inside of it that have to be cut first, so parts stay in place
+struct Toolpath {
+    path: Path,
+    inner: Vec<usize>,
+}
+
+fn toolpaths(paths: Vec<Path>, settings: &GcodeSettings, tolerance: f32) -> Result<Vec<Toolpath>> {
+    let polygons: Vec<Option<Vec<Vec2>>> = paths
+        .iter()
+        .map(|p| p.is_closed().then(|| p.polygon(tolerance)))
+        .collect();
+    let areas: Vec<f32> = polygons
+        .iter()
+        .map(|p| p.as_ref().map_or(0., |p| signed_area(p)))
+        .collect();
+    let inside = |i: usize, j: usize| match &polygons[j] {
+        Some(outer) => i != j && areas[i].abs() < areas[j].abs() && contains(outer, paths[i].start()),
+        None => false,
+    };
+    let radius = settings.tool_diameter / 2.;
+    let mut toolpaths = vec![];
+    for (i, path) in paths.iter().enumerate() {
+        let inner = (0..paths.len()).filter(|j| inside(*j, i)).collect();
+        let mut path = path.clone();
+        if polygons[i].is_some() && settings.side != CutSide::On && settings.machine != Machine::Pen {
+            // holes in parts alternate with parts in holes
+            let depth = (0..paths.len()).filter(|j| inside(i, *j)).count();
+            let waste_outside = (settings.side == CutSide::Outside) == (depth % 2 == 0);
+            // outside clockwise and inside counter clockwise is climb milling with a clockwise
+            // spindle, the waste is always on the left
+            if (areas[i] < 0.) != waste_outside {
+                path = path.reversed();
+            }
+            if radius > 0. {
+                path = offset(&path, radius, tolerance)?;
+                if gouges(&path, &polygons, radius - 2. * tolerance - MERGE_TOLERANCE) {
+                    return Err(Error::Geometry("the tool is too large for a closed profile".into()));
+                }
+            }
+        }
+        toolpaths.push(Toolpath { path, inner });
+    }
+    return Ok(toolpaths);
+}
+
+// nearest neighbour ordering, open paths may run backwards and closed ones start at any corner
+fn order(toolpaths: Vec<Toolpath>, passes: usize) -> Vec<Path> {
+    let mut done = vec![false; toolpaths.len()];
+    let mut ordered = vec![];
+    let mut position = Vec2::ZERO;
+    for _ in 0..toolpaths.len() {
+        let mut best: Option<(f32, usize, Path)> = None;
+        for (i, toolpath) in toolpaths.iter().enumerate() {
+            if done[i] || toolpath.inner.iter().any(|j| !done[*j]) {
+                continue;
+            }
+            let path = &toolpath.path;
+            let candidates: Vec<Path> = if path.is_closed() {
+                let k = (0..path.segments.len())
+                    .min_by(|a, b| {
+                        let a = path.segments[*a].start().distance(position);
+                        let b = path.segments[*b].start().distance(position);
+                        a.total_cmp(&b)
+                    })
+                    .unwrap();
+                let mut segments = path.segments.clone();
+                segments.rotate_left(k);
+                vec![Path { segments }]
+            } else {
+                vec![path.clone(), path.reversed()]
+            };
+            for candidate in candidates {
+                let distance = candidate.start().distance(position);
+                if best.as_ref().map_or(true, |(d, _, _)| distance < *d) {
+                    best = Some((distance, i, candidate));
+                }
+            }
+        }
+        // nesting can't form a cycle, the smallest remaining path is always free
+        let Some((_, i, path)) = best else {
+            break;
+        };
+        done[i] = true;
+        position = match path.is_closed() || passes % 2 == 1 {
+            true => path.end(),
+            false => path.start(),
+        };
+        ordered.push(path);
+    }
+    return ordered;
+}
+
+struct Program {
+    text: String,
+    feed: Option<f32>,
+    position: Vec2,
+    travel: f32,
+}
+
+impl Program {
+    fn line(&mut self, line: &str) {
+        self.text.push_str(line);
+        self.text.push('\n');
+    }
+
+    fn feed(&mut self, feed: f32) -> String {
+        if self.feed == Some(feed) {
+            return String::new();
+        }
+        self.feed = Some(feed);
+        return format!(" F{}", number(feed));
+    }
+
+    fn rapid(&mut self, p: Vec2) {
+        if p.distance(self.position) <= MERGE_TOLERANCE {
+            return;
+        }
+        self.travel += p.distance(self.position);
+        self.position = p;
+        self.line(&format!("G0 X{} Y{}", number(p.x), number(p.y)));
+    }
+
+    fn z(&mut self, code: &str, z: f32, feed: Option<f32>) {
+        let feed = feed.map_or(String::new(), |f| self.feed(f));
+        self.line(&format!("{} Z{}{}", code, number(z), feed));
+    }
+
+    fn cut(&mut self, segment: &Segment, feed: f32) {
+        match *segment {
+            Segment::Line(_, b) => {
+                let feed = self.feed(feed);
+                self.line(&format!("G1 X{} Y{}{}", number(b.x), number(b.y), feed));
+            }
+            Segment::Arc {
+                center,
+                radius,
+                start,
+                sweep,
+            } => {
+                // every arc is below half a turn so controllers can't read it the wrong way
+                let count = (sweep.abs() / (PI - 1e-3)).ceil().max(1.) as usize;
+                for i in 0..count {
+                    let from = center + Vec2::from_angle(start + sweep * i as f32 / count as f32) * radius;
+                    let to = center + Vec2::from_angle(start + sweep * (i + 1) as f32 / count as f32) * radius;
+                    let code = if sweep < 0. { "G2" } else { "G3" };
+                    let feed = self.feed(feed);
+                    self.line(&format!(
+                        "{} X{} Y{} I{} J{}{}",
+                        code,
+                        number(to.x),
+                        number(to.y),
+                        number(center.x - from.x),
+                        number(center.y - from.y),
+                        feed
+                    ));
+                }
+            }
+        }
+        self.position = segment.end();
+    }
+}
+
+// a program in millimeters and absolute coordinates in the frame of the sketch plane
+pub fn export(shapes: &[SketchShape], settings: &GcodeSettings, tolerance: f32) -> Result<String> {
+    let paths = chain(shapes, tolerance);
+    if paths.is_empty() {
+        return Err(Error::Geometry("there is nothing to export on the sketch plane".into()));
+    }
+    let router = settings.machine == Machine::Router;
+    let depths: Vec<f32> = match router {
+        true => {
+            let count = (settings.depth / settings.step_down.max(1e-3)).ceil().max(1.) as usize;
+            (1..=count).map(|i| -settings.depth * i as f32 / count as f32).collect()
+        }
+        false => vec![0.; settings.passes.max(1) as usize],
+    };
+    let paths = order(toolpaths(paths, settings, tolerance)?, depths.len());
+    let length: f32 = paths.iter().map(|p| p.length()).sum::<f32>() * depths.len() as f32;
+
+    let mut program = Program {
+        text: String::new(),
+        feed: None,
+        position: Vec2::ZERO,
+        travel: 0.,
+    };
+    program.line(&format!(
+        "({}, {} path(s), {} pass(es))",
+        settings.machine.name(),
+        paths.len(),
+        depths.len()
+    ));
+    program.line("G21 G90 G17");
+    match settings.machine {
+        Machine::Router => {
+            program.z("G0", settings.safe_height, None);
+            program.line(&format!("{} S{}", settings.tool_on, number(settings.speed)));
+        }
+        Machine::Laser => {
+            program.line(&settings.tool_off);
+            program.line(&format!("S{}", number(settings.speed)));
+        }
+        Machine::Pen => program.line(&settings.tool_off),
+    }
+    for path in paths.iter() {
+        program.rapid(path.start());
+        if !router {
+            program.line(&settings.tool_on);
+        }
+        for (pass, z) in depths.iter().enumerate() {
+            if router {
+                program.z("G1", *z, Some(settings.plunge_feed));
+            }
+            // open paths go back and forth instead of travelling to their start again
+            let backwards = !path.is_closed() && pass % 2 == 1;
+            match backwards {
+                true => path
+                    .reversed()
+                    .segments
+                    .iter()
+                    .for_each(|s| program.cut(s, settings.feed)),
+                false => path.segments.iter().for_each(|s| program.cut(s, settings.feed)),
+            }
+        }
+        match router {
+            true => program.z("G0", settings.safe_height, None),
+            false => program.line(&settings.tool_off),
+        }
+    }
+    if router {
+        program.line(&settings.tool_off);
+    }
+    program.rapid(Vec2::ZERO);
+    program.line("M2");
+    let mut text = String::new();
+    let _ = writeln!(
+        text,
+        "(cutting {} mm, travel {} mm)",
+        number(length),
+        number(program.travel)
+    );
+    return Ok(text + &program.text);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sketch_shape(shape: Shape) -> SketchShape {
+        SketchShape {
+            shape,
+            style: 0,
+            layer: "0".into(),
+        }
+    }
+
+    // counter clockwise
+    fn rectangle(min: Vec2, max: Vec2) -> Vec<SketchShape> {
+        let corners = [min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)];
+        return (0..4)
+            .map(|i| sketch_shape(Shape::Line(corners[i], corners[(i + 1) % 4])))
+            .collect();
+    }
+
+    fn router(side: CutSide, tool_diameter: f32) -> GcodeSettings {
+        GcodeSettings {
+            side,
+            tool_diameter,
+            depth: 1.,
+            step_down: 1.,
+            ..GcodeSettings::default()
+        }
+    }
+
+    // a line of the program with the position before it, arcs have their center
+    #[derive(Debug)]
+    struct Move {
+        code: String,
+        from: Vec2,
+        to: Vec2,
+        z: Option<f32>,
+        center: Option<Vec2>,
+    }
+
+    fn read_moves(gcode: &str) -> Vec<Move> {
+        let mut position = Vec2::ZERO;
+        let mut moves = vec![];
+        for line in gcode.lines().filter(|l| l.starts_with('G')) {
+            let code = line.split_whitespace().next().unwrap().to_string();
+            let word = |letter: char| {
+                line.split_whitespace()
+                    .find(|w| w.starts_with(letter))
+                    .map(|w| w[1..].parse::<f32>().unwrap())
+            };
+            if code == "G21" {
+                continue;
+            }
+            let to = Vec2::new(word('X').unwrap_or(position.x), word('Y').unwrap_or(position.y));
+            let center = word('I').map(|i| position + Vec2::new(i, word('J').unwrap()));
+            moves.push(Move {
+                code,
+                from: position,
+                to,
+                z: word('Z'),
+                center,
+            });
+            position = to;
+        }
+        return moves;
+    }
+
+    // the moves that cut along the sketch plane
+    fn cutting(moves: &[Move]) -> Vec<&Move> {
+        return moves.iter().filter(|m| m.code != "G0" && m.z.is_none()).collect();
+    }
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-3, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn outside_and_inside_offsets() {
+        let (min, max) = (Vec2::ZERO, Vec2::splat(40.));
+        let part = rectangle(min, max);
+
+        // outside the part the tool runs clockwise, its corners are rounded around the corners
+        let gcode = export(&part, &router(CutSide::Outside, 4.), 0.01).unwrap();
+        let moves = read_moves(&gcode);
+        let cuts = cutting(&moves);
+        assert!(!cuts.is_empty());
+        let mut area = 0.;
+        for cut in cuts.iter() {
+            let outside = cut.to - cut.to.clamp(min, max);
+            assert_close(outside.length(), 2.);
+            area += cut.from.perp_dot(cut.to) / 2.;
+            if let Some(center) = cut.center {
+                assert_eq!(cut.code, "G2");
+                assert!([min, max, Vec2::new(40., 0.), Vec2::new(0., 40.)].contains(&center));
+                assert_close(cut.from.distance(center), 2.);
+                assert_close(cut.to.distance(center), 2.);
+            }
+        }
+        assert_eq!(cuts.iter().filter(|c| c.code == "G2").count(), 4);
+        assert!(area < 0.);
+
+        // inside an opening it runs counter clockwise, the corners are sharp
+        let gcode = export(&part, &router(CutSide::Inside, 4.), 0.01).unwrap();
+        let moves = read_moves(&gcode);
+        let cuts = cutting(&moves);
+        let mut area = 0.;
+        for cut in cuts.iter() {
+            assert_eq!(cut.code, "G1");
+            let p = cut.to;
+            let edge = p.x.min(p.y).min(40. - p.x).min(40. - p.y);
+            assert_close(edge, 2.);
+            area += cut.from.perp_dot(cut.to) / 2.;
+        }
+        assert_close(area, 36. * 36.);
+    }
+
+    #[test]
+    fn arcs_follow_the_cut_direction() {
+        let hole = vec![sketch_shape(Shape::Circle {
+            center: Vec2::splat(10.),
+            radius: 5.,
+        })];
+        for (side, code, radius) in [(CutSide::Outside, "G2", 6.5), (CutSide::Inside, "G3", 3.5)] {
+            let gcode = export(&hole, &router(side, 3.), 0.01).unwrap();
+            let moves = read_moves(&gcode);
+            let cuts = cutting(&moves);
+            assert!(!cuts.is_empty());
+            for cut in cuts {
+                assert_eq!(cut.code, code);
+                assert_eq!(cut.center, Some(Vec2::splat(10.)));
+                assert_close(cut.to.distance(Vec2::splat(10.)), radius);
+            }
+        }
+    }
+
+    #[test]
+    fn tool_too_large_for_slot() {
+        let slot = rectangle(Vec2::ZERO, Vec2::new(20., 3.));
+        let result = export(&slot, &router(CutSide::Inside, 4.), 0.01);
+        assert!(matches!(result, Err(Error::Geometry(message)) if message.contains("too large")));
+        // a tool that fits the slot works
+        assert!(export(&slot, &router(CutSide::Inside, 2.), 0.01).is_ok());
+    }
+
+    #[test]
+    fn depth_passes() {
+        let line = vec![sketch_shape(Shape::Line(Vec2::new(5., 0.), Vec2::new(5., 10.)))];
+        let settings = GcodeSettings {
+            depth: 3.,
+            step_down: 1.2,
+            safe_height: 4.,
+            ..GcodeSettings::default()
+        };
+        let gcode = export(&line, &settings, 0.01).unwrap();
+        let moves = read_moves(&gcode);
+        let plunges: Vec<f32> = moves.iter().filter(|m| m.code == "G1").filter_map(|m| m.z).collect();
+        assert_eq!(plunges, vec![-1., -2., -3.]);
+        let lifts: Vec<f32> = moves.iter().filter(|m| m.code == "G0").filter_map(|m| m.z).collect();
+        assert_eq!(lifts, vec![4., 4.]);
+        // the open path goes back and forth
+        let ends: Vec<Vec2> = cutting(&moves).iter().map(|m| m.to).collect();
+        assert_eq!(ends, vec![Vec2::new(5., 10.), Vec2::new(5., 0.), Vec2::new(5., 10.)]);
+    }
+
+    #[test]
+    fn inner_paths_first() {
+        // a part with a hole that holds another part, the outer corner is next to the origin
+        let mut shapes = rectangle(Vec2::ZERO, Vec2::splat(100.));
+        shapes.extend(rectangle(Vec2::splat(10.), Vec2::splat(90.)));
+        shapes.extend(rectangle(Vec2::splat(40.), Vec2::splat(60.)));
+        shapes.extend(rectangle(Vec2::new(110., 0.), Vec2::new(120., 10.)));
+        let gcode = export(&shapes, &router(CutSide::On, 3.), 0.01).unwrap();
+        let mut starts: Vec<Vec2> = read_moves(&gcode)
+            .iter()
+            .filter(|m| m.code == "G0" && m.z.is_none())
+            .map(|m| m.to)
+            .collect();
+        // the last one returns to the origin
+        starts.pop();
+        let size = |p: Vec2| match p {
+            p if p.x > 100. => 10,
+            p if (40. ..=60.).contains(&p.x) && (40. ..=60.).contains(&p.y) => 20,
+            p if (10. ..=90.).contains(&p.x) && (10. ..=90.).contains(&p.y) => 80,
+            _ => 100,
+        };
+        let order: Vec<i32> = starts.iter().map(|p| size(*p)).collect();
+        // the separate part is cut whenever it is nearest, the nested ones from the inside out
+        let nested: Vec<i32> = order.iter().copied().filter(|s| *s != 10).collect();
+        assert_eq!(nested, vec![20, 80, 100]);
+        assert_eq!(order.len(), 4);
+    }
+}
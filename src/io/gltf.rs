@@ -0,0 +1,666 @@
+use std::{collections::HashMap, fmt::Write};
+
+use glam::{Mat3, Quat, Vec3};
+
+use crate::{
+    camera::{Camera, Projection},
+    component_collection::ComponentCollection,
+    components::{component::Component, style::Style},
+    core::result::{Error, Result},
+    rendering::theme::Theme,
+};
+
+use super::mesh::{polylines, solids, NamedMesh, Polyline};
+
+// gltf is in meters, the scene is in millimeters
+const SCALE: f32 = 0.001;
+// the color the mesh shader falls back to
+const MESH_COLOR: [f32; 4] = [0.7, 0.7, 0.75, 1.0];
+
+// the json of a scene without its buffer and the binary buffer its accessors point into
+pub struct Gltf {
+    pub json: String,
+    pub buffer: Vec<u8>,
+}
+
+impl Gltf {
+    // the buffer is embedded or the first chunk after the json when there is no uri
+    fn document(&self, uri: Option<String>) -> String {
+        let uri = uri.map_or(String::new(), |uri| format!(r#","uri":"{}""#, uri));
+        return format!(r#"{},"buffers":[{{"byteLength":{}{}}}]}}"#, self.json, self.buffer.len(), uri);
+    }
+}
+
+#[derive(Default)]
+struct Builder {
+    buffer: Vec<u8>,
+    views: Vec<String>,
+    accessors: Vec<String>,
+    materials: Vec<String>,
+    // (style, is a line) to material
+    material_index: HashMap<(u32, bool), usize>,
+}
+
+fn numbers(values: &[f32]) -> String {
+    values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(",")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl Builder {
+    // vec3 float accessor, positions need their bounds
+    fn accessor(&mut self, values: &[Vec3], bounds: bool) -> usize {
+        let offset = self.buffer.len();
+        for v in values {
+            for x in v.to_array() {
+                self.buffer.extend_from_slice(&x.to_le_bytes());
+            }
+        }
+        self.views.push(format!(
+            r#"{{"buffer":0,"byteOffset":{},"byteLength":{},"target":34962}}"#,
+            offset,
+            values.len() * 12
+        ));
+        let mut accessor = format!(
+            r#"{{"bufferView":{},"componentType":5126,"count":{},"type":"VEC3""#,
+            self.views.len() - 1,
+            values.len()
+        );
+        if bounds {
+            let min = values.iter().fold(Vec3::splat(f32::MAX), |a, b| a.min(*b));
+            let max = values.iter().fold(Vec3::splat(f32::MIN), |a, b| a.max(*b));
+            let _ = write!(accessor, r#","min":[{}],"max":[{}]"#, numbers(&min.to_array()), numbers(&max.to_array()));
+        }
+        accessor.push('}');
+        self.accessors.push(accessor);
+        return self.accessors.len() - 1;
+    }
+
+    // one material per style, styles without a color get the color they are drawn with
+    fn material(&mut self, styles: &[Component<Style>], theme: &Theme, style: u32, line: bool) -> usize {
+        if let Some(index) = self.material_index.get(&(style, line)) {
+            return *index;
+        }
+        let color = styles.get(style as usize).map(|s| s.data.color).unwrap_or([0.; 4]);
+        let color = match (color[3] > 0., line) {
+            (true, _) => color,
+            (false, true) => theme.normal,
+            (false, false) => MESH_COLOR,
+        };
+        let mode = if color[3] < 1. { r#","alphaMode":"BLEND""# } else { "" };
+        self.materials.push(format!(
+            r#"{{"name":"style_{}{}","pbrMetallicRoughness":{{"baseColorFactor":[{}],"metallicFactor":0,"roughnessFactor":0.8}},"doubleSided":true{}}}"#,
+            style,
+            if line { "_curves" } else { "" },
+            numbers(&color),
+            mode
+        ));
+        self.material_index.insert((style, line), self.materials.len() - 1);
+        return self.materials.len() - 1;
+    }
+}
+
+// the camera of the view, its node is placed at the root in meters
+fn camera(camera: &Camera) -> (String, String) {
+    let aspect = camera.aspect().max(1e-3);
+    let json = match camera.projection {
+        // the renderer takes the angle as it is stored, the exported one is the angle it shows
+        Projection::Perspective => format!(
+            r#"{{"type":"perspective","perspective":{{"aspectRatio":{},"yfov":{},"znear":{},"zfar":{}}}}}"#,
+            aspect,
+            2. * (camera.fovy() / 2.).tan().abs().atan(),
+            camera.z_near * SCALE,
+            camera.z_far * SCALE
+        ),
+        Projection::Orthographics => format!(
+            r#"{{"type":"orthographic","orthographic":{{"xmag":{},"ymag":{},"znear":{},"zfar":{}}}}}"#,
+            camera.fovy() / 2. * aspect * SCALE,
+            camera.fovy() / 2. * SCALE,
+            camera.z_near.max(0.) * SCALE,
+            camera.z_far * SCALE
+        ),
+    };
+    let world = camera.view_matrix.inverse();
+    let rotation = Quat::from_mat3(&Mat3::from_mat4(world)).normalize();
+    let node = format!(
+        r#"{{"name":"camera","camera":0,"translation":[{}],"rotation":[{}]}}"#,
+        numbers(&(camera.position * SCALE).to_array()),
+        numbers(&rotation.to_array())
+    );
+    return (json, node);
+}
+
+pub fn export(components: &ComponentCollection, view: &Camera, theme: &Theme, tolerance: f32) -> Result<Gltf> {
+    let solids = solids(components);
+    let curves = polylines(components, tolerance);
+    return scene(&solids, &curves, &components.styles.array, view, theme);
+}
+
+// a root node scales the scene to meters, below it a node per layer with a node for each of its
+// solids and one for its curves
+pub fn scene(
+    solids: &[NamedMesh],
+    curves: &[Polyline],
+    styles: &[Component<Style>],
+    view: &Camera,
+    theme: &Theme,
+) -> Result<Gltf> {
+    if solids.is_empty() && curves.is_empty() {
+        return Err(Error::Geometry("there are no visible solids or curves to export".into()));
+    }
+    let mut builder = Builder::default();
+    let mut meshes = vec![];
+
+    for solid in solids {
+        let positions = builder.accessor(&solid.mesh.positions, true);
+        let normals = builder.accessor(&solid.mesh.normals, false);
+        let material = builder.material(styles, theme, solid.style, false);
+        meshes.push(format!(
+            r#"{{"name":"{}","primitives":[{{"attributes":{{"POSITION":{},"NORMAL":{}}},"mode":4,"material":{}}}]}}"#,
+            escape(&solid.name),
+            positions,
+            normals,
+            material
+        ));
+    }
+
+    // the layers in the order they first appear, solids before curves
+    let mut layers: Vec<&str> = vec![];
+    for layer in solids.iter().map(|s| s.layer.as_str()).chain(curves.iter().map(|c| c.layer.as_str())) {
+        if !layers.contains(&layer) {
+            layers.push(layer);
+        }
+    }
+
+    let mut nodes = vec![String::new(), String::new()];
+    let mut layer_nodes = vec![];
+    for layer in layers {
+        let mut children = vec![];
+        for (i, solid) in solids.iter().enumerate().filter(|(_, s)| s.layer == layer) {
+            nodes.push(format!(r#"{{"name":"{}","mesh":{}}}"#, escape(&solid.name), i));
+            children.push((nodes.len() - 1).to_string());
+        }
+
+        // the curves of a style are one primitive of separate segments
+        let mut segments: Vec<(u32, Vec<Vec3>)> = vec![];
+        for curve in curves.iter().filter(|c| c.layer == layer) {
+            let lines = curve.points.windows(2).flat_map(|s| [s[0], s[1]]);
+            match segments.iter_mut().find(|(s, _)| *s == curve.style) {
+                Some((_, list)) => list.extend(lines),
+                None => segments.push((curve.style, lines.collect())),
+            }
+        }
+        let mut primitives = vec![];
+        for (style, lines) in segments.iter() {
+            let positions = builder.accessor(lines, true);
+            let material = builder.material(styles, theme, *style, true);
+            primitives.push(format!(
+                r#"{{"attributes":{{"POSITION":{}}},"mode":1,"material":{}}}"#,
+                positions, material
+            ));
+        }
+        if !primitives.is_empty() {
+            meshes.push(format!(r#"{{"name":"curves","primitives":[{}]}}"#, primitives.join(",")));
+            nodes.push(format!(r#"{{"name":"curves","mesh":{}}}"#, meshes.len() - 1));
+            children.push((nodes.len() - 1).to_string());
+        }
+
+        nodes.push(format!(r#"{{"name":"{}","children":[{}]}}"#, escape(layer), children.join(",")));
+        layer_nodes.push((nodes.len() - 1).to_string());
+    }
+    nodes[0] = format!(
+        r#"{{"name":"f9","scale":[{},{},{}],"children":[{}]}}"#,
+        SCALE,
+        SCALE,
+        SCALE,
+        layer_nodes.join(",")
+    );
+    let (camera, camera_node) = camera(view);
+    nodes[1] = camera_node;
+
+    let json = format!(
+        concat!(
+            r#"{{"asset":{{"version":"2.0","generator":"f9"}},"scene":0,"scenes":[{{"nodes":[0,1]}}],"#,
+            r#""nodes":[{}],"meshes":[{}],"materials":[{}],"cameras":[{}],"accessors":[{}],"bufferViews":[{}]"#
+        ),
+        nodes.join(","),
+        meshes.join(","),
+        builder.materials.join(","),
+        camera,
+        builder.accessors.join(","),
+        builder.views.join(",")
+    );
+    return Ok(Gltf {
+        json,
+        buffer: builder.buffer,
+    });
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut text = String::with_capacity((data.len() + 2) / 3 * 4);
+    for chunk in data.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                text.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                text.push('=');
+            }
+        }
+    }
+    return text;
+}
+
+// a single .gltf file with the buffer embedded as a data uri
+pub fn text(gltf: &Gltf) -> String {
+    return gltf.document(Some(format!("data:application/octet-stream;base64,{}", base64(&gltf.buffer))));
+}
+
+fn chunk(glb: &mut Vec<u8>, kind: &[u8; 4], data: &[u8], padding: u8) {
+    let length = (data.len() + 3) / 4 * 4;
+    glb.extend_from_slice(&(length as u32).to_le_bytes());
+    glb.extend_from_slice(kind);
+    glb.extend_from_slice(data);
+    glb.resize(glb.len() + length - data.len(), padding);
+}
+
+// the binary container, a header, the json chunk padded with spaces and the buffer chunk
+pub fn binary(gltf: &Gltf) -> Vec<u8> {
+    let json = gltf.document(None);
+    let mut glb = vec![];
+    glb.extend_from_slice(b"glTF");
+    glb.extend_from_slice(&2u32.to_le_bytes());
+    glb.extend_from_slice(&0u32.to_le_bytes());
+    chunk(&mut glb, b"JSON", json.as_bytes(), b' ');
+    chunk(&mut glb, b"BIN\0", &gltf.buffer, 0);
+    let length = glb.len() as u32;
+    glb[8..12].copy_from_slice(&length.to_le_bytes());
+    return glb;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{components::style, solids::mesh::TriangleMesh};
+
+    // just enough json to read the documents back
+    #[derive(Debug, Clone, PartialEq)]
+    enum Json {
+        Null,
+        Bool(bool),
+        Number(f64),
+        Text(String),
+        List(Vec<Json>),
+        Object(Vec<(String, Json)>),
+    }
+
+    impl Json {
+        fn get(&self, key: &str) -> &Json {
+            match self {
+                Json::Object(fields) => {
+                    &fields.iter().find(|(k, _)| k == key).unwrap_or_else(|| panic!("no {}", key)).1
+                }
+                _ => panic!("{} of no object", key),
+            }
+        }
+
+        fn has(&self, key: &str) -> bool {
+            matches!(self, Json::Object(fields) if fields.iter().any(|(k, _)| k == key))
+        }
+
+        fn at(&self, index: usize) -> &Json {
+            match self {
+                Json::List(values) => &values[index],
+                _ => panic!("no list"),
+            }
+        }
+
+        fn len(&self) -> usize {
+            match self {
+                Json::List(values) => values.len(),
+                _ => panic!("no list"),
+            }
+        }
+
+        fn number(&self) -> f64 {
+            match self {
+                Json::Number(n) => *n,
+                _ => panic!("no number"),
+            }
+        }
+
+        fn usize(&self) -> usize {
+            self.number() as usize
+        }
+
+        fn text(&self) -> &str {
+            match self {
+                Json::Text(t) => t,
+                _ => panic!("no text"),
+            }
+        }
+
+        fn vec3(&self) -> Vec3 {
+            Vec3::new(self.at(0).number() as f32, self.at(1).number() as f32, self.at(2).number() as f32)
+        }
+    }
+
+    struct Parser<'a> {
+        text: &'a [u8],
+        at: usize,
+    }
+
+    impl Parser<'_> {
+        fn skip(&mut self) {
+            while self.at < self.text.len() && self.text[self.at].is_ascii_whitespace() {
+                self.at += 1;
+            }
+        }
+
+        fn expect(&mut self, c: u8) {
+            self.skip();
+            assert_eq!(self.text[self.at] as char, c as char, "at {}", self.at);
+            self.at += 1;
+        }
+
+        fn string(&mut self) -> String {
+            self.expect(b'"');
+            let mut text = String::new();
+            while self.text[self.at] != b'"' {
+                if self.text[self.at] == b'\\' {
+                    self.at += 1;
+                }
+                text.push(self.text[self.at] as char);
+                self.at += 1;
+            }
+            self.at += 1;
+            return text;
+        }
+
+        fn value(&mut self) -> Json {
+            self.skip();
+            match self.text[self.at] {
+                b'{' => {
+                    self.at += 1;
+                    let mut fields = vec![];
+                    self.skip();
+                    while self.text[self.at] != b'}' {
+                        let key = self.string();
+                        self.expect(b':');
+                        fields.push((key, self.value()));
+                        self.skip();
+                        if self.text[self.at] == b',' {
+                            self.at += 1;
+                        }
+                        self.skip();
+                    }
+                    self.at += 1;
+                    Json::Object(fields)
+                }
+                b'[' => {
+                    self.at += 1;
+                    let mut values = vec![];
+                    self.skip();
+                    while self.text[self.at] != b']' {
+                        values.push(self.value());
+                        self.skip();
+                        if self.text[self.at] == b',' {
+                            self.at += 1;
+                        }
+                        self.skip();
+                    }
+                    self.at += 1;
+                    Json::List(values)
+                }
+                b'"' => Json::Text(self.string()),
+                b't' | b'f' | b'n' => {
+                    let word: String = self.text[self.at..]
+                        .iter()
+                        .take_while(|c| c.is_ascii_alphabetic())
+                        .map(|c| *c as char)
+                        .collect();
+                    self.at += word.len();
+                    match word.as_str() {
+                        "true" => Json::Bool(true),
+                        "false" => Json::Bool(false),
+                        "null" => Json::Null,
+                        _ => panic!("unknown word {}", word),
+                    }
+                }
+                _ => {
+                    let start = self.at;
+                    while self.at < self.text.len() && b"+-.eE0123456789".contains(&self.text[self.at]) {
+                        self.at += 1;
+                    }
+                    let number = std::str::from_utf8(&self.text[start..self.at]).unwrap();
+                    Json::Number(number.parse().unwrap_or_else(|_| panic!("no number at {}", start)))
+                }
+            }
+        }
+    }
+
+    fn parse(text: &str) -> Json {
+        let mut parser = Parser {
+            text: text.as_bytes(),
+            at: 0,
+        };
+        let json = parser.value();
+        parser.skip();
+        assert_eq!(parser.at, text.len(), "trailing text after the json");
+        return json;
+    }
+
+    fn unbase64(text: &str) -> Vec<u8> {
+        const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let mut data = vec![];
+        for chunk in text.as_bytes().chunks(4) {
+            let digits: Vec<u32> = chunk
+                .iter()
+                .take_while(|c| **c != b'=')
+                .map(|c| ALPHABET.iter().position(|a| a == c).unwrap() as u32)
+                .collect();
+            let n = digits.iter().enumerate().fold(0, |n, (i, d)| n | d << (18 - 6 * i));
+            data.extend_from_slice(&n.to_be_bytes()[1..digits.len()]);
+        }
+        return data;
+    }
+
+    fn le(data: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]])
+    }
+
+    fn vectors(buffer: &[u8], json: &Json, accessor: usize) -> Vec<Vec3> {
+        let accessor = json.get("accessors").at(accessor);
+        let view = json.get("bufferViews").at(accessor.get("bufferView").usize());
+        let offset = view.get("byteOffset").usize();
+        let count = accessor.get("count").usize();
+        assert_eq!(view.get("byteLength").usize(), count * 12);
+        let float = |i: usize| f32::from_le_bytes(buffer[i..i + 4].try_into().unwrap());
+        return (0..count)
+            .map(|i| Vec3::new(float(offset + i * 12), float(offset + i * 12 + 4), float(offset + i * 12 + 8)))
+            .collect();
+    }
+
+    fn curve(style: u32, layer: &str, points: &[Vec3]) -> Polyline {
+        Polyline {
+            style,
+            layer: layer.into(),
+            points: points.to_vec(),
+        }
+    }
+
+    fn example() -> (Vec<NamedMesh>, Vec<Polyline>, Gltf) {
+        let mut mesh = TriangleMesh::default();
+        mesh.push(Vec3::ZERO, Vec3::new(10., 0., 0.), Vec3::new(0., 5., 0.));
+        mesh.push(Vec3::ZERO, Vec3::new(0., 5., 0.), Vec3::new(0., 0., -2.5));
+        let mut other = TriangleMesh::default();
+        other.push(Vec3::ONE, Vec3::new(2., 1., 1.), Vec3::new(1., 2., 1.));
+        let solids = vec![
+            NamedMesh {
+                name: "block \"a\"".into(),
+                style: 0,
+                layer: "0".into(),
+                mesh,
+            },
+            NamedMesh {
+                name: "wedge".into(),
+                style: 1,
+                layer: "parts".into(),
+                mesh: other,
+            },
+        ];
+        let curves = vec![
+            curve(0, "0", &[Vec3::new(-1., 0., 0.), Vec3::new(1., 2., 0.), Vec3::new(3., 2., 7.)]),
+            curve(1, "outline", &[Vec3::new(0.5, 0.5, 0.5), Vec3::new(-4., 0.5, 0.5)]),
+            curve(0, "0", &[Vec3::new(9., 9., 9.), Vec3::new(8., 8., 8.)]),
+            curve(1, "0", &[Vec3::new(1., 1., 1.), Vec3::new(2., 2., 2.)]),
+        ];
+        let styles = vec![style::default(), style::default()];
+        let gltf = scene(&solids, &curves, &styles, &Camera::default(), &Theme::default()).unwrap();
+        return (solids, curves, gltf);
+    }
+
+    fn names(json: &Json, children: &Json) -> Vec<String> {
+        (0..children.len())
+            .map(|i| json.get("nodes").at(children.at(i).usize()).get("name").text().to_string())
+            .collect()
+    }
+
+    // the accessors point into the buffer, are aligned and hold the exported values
+    fn check(json: &Json, buffer: &[u8], solids: &[NamedMesh], curves: &[Polyline]) {
+        assert_eq!(json.get("asset").get("version").text(), "2.0");
+        assert_eq!(json.get("buffers").len(), 1);
+        assert_eq!(json.get("buffers").at(0).get("byteLength").usize(), buffer.len());
+
+        let views = json.get("bufferViews");
+        for i in 0..views.len() {
+            let view = views.at(i);
+            let offset = view.get("byteOffset").usize();
+            assert_eq!(offset % 4, 0);
+            assert!(offset + view.get("byteLength").usize() <= buffer.len());
+        }
+
+        let accessors = json.get("accessors");
+        for i in 0..accessors.len() {
+            let accessor = accessors.at(i);
+            if accessor.has("min") {
+                let values = vectors(buffer, json, i);
+                let min = values.iter().fold(Vec3::splat(f32::MAX), |a, b| a.min(*b));
+                let max = values.iter().fold(Vec3::splat(f32::MIN), |a, b| a.max(*b));
+                assert_eq!(accessor.get("min").vec3(), min);
+                assert_eq!(accessor.get("max").vec3(), max);
+            }
+        }
+
+        let nodes = json.get("nodes");
+        let meshes = json.get("meshes");
+        let layers = nodes.at(0).get("children");
+        for l in 0..layers.len() {
+            let layer_node = nodes.at(layers.at(l).usize());
+            let layer = layer_node.get("name").text();
+            let children = layer_node.get("children");
+
+            // the solids of the layer come first with their own mesh
+            let layer_solids: Vec<&NamedMesh> = solids.iter().filter(|s| s.layer == layer).collect();
+            for (i, solid) in layer_solids.iter().enumerate() {
+                let node = nodes.at(children.at(i).usize());
+                assert_eq!(node.get("name").text(), solid.name);
+                let mesh = meshes.at(node.get("mesh").usize());
+                assert_eq!(mesh.get("name").text(), solid.name);
+                let attributes = mesh.get("primitives").at(0).get("attributes");
+                assert_eq!(vectors(buffer, json, attributes.get("POSITION").usize()), solid.mesh.positions);
+                assert_eq!(vectors(buffer, json, attributes.get("NORMAL").usize()), solid.mesh.normals);
+            }
+
+            // then one line primitive per style of the curves on the layer, two points per segment
+            let layer_curves: Vec<&Polyline> = curves.iter().filter(|c| c.layer == layer).collect();
+            let mut styles: Vec<u32> = vec![];
+            for curve in layer_curves.iter() {
+                if !styles.contains(&curve.style) {
+                    styles.push(curve.style);
+                }
+            }
+            let count = layer_solids.len() + usize::from(!styles.is_empty());
+            assert_eq!(children.len(), count);
+            if styles.is_empty() {
+                continue;
+            }
+            let node = nodes.at(children.at(layer_solids.len()).usize());
+            assert_eq!(node.get("name").text(), "curves");
+            let primitives = meshes.at(node.get("mesh").usize()).get("primitives");
+            assert_eq!(primitives.len(), styles.len());
+            for (i, style) in styles.iter().enumerate() {
+                let segments: Vec<Vec3> = layer_curves
+                    .iter()
+                    .filter(|c| c.style == *style)
+                    .flat_map(|c| c.points.windows(2).flat_map(|s| [s[0], s[1]]))
+                    .collect();
+                let positions = primitives.at(i).get("attributes").get("POSITION").usize();
+                assert_eq!(primitives.at(i).get("mode").usize(), 1);
+                assert_eq!(accessors.at(positions).get("count").usize(), segments.len());
+                assert_eq!(vectors(buffer, json, positions), segments);
+            }
+        }
+    }
+
+    // the root scales to meters and holds a node per layer in the order they first appear
+    #[test]
+    fn layer_nodes() {
+        let (_, _, gltf) = example();
+        let json = parse(&text(&gltf));
+        assert_eq!(json.get("scenes").at(0).get("nodes").len(), 2);
+        let root = json.get("nodes").at(0);
+        assert_eq!(root.get("name").text(), "f9");
+        assert_eq!(root.get("scale").vec3(), Vec3::splat(SCALE));
+        assert_eq!(names(&json, root.get("children")), ["0", "parts", "outline"]);
+
+        let layer = |i: usize| json.get("nodes").at(root.get("children").at(i).usize());
+        assert_eq!(names(&json, layer(0).get("children")), ["block \"a\"", "curves"]);
+        assert_eq!(names(&json, layer(1).get("children")), ["wedge"]);
+        assert_eq!(names(&json, layer(2).get("children")), ["curves"]);
+        assert_eq!(json.get("nodes").at(1).get("name").text(), "camera");
+        // two solids and the curves of two layers
+        assert_eq!(json.get("meshes").len(), 4);
+    }
+
+    #[test]
+    fn embedded_buffer() {
+        let (solids, curves, gltf) = example();
+        let json = parse(&text(&gltf));
+        let uri = json.get("buffers").at(0).get("uri").text().to_string();
+        let data = uri.strip_prefix("data:application/octet-stream;base64,").unwrap();
+        let buffer = unbase64(data);
+        assert_eq!(buffer, gltf.buffer);
+        check(&json, &buffer, &solids, &curves);
+    }
+
+    #[test]
+    fn binary_container() {
+        let (solids, curves, gltf) = example();
+        let glb = binary(&gltf);
+        assert_eq!(&glb[0..4], b"glTF");
+        assert_eq!(le(&glb, 4), 2);
+        assert_eq!(le(&glb, 8) as usize, glb.len());
+
+        let json_length = le(&glb, 12) as usize;
+        assert_eq!(&glb[16..20], b"JSON");
+        assert_eq!(json_length % 4, 0);
+        let text = std::str::from_utf8(&glb[20..20 + json_length]).unwrap();
+        let json = parse(text);
+        assert!(!json.get("buffers").at(0).has("uri"));
+
+        let bin = 20 + json_length;
+        let bin_length = le(&glb, bin) as usize;
+        assert_eq!(&glb[bin + 4..bin + 8], b"BIN\0");
+        assert_eq!(bin_length % 4, 0);
+        assert_eq!(bin + 8 + bin_length, glb.len());
+        let buffer = &glb[bin + 8..bin + 8 + gltf.buffer.len()];
+        assert_eq!(buffer, gltf.buffer.as_slice());
+        assert!(glb[bin + 8 + gltf.buffer.len()..].iter().all(|b| *b == 0));
+        check(&json, buffer, &solids, &curves);
+    }
+}
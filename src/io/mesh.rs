@@ -0,0 +1,268 @@
+use std::{collections::HashMap, f32::consts::PI};
+
+use async_std::channel::{bounded, Receiver};
+use glam::Vec3;
+
+use crate::{
+    component_collection::ComponentCollection,
+    components::component::{Component, ComponentType, IComponent, IComponentData, WalkDirection},
+    core::result::Result,
+    solids::{
+        mesh::TriangleMesh,
+        sweep::rotation_minimizing_frames,
+    },
+    topology::tessellation::{arc_segments, tessellate},
+};
+
+use super::{obj, read_file, sketch::exported, stl};
+
+// a solid or the tubes around the curves, written as one object or group
+pub struct NamedMesh {
+    pub name: String,
+    pub style: u32,
+    pub layer: String,
+    pub mesh: TriangleMesh,
+}
+
+#[derive(Clone, Copy)]
+pub struct MeshOptions {
+    // chord tolerance of the curves, the solids keep the triangles they were built with
+    pub tolerance: f32,
+    // curves are written as closed tubes of this radius
+    pub tubes: Option<f32>,
+}
+
+// the visible meshes, one per solid
+pub fn solids(components: &ComponentCollection) -> Vec<NamedMesh> {
+    return components
+        .meshes
+        .array
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| exported(*m, m.flags) && m.data.count > 0)
+        .map(|(i, m)| NamedMesh {
+            name: format!("solid_{}", i + 1),
+            style: m.style,
+            layer: components.layers.name(ComponentType::Mesh, i as u32).to_string(),
+            mesh: TriangleMesh::from_component(components, i),
+        })
+        .collect();
+}
+
+pub fn export_meshes(components: &ComponentCollection, options: MeshOptions) -> Vec<NamedMesh> {
+    let mut meshes = solids(components);
+    if let Some(radius) = options.tubes {
+        let mesh = tubes(components, radius, options.tolerance);
+        if !mesh.positions.is_empty() {
+            meshes.push(NamedMesh {
+                name: "curves".into(),
+                style: 0,
+                layer: "0".into(),
+                mesh,
+            });
+        }
+    }
+    return meshes;
+}
+
+// the exported components of one array with the name of their layer
+fn exported_of<'a, T>(
+    components: &'a ComponentCollection,
+    ctype: ComponentType,
+    array: &'a [Component<T>],
+) -> impl Iterator<Item = (&'a str, &'a dyn IComponent)> + 'a
+where
+    T: IComponentData + Clone + 'static,
+{
+    array
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| exported(*c, c.flags))
+        .map(move |(i, c)| (components.layers.name(ctype, i as u32), c as &dyn IComponent))
+}
+
+fn curves(components: &ComponentCollection) -> Vec<(&str, &dyn IComponent)> {
+    let mut curves = vec![];
+    curves.extend(exported_of(components, ComponentType::Line, &components.lines.array));
+    curves.extend(exported_of(components, ComponentType::Bezier, &components.beziers.array));
+    curves.extend(exported_of(components, ComponentType::Circle, &components.circles.array));
+    curves.extend(exported_of(components, ComponentType::Arc, &components.arcs.array));
+    curves.extend(exported_of(components, ComponentType::Ellipse, &components.ellipses.array));
+    curves.extend(exported_of(components, ComponentType::Nurbs, &components.nurbs.array));
+    return curves;
+}
+
+// a visible curve tessellated with the tolerance
+pub struct Polyline {
+    pub style: u32,
+    pub layer: String,
+    pub points: Vec<Vec3>,
+}
+
+pub fn polylines(components: &ComponentCollection, tolerance: f32) -> Vec<Polyline> {
+    let mut polylines = vec![];
+    for (layer, curve) in curves(components) {
+        let position = |t: f32| curve.get_position(components, WalkDirection::Up, t);
+        let mut points: Vec<Vec3> = tessellate(position, tolerance).iter().map(|t| position(*t)).collect();
+        points.dedup_by(|a, b| a.distance(*b) < 1e-6);
+        if points.len() >= 2 {
+            polylines.push(Polyline {
+                style: curve.get_style(),
+                layer: layer.to_string(),
+                points,
+            });
+        }
+    }
+    return polylines;
+}
+
+// every visible curve as a tube
+pub fn tubes(components: &ComponentCollection, radius: f32, tolerance: f32) -> TriangleMesh {
+    let sides = arc_segments(radius, 2. * PI, tolerance).max(6);
+    let mut mesh = TriangleMesh::default();
+    for polyline in polylines(components, tolerance) {
+        mesh.append(&tube(&polyline.points, radius, sides));
+    }
+    return mesh;
+}
+
+// a circle of the given radius swept along a polyline, open ends are closed with flat caps
+pub fn tube(points: &[Vec3], radius: f32, sides: usize) -> TriangleMesh {
+    let frames = rotation_minimizing_frames(points);
+    let rings: Vec<Vec<Vec3>> = frames
+        .iter()
+        .map(|f| {
+            let binormal = f.tangent.cross(f.normal);
+            (0..sides)
+                .map(|i| {
+                    let angle = 2. * PI * i as f32 / sides as f32;
+                    f.position + (f.normal * angle.cos() + binormal * angle.sin()) * radius
+                })
+                .collect()
+        })
+        .collect();
+    let mut tube = TriangleMesh::default();
+    for pair in rings.windows(2) {
+        tube.stitch(&pair[0], &pair[1], true);
+    }
+    if points[0].distance(points[points.len() - 1]) >= 1e-4 {
+        let (first, last) = (&rings[0], &rings[rings.len() - 1]);
+        for i in 0..sides {
+            let j = (i + 1) % sides;
+            tube.push(points[0], first[j], first[i]);
+            tube.push(points[points.len() - 1], last[i], last[j]);
+        }
+    }
+    tube.smooth(60.);
+    return tube;
+}
+
+// edges of a closed surface are shared by exactly two triangles
+pub struct Validation {
+    pub open_edges: usize,
+    pub non_manifold_edges: usize,
+}
+
+pub fn validate(mesh: &TriangleMesh) -> Validation {
+    let (_, triangles) = mesh.weld();
+    let mut edges: HashMap<(u32, u32), usize> = HashMap::new();
+    for t in triangles.iter() {
+        for (a, b) in [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])] {
+            *edges.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+        }
+    }
+    return Validation {
+        open_edges: edges.values().filter(|n| **n == 1).count(),
+        non_manifold_edges: edges.values().filter(|n| **n > 2).count(),
+    };
+}
+
+// one line per mesh that is not a closed manifold surface
+pub fn warnings(meshes: &[NamedMesh]) -> Vec<String> {
+    let mut warnings = vec![];
+    for m in meshes {
+        let v = validate(&m.mesh);
+        if v.open_edges > 0 || v.non_manifold_edges > 0 {
+            warnings.push(format!(
+                "{}: {} open and {} non-manifold edge(s)",
+                m.name, v.open_edges, v.non_manifold_edges
+            ));
+        }
+    }
+    return warnings;
+}
+
+// a position and normal per triangle corner mapped to shared indices
+pub struct IndexedMesh {
+    pub positions: Vec<Vec3>,
+    pub normals: Vec<Vec3>,
+    // position and normal index of each corner
+    pub triangles: Vec<[(u32, u32); 3]>,
+}
+
+fn key(v: Vec3, tolerance: f32) -> [i64; 3] {
+    v.to_array().map(|x| (x / tolerance).round() as i64)
+}
+
+pub fn index(mesh: &TriangleMesh) -> IndexedMesh {
+    let mut indexed = IndexedMesh {
+        positions: vec![],
+        normals: vec![],
+        triangles: vec![],
+    };
+    let mut positions: HashMap<[i64; 3], u32> = HashMap::new();
+    let mut normals: HashMap<[i64; 3], u32> = HashMap::new();
+    for (p, n) in mesh.positions.chunks(3).zip(mesh.normals.chunks(3)) {
+        let mut corners = [(0, 0); 3];
+        for i in 0..3 {
+            let position = *positions.entry(key(p[i], 1e-4)).or_insert_with(|| {
+                indexed.positions.push(p[i]);
+                indexed.positions.len() as u32 - 1
+            });
+            let normal = *normals.entry(key(n[i], 1e-5)).or_insert_with(|| {
+                indexed.normals.push(n[i]);
+                indexed.normals.len() as u32 - 1
+            });
+            corners[i] = (position, normal);
+        }
+        // welding may collapse thin triangles
+        if corners[0].0 != corners[1].0 && corners[1].0 != corners[2].0 && corners[2].0 != corners[0].0 {
+            indexed.triangles.push(corners);
+        }
+    }
+    return indexed;
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum MeshFile {
+    Stl,
+    Obj,
+}
+
+// the file scaled by the size of its unit in mm
+pub fn read_mesh(path: &str, file: MeshFile, scale: f32) -> Result<TriangleMesh> {
+    let data = read_file(path)?;
+    let mut mesh = match file {
+        MeshFile::Stl => stl::import(&data)?,
+        MeshFile::Obj => obj::import(&String::from_utf8_lossy(&data))?,
+    };
+    mesh.positions.iter_mut().for_each(|p| *p *= scale);
+    return Ok(mesh);
+}
+
+// scans have millions of triangles, they are read on a thread so the ui keeps running
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read_in_background(path: String, file: MeshFile, scale: f32) -> Receiver<Result<TriangleMesh>> {
+    let (sender, receiver) = bounded(1);
+    std::thread::spawn(move || {
+        let _ = sender.try_send(read_mesh(&path, file, scale));
+    });
+    return receiver;
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn read_in_background(path: String, file: MeshFile, scale: f32) -> Receiver<Result<TriangleMesh>> {
+    let (sender, receiver) = bounded(1);
+    let _ = sender.try_send(read_mesh(&path, file, scale));
+    return receiver;
+}
@@ -0,0 +1,37 @@
+pub mod dxf;
+pub mod gcode;
+pub mod gltf;
+pub mod mesh;
+pub mod obj;
+pub mod raster;
+pub mod sketch;
+pub mod step;
+pub mod stl;
+pub mod svg;
+pub mod threemf;
+pub mod ttf;
+pub mod xml;
+pub mod zip;
+
+use crate::core::result::{Error, Result};
+
+// the browser build has no file system
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write_file(path: &str, data: &[u8]) -> Result<()> {
+    std::fs::write(path, data).map_err(|e| Error::File(format!("{}: {}", path, e)))
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn write_file(_: &str, _: &[u8]) -> Result<()> {
+    Err(Error::File("files can't be written in the browser".into()))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read_file(path: &str) -> Result<Vec<u8>> {
+    std::fs::read(path).map_err(|e| Error::File(format!("{}: {}", path, e)))
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn read_file(_: &str) -> Result<Vec<u8>> {
+    Err(Error::File("files can't be read in the browser".into()))
+}
@@ -0,0 +1,91 @@
+use std::fmt::Write;
+
+use glam::Vec3;
+
+use crate::{
+    core::result::{Error, Result},
+    solids::mesh::TriangleMesh,
+};
+
+use super::mesh::{index, NamedMesh};
+
+// one group per mesh, indices count on over all groups and start at 1
+pub fn export(meshes: &[NamedMesh]) -> String {
+    let mut obj = String::from("# f9 wavefront obj, millimeters\n");
+    let (mut positions, mut normals) = (1, 1);
+    for m in meshes {
+        let indexed = index(&m.mesh);
+        let _ = writeln!(obj, "o {}\ng {}", m.name, m.name);
+        for v in indexed.positions.iter() {
+            let _ = writeln!(obj, "v {} {} {}", v.x, v.y, v.z);
+        }
+        for n in indexed.normals.iter() {
+            let _ = writeln!(obj, "vn {} {} {}", n.x, n.y, n.z);
+        }
+        for t in indexed.triangles.iter() {
+            let [a, b, c] = t.map(|(p, n)| format!("{}//{}", p + positions, n + normals));
+            let _ = writeln!(obj, "f {} {} {}", a, b, c);
+        }
+        positions += indexed.positions.len() as u32;
+        normals += indexed.normals.len() as u32;
+    }
+    return obj;
+}
+
+fn vector(values: &[&str]) -> Result<Vec3> {
+    let mut v = [0.; 3];
+    for (i, x) in v.iter_mut().enumerate() {
+        *x = values
+            .get(i)
+            .and_then(|t| t.parse().ok())
+            .ok_or(Error::File(format!("'{}' is no vector", values.join(" "))))?;
+    }
+    return Ok(Vec3::from_array(v));
+}
+
+// indices start at 1, negative ones count back from the last element read
+fn resolve(index: &str, len: usize) -> Option<usize> {
+    let i: i64 = index.parse().ok()?;
+    let i = if i < 0 { len as i64 + i } else { i - 1 };
+    (0..len as i64).contains(&i).then_some(i as usize)
+}
+
+// positions, normals and faces of all groups in one mesh, polygons are split into fans
+pub fn import(text: &str) -> Result<TriangleMesh> {
+    let mut positions = vec![];
+    let mut normals = vec![];
+    let mut mesh = TriangleMesh::default();
+    for line in text.lines() {
+        let values: Vec<&str> = line.split_ascii_whitespace().collect();
+        match values.first() {
+            Some(&"v") => positions.push(vector(&values[1..])?),
+            Some(&"vn") => normals.push(vector(&values[1..])?),
+            Some(&"f") => {
+                let mut corners = vec![];
+                for corner in values[1..].iter() {
+                    let mut parts = corner.split('/');
+                    let position = parts
+                        .next()
+                        .and_then(|p| resolve(p, positions.len()))
+                        .ok_or(Error::File(format!("face corner '{}' has no vertex", corner)))?;
+                    let normal = parts.nth(1).and_then(|n| resolve(n, normals.len()));
+                    corners.push((positions[position], normal.map(|n| normals[n])));
+                }
+                for i in 1..corners.len().saturating_sub(1) {
+                    let triangle = [corners[0], corners[i], corners[i + 1]];
+                    mesh.push(triangle[0].0, triangle[1].0, triangle[2].0);
+                    // the normals of the file replace the flat one when every corner has one
+                    if let [Some(a), Some(b), Some(c)] = triangle.map(|(_, n)| n) {
+                        mesh.normals.truncate(mesh.normals.len() - 3);
+                        mesh.normals.extend([a, b, c].map(|n| n.normalize_or_zero()));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    if mesh.positions.is_empty() {
+        return Err(Error::File("the file has no faces".into()));
+    }
+    return Ok(mesh);
+}
@@ -0,0 +1,39 @@
+use image::imageops::FilterType;
+
+use crate::core::result::{Error, Result};
+
+use super::read_file;
+
+// longer pictures are scaled down, all images share one storage buffer
+pub const MAX_IMAGE_SIZE: u32 = 2048;
+
+// the pixels of a png or jpeg as rgba8 packed into u32, row by row from the top
+pub struct Raster {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u32>,
+}
+
+pub fn decode(data: &[u8]) -> Result<Raster> {
+    let mut picture = image::load_from_memory(data)
+        .map_err(|e| Error::File(e.to_string()))?
+        .to_rgba8();
+    let longest = picture.width().max(picture.height());
+    if longest > MAX_IMAGE_SIZE {
+        let factor = MAX_IMAGE_SIZE as f32 / longest as f32;
+        let width = ((picture.width() as f32 * factor).round() as u32).max(1);
+        let height = ((picture.height() as f32 * factor).round() as u32).max(1);
+        picture = image::imageops::resize(&picture, width, height, FilterType::Triangle);
+    }
+    let pixels = picture.pixels().map(|p| u32::from_le_bytes(p.0)).collect();
+    return Ok(Raster {
+        width: picture.width(),
+        height: picture.height(),
+        pixels,
+    });
+}
+
+pub fn read_raster(path: &str) -> Result<Raster> {
+    let data = read_file(path)?;
+    return decode(&data).map_err(|e| Error::File(format!("{}: {}", path, e)));
+}
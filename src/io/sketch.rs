@@ -0,0 +1,455 @@
+use std::{collections::HashMap, f32::consts::PI};
+
+use glam::{Vec2, Vec3};
+
+use crate::{
+    component_collection::ComponentCollection,
+    components::{
+        arc, bezier, circle,
+        component::{ComponentFlags, ComponentType, IComponent, WalkDirection},
+        ellipse, line,
+        nurbs::{push_nurbs, to_beziers},
+        point,
+    },
+    core::basics::Plane,
+    topology::{
+        profile::{from_plane, in_plane, to_plane},
+        tessellation::tessellate,
+    },
+};
+
+// a curve in the local frame of the sketch plane, angles are counter clockwise in that frame
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    Line(Vec2, Vec2),
+    Bezier([Vec2; 4]),
+    Circle {
+        center: Vec2,
+        radius: f32,
+    },
+    Arc {
+        center: Vec2,
+        radius: f32,
+        start: f32,
+        sweep: f32,
+    },
+    // the points are center + major * cos(angle) + perp(major) * ratio * sin(angle)
+    Ellipse {
+        center: Vec2,
+        major: Vec2,
+        ratio: f32,
+        start: f32,
+        sweep: f32,
+    },
+    // spirals and rational nurbs, tessellated on the plane
+    Polyline(Vec<Vec2>),
+}
+
+// a shape with the style and layer of the component it came from
+pub struct SketchShape {
+    pub shape: Shape,
+    pub style: u32,
+    pub layer: String,
+}
+
+pub struct SketchPoint {
+    pub position: Vec2,
+    pub layer: String,
+}
+
+impl Shape {
+    pub fn is_closed(&self) -> bool {
+        match self {
+            Shape::Circle { .. } => true,
+            Shape::Ellipse { sweep, .. } => sweep.abs() >= 2. * PI - 1e-4,
+            _ => false,
+        }
+    }
+
+    pub fn points(&self) -> Vec<Vec2> {
+        match self {
+            Shape::Line(a, b) => vec![*a, *b],
+            Shape::Bezier(p) => p.to_vec(),
+            Shape::Circle { center, radius } => vec![*center - Vec2::splat(*radius), *center + Vec2::splat(*radius)],
+            Shape::Arc { .. } | Shape::Ellipse { .. } => (0..=32).map(|i| self.position(i as f32 / 32.)).collect(),
+            Shape::Polyline(points) => points.clone(),
+        }
+    }
+
+    pub fn position(&self, t: f32) -> Vec2 {
+        match self {
+            Shape::Line(a, b) => a.lerp(*b, t),
+            Shape::Bezier(p) => {
+                let s = 1. - t;
+                p[0] * s * s * s + p[1] * 3. * s * s * t + p[2] * 3. * s * t * t + p[3] * t * t * t
+            }
+            Shape::Circle { center, radius } => *center + Vec2::from_angle(2. * PI * t) * *radius,
+            Shape::Arc {
+                center,
+                radius,
+                start,
+                sweep,
+            } => *center + Vec2::from_angle(start + sweep * t) * *radius,
+            Shape::Ellipse {
+                center,
+                major,
+                ratio,
+                start,
+                sweep,
+            } => {
+                let angle = start + sweep * t;
+                *center + *major * angle.cos() + major.perp() * *ratio * angle.sin()
+            }
+            Shape::Polyline(points) => {
+                let segments = (points.len() - 1) as f32;
+                let i = ((t * segments) as usize).min(points.len() - 2);
+                points[i].lerp(points[i + 1], t * segments - i as f32)
+            }
+        }
+    }
+}
+
+// everything that belongs into a drawing, construction geometry is only a reference
+pub fn exported(c: &dyn IComponent, flags: i32) -> bool {
+    let visible = (flags & ComponentFlags::Visible as i32) != 0;
+    return visible && !c.is_deleted() && !c.is_construction();
+}
+
+fn parallel(plane: &Plane, orientation: Vec3) -> Option<f32> {
+    let dot = orientation.normalize().dot(plane.orientation.normalize());
+    if dot.abs() > 1. - 1e-4 {
+        return Some(dot.signum());
+    }
+    return None;
+}
+
+fn polyline(plane: &Plane, position: impl Fn(f32) -> Vec3, tolerance: f32) -> Shape {
+    let points = tessellate(&position, tolerance)
+        .iter()
+        .map(|t| to_plane(plane, position(*t)))
+        .collect();
+    return Shape::Polyline(points);
+}
+
+// the visible curves that lie on the plane in its local frame, curves off the plane or tilted
+// to it are left out, spirals and rational nurbs are tessellated with the tolerance
+pub fn project(components: &ComponentCollection, plane: &Plane, tolerance: f32) -> Vec<SketchShape> {
+    let mut shapes = vec![];
+    let point = |i: u32| components.points.array[i as usize].data.position;
+    let on_plane = |points: &[u32]| points.iter().all(|p| in_plane(plane, point(*p)));
+    let local = |p: Vec3| to_plane(plane, p);
+    let layer = |ctype: ComponentType, i: usize| components.layers.name(ctype, i as u32).to_string();
+
+    for (i, line) in components.lines.array.iter().enumerate().filter(|(_, l)| exported(*l, l.flags)) {
+        let l = &line.data;
+        if !on_plane(&[l.point_a, l.point_b]) {
+            continue;
+        }
+        let shape = Shape::Line(local(point(l.point_a)), local(point(l.point_b)));
+        shapes.push(SketchShape {
+            shape,
+            style: line.style,
+            layer: layer(ComponentType::Line, i),
+        });
+    }
+    for (i, bezier) in components.beziers.array.iter().enumerate().filter(|(_, b)| exported(*b, b.flags)) {
+        let b = &bezier.data;
+        if !on_plane(&[b.point_a, b.point_b, b.control_a, b.control_b]) {
+            continue;
+        }
+        let shape = Shape::Bezier(b.controls(components).map(local));
+        shapes.push(SketchShape {
+            shape,
+            style: bezier.style,
+            layer: layer(ComponentType::Bezier, i),
+        });
+    }
+    for (i, circle) in components.circles.array.iter().enumerate().filter(|(_, c)| exported(*c, c.flags)) {
+        let c = &circle.data;
+        if parallel(plane, c.orientation).is_none() || c.heightfactor != 0. || !on_plane(&[c.center]) {
+            continue;
+        }
+        let center = point(c.center);
+        let shape = match c.is_closed() {
+            true => Shape::Circle {
+                center: local(center),
+                radius: c.radius,
+            },
+            false => polyline(plane, |t| c.position(center, t), tolerance),
+        };
+        shapes.push(SketchShape {
+            shape,
+            style: circle.style,
+            layer: layer(ComponentType::Circle, i),
+        });
+    }
+    for (i, arc) in components.arcs.array.iter().enumerate().filter(|(_, a)| exported(*a, a.flags)) {
+        let a = &arc.data;
+        let Some(side) = parallel(plane, a.orientation) else {
+            continue;
+        };
+        if !on_plane(&[a.center, a.start, a.end]) {
+            continue;
+        }
+        let (center, radius, u, _, angle) = a.geometry(components);
+        let u = local(center + u) - local(center);
+        // seen from behind the arc runs clockwise in the plane
+        let shape = Shape::Arc {
+            center: local(center),
+            radius,
+            start: u.y.atan2(u.x),
+            sweep: angle * side,
+        };
+        shapes.push(SketchShape {
+            shape,
+            style: arc.style,
+            layer: layer(ComponentType::Arc, i),
+        });
+    }
+    for (i, ellipse) in components.ellipses.array.iter().enumerate().filter(|(_, e)| exported(*e, e.flags)) {
+        let e = &ellipse.data;
+        let Some(side) = parallel(plane, e.orientation) else {
+            continue;
+        };
+        if !on_plane(&[e.center]) {
+            continue;
+        }
+        let center = point(e.center);
+        let (a, _) = e.axes();
+        let shape = Shape::Ellipse {
+            center: local(center),
+            major: local(center + a * e.radius_a) - local(center),
+            ratio: e.radius_b / e.radius_a,
+            start: e.start_angle * side,
+            sweep: e.sweep_angle * side,
+        };
+        shapes.push(SketchShape {
+            shape,
+            style: ellipse.style,
+            layer: layer(ComponentType::Ellipse, i),
+        });
+    }
+    for (i, nurbs) in components.nurbs.array.iter().enumerate().filter(|(_, n)| exported(*n, n.flags)) {
+        let n = &nurbs.data;
+        let controls: Vec<u32> = n.controls(components).iter().map(|c| c.data.point).collect();
+        if !on_plane(&controls) {
+            continue;
+        }
+        // polynomial curves become beziers, rational ones are tessellated
+        match to_beziers(&n.homogeneous(components), &n.knots(components), n.degree as usize) {
+            Ok(segments) => {
+                for segment in segments {
+                    let shape = Shape::Bezier(segment.map(local));
+                    shapes.push(SketchShape {
+                        shape,
+                        style: nurbs.style,
+                        layer: layer(ComponentType::Nurbs, i),
+                    });
+                }
+            }
+            Err(_) => {
+                let position = |t: f32| nurbs.get_position(components, WalkDirection::Up, t);
+                let shape = polyline(plane, position, tolerance);
+                shapes.push(SketchShape {
+                    shape,
+                    style: nurbs.style,
+                    layer: layer(ComponentType::Nurbs, i),
+                });
+            }
+        }
+    }
+    return shapes;
+}
+
+// visible points that no curve is built from
+pub fn free_point_indices(components: &ComponentCollection) -> Vec<usize> {
+    let mut used = vec![false; components.points.array.len()];
+    let mut use_points = |points: &[u32]| points.iter().for_each(|p| used[*p as usize] = true);
+    for l in components.lines.array.iter() {
+        use_points(&[l.data.point_a, l.data.point_b]);
+    }
+    for b in components.beziers.array.iter() {
+        use_points(&[b.data.point_a, b.data.point_b, b.data.control_a, b.data.control_b]);
+    }
+    for c in components.circles.array.iter() {
+        use_points(&[c.data.center]);
+    }
+    for a in components.arcs.array.iter() {
+        use_points(&[a.data.center, a.data.start, a.data.end]);
+    }
+    for e in components.ellipses.array.iter() {
+        use_points(&[e.data.center]);
+    }
+    for c in components.nurbs_controls.array.iter() {
+        use_points(&[c.data.point]);
+    }
+    return components
+        .points
+        .array
+        .iter()
+        .enumerate()
+        .filter(|(i, p)| !used[*i] && exported(*p, p.flags))
+        .map(|(i, _)| i)
+        .collect();
+}
+
+pub fn free_points(components: &ComponentCollection, plane: &Plane) -> Vec<SketchPoint> {
+    return free_point_indices(components)
+        .iter()
+        .filter(|i| in_plane(plane, components.points.array[**i].data.position))
+        .map(|i| SketchPoint {
+            position: to_plane(plane, components.points.array[*i].data.position),
+            layer: components.layers.name(ComponentType::Point, *i as u32).to_string(),
+        })
+        .collect();
+}
+
+// smallest and largest corner of all shapes
+pub fn bounds(shapes: &[SketchShape]) -> Option<(Vec2, Vec2)> {
+    let mut points = shapes.iter().flat_map(|s| s.shape.points());
+    let first = points.next()?;
+    return Some(points.fold((first, first), |(min, max), p| (min.min(p), max.max(p))));
+}
+
+// imported end points closer than this are the same point, in millimeters
+pub const MERGE_TOLERANCE: f32 = 1e-3;
+
+// adds imported curves on a plane, end points that meet are shared so the curves chain into
+// profiles
+pub struct SketchBuilder<'a> {
+    pub components: &'a mut ComponentCollection,
+    plane: Plane,
+    // end points by their cell on a grid of the merge tolerance
+    cells: HashMap<(i64, i64), Vec<(Vec2, u32)>>,
+    pub count: usize,
+    // the layer that added components are put on, shared points stay on the first one
+    pub layer: usize,
+}
+
+impl<'a> SketchBuilder<'a> {
+    pub fn new(components: &'a mut ComponentCollection, plane: Plane) -> SketchBuilder<'a> {
+        SketchBuilder {
+            components,
+            plane,
+            cells: HashMap::new(),
+            count: 0,
+            layer: 0,
+        }
+    }
+
+    fn added(&mut self, ctype: ComponentType, index: usize) {
+        self.components.layers.assign(ctype, index as u32, self.layer);
+        if ctype != ComponentType::Point {
+            self.count += 1;
+        }
+    }
+
+    fn cell(p: Vec2) -> (i64, i64) {
+        ((p.x / MERGE_TOLERANCE).floor() as i64, (p.y / MERGE_TOLERANCE).floor() as i64)
+    }
+
+    // a point that isn't shared, like a center or a control point
+    pub fn free_point(&mut self, p: Vec2) -> u32 {
+        let position = from_plane(&self.plane, p);
+        let index = self.components.points.push(point::new(position));
+        self.added(ComponentType::Point, index);
+        return index as u32;
+    }
+
+    pub fn point(&mut self, p: Vec2) -> u32 {
+        let (x, y) = Self::cell(p);
+        for cx in x - 1..=x + 1 {
+            for cy in y - 1..=y + 1 {
+                let shared = self.cells.get(&(cx, cy)).and_then(|points| {
+                    points.iter().find(|(q, _)| q.distance(p) <= MERGE_TOLERANCE)
+                });
+                if let Some((_, index)) = shared {
+                    return *index;
+                }
+            }
+        }
+        let index = self.free_point(p);
+        self.cells.entry((x, y)).or_default().push((p, index));
+        return index;
+    }
+
+    pub fn line(&mut self, a: Vec2, b: Vec2) {
+        if a.distance(b) <= MERGE_TOLERANCE {
+            return;
+        }
+        let (a, b) = (self.point(a), self.point(b));
+        let index = self.components.lines.push(line::new(a, b));
+        self.added(ComponentType::Line, index);
+    }
+
+    pub fn bezier(&mut self, p: [Vec2; 4]) {
+        let (a, b) = (self.point(p[0]), self.point(p[3]));
+        let (control_a, control_b) = (self.free_point(p[1]), self.free_point(p[2]));
+        let index = self.components.beziers.push(bezier::new(a, b, control_a, control_b));
+        self.added(ComponentType::Bezier, index);
+    }
+
+    pub fn circle(&mut self, center: Vec2, radius: f32) {
+        let center = self.free_point(center);
+        let orientation = self.plane.orientation;
+        let index = self.components.circles.push(circle::new(center, radius, orientation, 0.));
+        self.added(ComponentType::Circle, index);
+    }
+
+    // counter clockwise for a positive sweep
+    pub fn arc(&mut self, center: Vec2, radius: f32, start: f32, sweep: f32) {
+        let start_point = self.point(center + Vec2::from_angle(start) * radius);
+        let end_point = self.point(center + Vec2::from_angle(start + sweep) * radius);
+        let center = self.free_point(center);
+        // clockwise in the plane is counter clockwise seen from behind
+        let orientation = self.plane.orientation * sweep.signum();
+        let index = self.components.arcs.push(arc::new(center, start_point, end_point, orientation));
+        self.added(ComponentType::Arc, index);
+    }
+
+    // angles like Shape::Ellipse
+    pub fn ellipse(&mut self, center: Vec2, major: Vec2, ratio: f32, start: f32, sweep: f32) {
+        let center = self.free_point(center);
+        let radius = major.length();
+        let rotation = major.y.atan2(major.x);
+        let mut ellipse = ellipse::new(center, radius, radius * ratio, self.plane.orientation, rotation);
+        ellipse.data.start_angle = start;
+        ellipse.data.sweep_angle = sweep;
+        let index = self.components.ellipses.push(ellipse);
+        self.added(ComponentType::Ellipse, index);
+    }
+
+    pub fn shape(&mut self, shape: &Shape) {
+        match shape {
+            Shape::Line(a, b) => self.line(*a, *b),
+            Shape::Bezier(p) => self.bezier(*p),
+            Shape::Circle { center, radius } => self.circle(*center, *radius),
+            Shape::Arc {
+                center,
+                radius,
+                start,
+                sweep,
+            } => self.arc(*center, *radius, *start, *sweep),
+            Shape::Ellipse {
+                center,
+                major,
+                ratio,
+                start,
+                sweep,
+            } => self.ellipse(*center, *major, *ratio, *start, *sweep),
+            Shape::Polyline(points) => points.windows(2).for_each(|p| self.line(p[0], p[1])),
+        }
+    }
+
+    // the first and last control point are shared, the curve is expected to be clamped
+    pub fn nurbs(&mut self, points: &[Vec2], weights: &[f32], knots: &[f32], degree: u32) {
+        let last = points.len() - 1;
+        let points: Vec<u32> = points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| if i == 0 || i == last { self.point(*p) } else { self.free_point(*p) })
+            .collect();
+        let index = push_nurbs(self.components, &points, weights, knots, degree);
+        self.added(ComponentType::Nurbs, index);
+    }
+}
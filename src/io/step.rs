@@ -0,0 +1,493 @@
+use std::fmt::Write;
+
+use glam::Vec3;
+
+use crate::{
+    component_collection::ComponentCollection,
+    core::{
+        basics::Plane,
+        result::{Error, Result},
+        units::Unit,
+    },
+    topology::tessellation::tessellate,
+};
+
+use super::{
+    mesh::{solids, validate, NamedMesh},
+    sketch::{exported, free_point_indices},
+};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum StepSchema {
+    Ap214,
+    Ap242,
+}
+
+impl StepSchema {
+    fn file_schema(&self) -> &'static str {
+        match self {
+            StepSchema::Ap214 => "AUTOMOTIVE_DESIGN { 1 0 10303 214 1 1 1 1 }",
+            StepSchema::Ap242 => "AP242_MANAGED_MODEL_BASED_3D_ENGINEERING_MIM_LF { 1 0 10303 442 1 1 4 }",
+        }
+    }
+
+    fn application(&self) -> (&'static str, &'static str, i32) {
+        match self {
+            StepSchema::Ap214 => ("automotive design", "automotive_design", 2000),
+            StepSchema::Ap242 => (
+                "managed model based 3d engineering",
+                "ap242_managed_model_based_3d_engineering",
+                2011,
+            ),
+        }
+    }
+}
+
+// a curve in world coordinates, conics keep their exact shape
+#[derive(Debug, Clone, PartialEq)]
+pub enum Curve {
+    Line(Vec3, Vec3),
+    // x is the direction of parameter 0, the curve turns counter clockwise around the normal
+    Circle {
+        center: Vec3,
+        normal: Vec3,
+        x: Vec3,
+        radius: f32,
+    },
+    Arc {
+        center: Vec3,
+        normal: Vec3,
+        x: Vec3,
+        radius: f32,
+        angle: f32,
+    },
+    Ellipse {
+        center: Vec3,
+        normal: Vec3,
+        major: Vec3,
+        radius_a: f32,
+        radius_b: f32,
+        start: f32,
+        sweep: f32,
+    },
+    Bezier([Vec3; 4]),
+    Nurbs {
+        points: Vec<Vec3>,
+        weights: Vec<f32>,
+        knots: Vec<f32>,
+        degree: u32,
+    },
+    Polyline(Vec<Vec3>),
+}
+
+fn polyline(position: impl Fn(f32) -> Vec3, tolerance: f32) -> Curve {
+    Curve::Polyline(tessellate(&position, tolerance).iter().map(|t| position(*t)).collect())
+}
+
+// the visible curves, helices and spirals are tessellated with the tolerance
+pub fn curves(components: &ComponentCollection, tolerance: f32) -> Vec<Curve> {
+    let mut curves = vec![];
+    let point = |i: u32| components.points.array[i as usize].data.position;
+
+    for line in components.lines.array.iter().filter(|l| exported(*l, l.flags)) {
+        curves.push(Curve::Line(point(line.data.point_a), point(line.data.point_b)));
+    }
+    for bezier in components.beziers.array.iter().filter(|b| exported(*b, b.flags)) {
+        curves.push(Curve::Bezier(bezier.data.controls(components)));
+    }
+    for circle in components.circles.array.iter().filter(|c| exported(*c, c.flags)) {
+        let c = &circle.data;
+        let center = point(c.center);
+        if !c.is_closed() {
+            curves.push(polyline(|t| c.position(center, t), tolerance));
+            continue;
+        }
+        let plane = Plane {
+            position: center,
+            orientation: c.orientation,
+        };
+        curves.push(Curve::Circle {
+            center,
+            normal: c.orientation.normalize(),
+            x: plane.basis().0,
+            radius: c.radius,
+        });
+    }
+    for arc in components.arcs.array.iter().filter(|a| exported(*a, a.flags)) {
+        let (center, radius, x, _, angle) = arc.data.geometry(components);
+        curves.push(Curve::Arc {
+            center,
+            normal: arc.data.orientation.normalize(),
+            x,
+            radius,
+            angle,
+        });
+    }
+    for ellipse in components.ellipses.array.iter().filter(|e| exported(*e, e.flags)) {
+        let e = &ellipse.data;
+        curves.push(Curve::Ellipse {
+            center: point(e.center),
+            normal: e.orientation.normalize(),
+            major: e.axes().0,
+            radius_a: e.radius_a,
+            radius_b: e.radius_b,
+            start: e.start_angle,
+            sweep: e.sweep_angle,
+        });
+    }
+    for nurbs in components.nurbs.array.iter().filter(|n| exported(*n, n.flags)) {
+        let n = &nurbs.data;
+        let homogeneous = n.homogeneous(components);
+        curves.push(Curve::Nurbs {
+            points: homogeneous.iter().map(|c| c.truncate() / c.w).collect(),
+            weights: homogeneous.iter().map(|c| c.w).collect(),
+            knots: n.knots(components),
+            degree: n.degree,
+        });
+    }
+    return curves;
+}
+
+// step reals need a decimal point
+fn real(value: f32) -> String {
+    let mut text = value.to_string();
+    if !text.contains('.') {
+        text.push('.');
+    }
+    return text;
+}
+
+fn reals(values: &[f32]) -> String {
+    values.iter().map(|v| real(*v)).collect::<Vec<_>>().join(",")
+}
+
+fn string(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+fn list(ids: &[usize]) -> String {
+    ids.iter().map(|i| format!("#{}", i)).collect::<Vec<_>>().join(",")
+}
+
+// distinct knots and how often each is repeated
+fn multiplicities(knots: &[f32]) -> (Vec<f32>, Vec<usize>) {
+    let mut values: Vec<f32> = vec![];
+    let mut counts: Vec<usize> = vec![];
+    for knot in knots {
+        match values.last() {
+            Some(last) if (knot - last).abs() < 1e-9 => *counts.last_mut().unwrap() += 1,
+            _ => {
+                values.push(*knot);
+                counts.push(1);
+            }
+        }
+    }
+    return (values, counts);
+}
+
+struct Writer {
+    entities: Vec<String>,
+    // project units per mm
+    scale: f32,
+}
+
+impl Writer {
+    fn add(&mut self, entity: String) -> usize {
+        self.entities.push(entity);
+        return self.entities.len();
+    }
+
+    fn point(&mut self, p: Vec3) -> usize {
+        let p = p * self.scale;
+        self.add(format!("CARTESIAN_POINT('',({}))", reals(&p.to_array())))
+    }
+
+    fn direction(&mut self, d: Vec3) -> usize {
+        self.add(format!("DIRECTION('',({}))", reals(&d.normalize_or_zero().to_array())))
+    }
+
+    fn placement(&mut self, location: Vec3, z: Vec3, x: Vec3) -> usize {
+        let location = self.point(location);
+        let z = self.direction(z);
+        let x = self.direction(x);
+        self.add(format!("AXIS2_PLACEMENT_3D('',#{},#{},#{})", location, z, x))
+    }
+
+    fn length(&self, value: f32) -> String {
+        real(value * self.scale)
+    }
+
+    fn curve(&mut self, curve: &Curve) -> usize {
+        match curve {
+            Curve::Line(a, b) => {
+                let length = a.distance(*b);
+                let (pa, pb) = (self.point(*a), self.point(*b));
+                let direction = self.direction(*b - *a);
+                let vector = self.add(format!("VECTOR('',#{},1.)", direction));
+                let line = self.add(format!("LINE('',#{},#{})", pa, vector));
+                let end = self.length(length);
+                self.add(format!(
+                    "TRIMMED_CURVE('',#{},(#{},PARAMETER_VALUE(0.)),(#{},PARAMETER_VALUE({})),.T.,.CARTESIAN.)",
+                    line, pa, pb, end
+                ))
+            }
+            Curve::Circle { center, normal, x, radius } => {
+                let placement = self.placement(*center, *normal, *x);
+                let radius = self.length(*radius);
+                self.add(format!("CIRCLE('',#{},{})", placement, radius))
+            }
+            Curve::Arc { center, normal, x, radius, angle } => {
+                let placement = self.placement(*center, *normal, *x);
+                let radius = self.length(*radius);
+                let circle = self.add(format!("CIRCLE('',#{},{})", placement, radius));
+                self.add(format!(
+                    "TRIMMED_CURVE('',#{},(PARAMETER_VALUE(0.)),(PARAMETER_VALUE({})),.T.,.PARAMETER.)",
+                    circle,
+                    real(*angle)
+                ))
+            }
+            Curve::Ellipse {
+                center,
+                normal,
+                major,
+                radius_a,
+                radius_b,
+                start,
+                sweep,
+            } => {
+                let placement = self.placement(*center, *normal, *major);
+                let (a, b) = (self.length(*radius_a), self.length(*radius_b));
+                let ellipse = self.add(format!("ELLIPSE('',#{},{},{})", placement, a, b));
+                if sweep.abs() >= 2. * std::f32::consts::PI - 1e-4 {
+                    return ellipse;
+                }
+                // against the sense the curve runs from the first trim back to the second
+                self.add(format!(
+                    "TRIMMED_CURVE('',#{},(PARAMETER_VALUE({})),(PARAMETER_VALUE({})),{},.PARAMETER.)",
+                    ellipse,
+                    real(*start),
+                    real(start + sweep),
+                    if *sweep >= 0. { ".T." } else { ".F." }
+                ))
+            }
+            Curve::Bezier(points) => {
+                let points: Vec<usize> = points.iter().map(|p| self.point(*p)).collect();
+                self.add(format!(
+                    "B_SPLINE_CURVE_WITH_KNOTS('',3,({}),.UNSPECIFIED.,.F.,.F.,(4,4),(0.,1.),.PIECEWISE_BEZIER_KNOTS.)",
+                    list(&points)
+                ))
+            }
+            Curve::Nurbs {
+                points,
+                weights,
+                knots,
+                degree,
+            } => {
+                let ids: Vec<usize> = points.iter().map(|p| self.point(*p)).collect();
+                let (values, counts) = multiplicities(knots);
+                let counts = counts.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(",");
+                if weights.iter().all(|w| (w - weights[0]).abs() < 1e-6) {
+                    return self.add(format!(
+                        "B_SPLINE_CURVE_WITH_KNOTS('',{},({}),.UNSPECIFIED.,.F.,.F.,({}),({}),.UNSPECIFIED.)",
+                        degree,
+                        list(&ids),
+                        counts,
+                        reals(&values)
+                    ));
+                }
+                // rational curves are a complex entity, its parts in alphabetical order
+                self.add(format!(
+                    concat!(
+                        "(BOUNDED_CURVE() B_SPLINE_CURVE({},({}),.UNSPECIFIED.,.F.,.F.) ",
+                        "B_SPLINE_CURVE_WITH_KNOTS(({}),({}),.UNSPECIFIED.) CURVE() GEOMETRIC_REPRESENTATION_ITEM() ",
+                        "RATIONAL_B_SPLINE_CURVE(({})) REPRESENTATION_ITEM(''))"
+                    ),
+                    degree,
+                    list(&ids),
+                    counts,
+                    reals(&values),
+                    reals(weights)
+                ))
+            }
+            Curve::Polyline(points) => {
+                let points: Vec<usize> = points.iter().map(|p| self.point(*p)).collect();
+                self.add(format!("POLYLINE('',({}))", list(&points)))
+            }
+        }
+    }
+
+    // units of length and angle with the uncertainty, shared by all representations
+    fn context(&mut self, unit: Unit) -> usize {
+        let length = match unit {
+            Unit::Millimeter => self.add("(LENGTH_UNIT() NAMED_UNIT(*) SI_UNIT(.MILLI.,.METRE.))".into()),
+            Unit::Centimeter => self.add("(LENGTH_UNIT() NAMED_UNIT(*) SI_UNIT(.CENTI.,.METRE.))".into()),
+            Unit::Meter => self.add("(LENGTH_UNIT() NAMED_UNIT(*) SI_UNIT($,.METRE.))".into()),
+            Unit::Inch => {
+                let mm = self.add("(LENGTH_UNIT() NAMED_UNIT(*) SI_UNIT(.MILLI.,.METRE.))".into());
+                let measure = self.add(format!("LENGTH_MEASURE_WITH_UNIT(LENGTH_MEASURE(25.4),#{})", mm));
+                let exponents = self.add("DIMENSIONAL_EXPONENTS(1.,0.,0.,0.,0.,0.,0.)".into());
+                self.add(format!(
+                    "(CONVERSION_BASED_UNIT('INCH',#{}) LENGTH_UNIT() NAMED_UNIT(#{}))",
+                    measure, exponents
+                ))
+            }
+        };
+        let angle = self.add("(NAMED_UNIT(*) PLANE_ANGLE_UNIT() SI_UNIT($,.RADIAN.))".into());
+        let solid_angle = self.add("(NAMED_UNIT(*) SI_UNIT($,.STERADIAN.) SOLID_ANGLE_UNIT())".into());
+        let uncertainty = self.add(format!(
+            "UNCERTAINTY_MEASURE_WITH_UNIT(LENGTH_MEASURE({}),#{},'distance_accuracy_value','confusion accuracy')",
+            self.length(1e-4),
+            length
+        ));
+        self.add(format!(
+            concat!(
+                "(GEOMETRIC_REPRESENTATION_CONTEXT(3) GLOBAL_UNCERTAINTY_ASSIGNED_CONTEXT((#{})) ",
+                "GLOBAL_UNIT_ASSIGNED_CONTEXT((#{},#{},#{})) REPRESENTATION_CONTEXT('','3D'))"
+            ),
+            uncertainty, length, angle, solid_angle
+        ))
+    }
+
+    // closed meshes become faceted breps, open ones shell based surface models
+    fn solid(&mut self, solid: &NamedMesh, context: usize, origin: usize) -> usize {
+        let (vertices, triangles) = solid.mesh.weld();
+        let points: Vec<usize> = vertices.iter().map(|v| self.point(*v)).collect();
+        let faces: Vec<usize> = triangles
+            .iter()
+            .map(|t| {
+                let polygon = self.add(format!("POLY_LOOP('',({}))", list(&t.map(|i| points[i as usize]))));
+                let bound = self.add(format!("FACE_OUTER_BOUND('',#{},.T.)", polygon));
+                self.add(format!("FACE('',(#{}))", bound))
+            })
+            .collect();
+        let name = string(&solid.name);
+        let check = validate(&solid.mesh);
+        if check.open_edges == 0 && check.non_manifold_edges == 0 {
+            let shell = self.add(format!("CLOSED_SHELL('',({}))", list(&faces)));
+            let brep = self.add(format!("FACETED_BREP({},#{})", name, shell));
+            return self.add(format!(
+                "FACETED_BREP_SHAPE_REPRESENTATION({},(#{},#{}),#{})",
+                name, brep, origin, context
+            ));
+        }
+        let shell = self.add(format!("OPEN_SHELL('',({}))", list(&faces)));
+        let model = self.add(format!("SHELL_BASED_SURFACE_MODEL({},(#{}))", name, shell));
+        return self.add(format!(
+            "MANIFOLD_SURFACE_SHAPE_REPRESENTATION({},(#{},#{}),#{})",
+            name, model, origin, context
+        ));
+    }
+}
+
+// one product whose shape holds a wireframe of the curves and points and a representation per solid
+pub fn write(
+    curves: &[Curve],
+    points: &[Vec3],
+    solids: &[NamedMesh],
+    schema: StepSchema,
+    unit: Unit,
+    name: &str,
+    time: &str,
+) -> String {
+    let mut w = Writer {
+        entities: vec![],
+        scale: 1. / unit.to_mm() as f32,
+    };
+    let (context_name, protocol, year) = schema.application();
+    let application = w.add(format!("APPLICATION_CONTEXT('{}')", context_name));
+    w.add(format!(
+        "APPLICATION_PROTOCOL_DEFINITION('international standard','{}',{},#{})",
+        protocol, year, application
+    ));
+    let product_context = w.add(format!("PRODUCT_CONTEXT('',#{},'mechanical')", application));
+    let product = w.add(format!("PRODUCT({},{},'',(#{}))", string(name), string(name), product_context));
+    w.add(format!("PRODUCT_RELATED_PRODUCT_CATEGORY('part',$,(#{}))", product));
+    let formation = w.add(format!("PRODUCT_DEFINITION_FORMATION('','',#{})", product));
+    let definition_context = w.add(format!("PRODUCT_DEFINITION_CONTEXT('part definition',#{},'design')", application));
+    let definition = w.add(format!("PRODUCT_DEFINITION('design','',#{},#{})", formation, definition_context));
+    let shape = w.add(format!("PRODUCT_DEFINITION_SHAPE('','',#{})", definition));
+
+    let context = w.context(unit);
+    let origin = w.placement(Vec3::ZERO, Vec3::Z, Vec3::X);
+    let representation = w.add(format!("SHAPE_REPRESENTATION({},(#{}),#{})", string(name), origin, context));
+    w.add(format!("SHAPE_DEFINITION_REPRESENTATION(#{},#{})", shape, representation));
+
+    let mut parts = vec![];
+    if !curves.is_empty() || !points.is_empty() {
+        let mut items: Vec<usize> = curves.iter().map(|c| w.curve(c)).collect();
+        items.extend(points.iter().map(|p| w.point(*p)));
+        let set = w.add(format!("GEOMETRIC_CURVE_SET('',({}))", list(&items)));
+        parts.push(w.add(format!(
+            "GEOMETRICALLY_BOUNDED_WIREFRAME_SHAPE_REPRESENTATION('wireframe',(#{},#{}),#{})",
+            set, origin, context
+        )));
+    }
+    for solid in solids {
+        parts.push(w.solid(solid, context, origin));
+    }
+    for part in parts {
+        w.add(format!(
+            "SHAPE_REPRESENTATION_RELATIONSHIP('','',#{},#{})",
+            representation, part
+        ));
+    }
+
+    let mut step = String::new();
+    let _ = writeln!(step, "ISO-10303-21;\nHEADER;");
+    let _ = writeln!(step, "FILE_DESCRIPTION(({}),'2;1');", string(name));
+    let _ = writeln!(step, "FILE_NAME({},'{}',(''),(''),'f9','f9','');", string(name), time);
+    let _ = writeln!(step, "FILE_SCHEMA(('{}'));", schema.file_schema());
+    let _ = writeln!(step, "ENDSEC;\nDATA;");
+    for (i, entity) in w.entities.iter().enumerate() {
+        let _ = writeln!(step, "#{}={};", i + 1, entity);
+    }
+    let _ = writeln!(step, "ENDSEC;\nEND-ISO-10303-21;");
+    return step;
+}
+
+// iso 8601 in utc, the browser build has no clock
+#[cfg(not(target_arch = "wasm32"))]
+fn time_stamp() -> String {
+    let Ok(now) = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) else {
+        return String::new();
+    };
+    let seconds = now.as_secs() as i64;
+    // days to a civil date (Howard Hinnant)
+    let z = seconds.div_euclid(86400) + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    let time = seconds.rem_euclid(86400);
+    return format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    );
+}
+
+#[cfg(target_arch = "wasm32")]
+fn time_stamp() -> String {
+    String::new()
+}
+
+pub fn export(components: &ComponentCollection, schema: StepSchema, unit: Unit, tolerance: f32) -> Result<String> {
+    let curves = curves(components, tolerance);
+    let points: Vec<Vec3> = free_point_indices(components)
+        .iter()
+        .map(|i| components.points.array[*i].data.position)
+        .collect();
+    let solids = solids(components);
+    if curves.is_empty() && points.is_empty() && solids.is_empty() {
+        return Err(Error::Geometry("there are no visible curves, points or solids to export".into()));
+    }
+    return Ok(write(&curves, &points, &solids, schema, unit, "f9", &time_stamp()));
+}
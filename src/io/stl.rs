@@ -0,0 +1,130 @@
+use std::fmt::Write;
+
+use glam::Vec3;
+
+use crate::{
+    core::result::{Error, Result},
+    solids::mesh::TriangleMesh,
+};
+
+use super::mesh::NamedMesh;
+
+// stl has no units, millimeters are what slicers expect
+fn facets(meshes: &[NamedMesh]) -> impl Iterator<Item = (Vec3, [Vec3; 3])> + '_ {
+    meshes.iter().flat_map(|m| {
+        (0..m.mesh.triangle_count()).map(|i| {
+            let [a, b, c] = m.mesh.triangle(i);
+            ((b - a).cross(c - a).normalize_or_zero(), [a, b, c])
+        })
+    })
+}
+
+// an 80 byte header, the triangle count and 50 bytes per triangle
+pub fn binary(meshes: &[NamedMesh]) -> Vec<u8> {
+    let count = meshes.iter().map(|m| m.mesh.triangle_count()).sum::<usize>();
+    let mut stl = Vec::with_capacity(84 + count * 50);
+    let mut header = [0u8; 80];
+    let title = b"f9 binary stl, millimeters";
+    header[..title.len()].copy_from_slice(title);
+    stl.extend_from_slice(&header);
+    stl.extend_from_slice(&(count as u32).to_le_bytes());
+    for (normal, corners) in facets(meshes) {
+        for v in [normal].iter().chain(corners.iter()) {
+            for x in v.to_array() {
+                stl.extend_from_slice(&x.to_le_bytes());
+            }
+        }
+        stl.extend_from_slice(&0u16.to_le_bytes());
+    }
+    return stl;
+}
+
+// one solid per mesh
+pub fn ascii(meshes: &[NamedMesh]) -> String {
+    let mut stl = String::new();
+    for m in meshes {
+        let _ = writeln!(stl, "solid {}", m.name);
+        for (normal, corners) in facets(std::slice::from_ref(m)) {
+            let _ = writeln!(stl, "  facet normal {} {} {}", normal.x, normal.y, normal.z);
+            let _ = writeln!(stl, "    outer loop");
+            for v in corners {
+                let _ = writeln!(stl, "      vertex {} {} {}", v.x, v.y, v.z);
+            }
+            let _ = writeln!(stl, "    endloop");
+            let _ = writeln!(stl, "  endfacet");
+        }
+        let _ = writeln!(stl, "endsolid {}", m.name);
+    }
+    return stl;
+}
+
+fn vector(data: &[u8]) -> Vec3 {
+    let f = |i: usize| f32::from_le_bytes([data[i], data[i + 1], data[i + 2], data[i + 3]]);
+    Vec3::new(f(0), f(4), f(8))
+}
+
+// the facet count of the header and the size of a binary file with that many facets
+fn binary_size(data: &[u8]) -> Option<(usize, usize)> {
+    if data.len() < 84 {
+        return None;
+    }
+    let count = u32::from_le_bytes([data[80], data[81], data[82], data[83]]) as usize;
+    let size = count.checked_mul(50)?.checked_add(84)?;
+    return Some((count, size));
+}
+
+fn import_binary(data: &[u8], count: usize) -> TriangleMesh {
+    let mut mesh = TriangleMesh::default();
+    mesh.positions.reserve(count * 3);
+    mesh.normals.reserve(count * 3);
+    for facet in data[84..84 + count * 50].chunks_exact(50) {
+        // the stored normal is often missing, it is taken from the winding
+        mesh.push(vector(&facet[12..]), vector(&facet[24..]), vector(&facet[36..]));
+    }
+    return mesh;
+}
+
+fn import_ascii(data: &[u8]) -> Result<TriangleMesh> {
+    let text = String::from_utf8_lossy(data);
+    let mut tokens = text.split_ascii_whitespace();
+    let mut corners = vec![];
+    while let Some(token) = tokens.next() {
+        if token != "vertex" {
+            continue;
+        }
+        let mut v = [0.; 3];
+        for x in v.iter_mut() {
+            *x = tokens
+                .next()
+                .and_then(|t| t.parse().ok())
+                .ok_or(Error::File("a vertex has no three coordinates".into()))?;
+        }
+        corners.push(Vec3::from_array(v));
+    }
+    if corners.is_empty() || corners.len() % 3 != 0 {
+        return Err(Error::File("the file is no stl mesh".into()));
+    }
+    let mut mesh = TriangleMesh::default();
+    for c in corners.chunks(3) {
+        mesh.push(c[0], c[1], c[2]);
+    }
+    return Ok(mesh);
+}
+
+// binary files may start with "solid" too, files of exactly the binary size are binary, others
+// only when they aren't ascii, some writers pad binary files at the end
+pub fn import(data: &[u8]) -> Result<TriangleMesh> {
+    let binary = binary_size(data);
+    if let Some((count, size)) = binary {
+        if data.len() == size {
+            return Ok(import_binary(data, count));
+        }
+    }
+    let ascii = import_ascii(data);
+    if let (Err(_), Some((count, size))) = (&ascii, binary) {
+        if data.len() >= size {
+            return Ok(import_binary(data, count));
+        }
+    }
+    return ascii;
+}
@@ -0,0 +1,684 @@
+use std::f32::consts::PI;
+use std::fmt::Write;
+
+use glam::Vec2;
+
+use crate::{
+    components::{component::Component, style::Style},
+    core::{
+        result::{Error, Result},
+        units::Unit,
+    },
+};
+
+use super::{
+    sketch::{bounds, Shape, SketchBuilder, SketchShape},
+    xml::{escape, tags, Tag},
+};
+
+// line width of the strokes, thin enough for laser cutters to treat them as cut lines
+const STROKE_WIDTH: f32 = 0.1;
+
+// numbers without trailing zeros, a micrometer is more than enough for drawings
+pub fn number(value: f32) -> String {
+    let text = format!("{:.4}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    return match text {
+        "-0" | "" => "0".into(),
+        _ => text.into(),
+    };
+}
+
+// svg has y pointing down, the sketch plane has it pointing up
+fn point(p: Vec2) -> String {
+    format!("{} {}", number(p.x), number(-p.y))
+}
+
+fn color(style: &Style) -> Option<String> {
+    let [r, g, b, a] = style.color;
+    if a <= 0. {
+        return None;
+    }
+    let channel = |c: f32| (c.clamp(0., 1.) * 255.).round() as u8;
+    return Some(format!("#{:02x}{:02x}{:02x}", channel(r), channel(g), channel(b)));
+}
+
+// arc commands to the end of an elliptical arc, arcs over half a turn are split so the flags
+// never have to pick between the two possible arcs
+fn arc_path(d: &mut String, radii: Vec2, rotation: f32, position: impl Fn(f32) -> Vec2, sweep: f32) {
+    let halves = if sweep.abs() > PI { 2 } else { 1 };
+    // counter clockwise in the plane is clockwise once y is flipped
+    let flag = if sweep > 0. { 0 } else { 1 };
+    for i in 1..=halves {
+        let end = position(i as f32 / halves as f32);
+        let _ = write!(
+            d,
+            " A {} {} {} 0 {} {}",
+            number(radii.x),
+            number(radii.y),
+            number(-rotation.to_degrees()),
+            flag,
+            point(end)
+        );
+    }
+}
+
+fn element(shape: &Shape) -> String {
+    match shape {
+        Shape::Line(a, b) => format!(
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"",
+            number(a.x),
+            number(-a.y),
+            number(b.x),
+            number(-b.y)
+        ),
+        Shape::Bezier(p) => format!(
+            "<path d=\"M {} C {} {} {}\"",
+            point(p[0]),
+            point(p[1]),
+            point(p[2]),
+            point(p[3])
+        ),
+        Shape::Circle { center, radius } => format!(
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\"",
+            number(center.x),
+            number(-center.y),
+            number(*radius)
+        ),
+        Shape::Arc { radius, sweep, .. } => {
+            let mut d = format!("M {}", point(shape.position(0.)));
+            arc_path(&mut d, Vec2::splat(*radius), 0., |t| shape.position(t), *sweep);
+            format!("<path d=\"{}\"", d)
+        }
+        Shape::Ellipse {
+            center,
+            major,
+            ratio,
+            sweep,
+            ..
+        } => {
+            let rotation = major.y.atan2(major.x);
+            let radii = Vec2::new(major.length(), major.length() * ratio);
+            if shape.is_closed() {
+                format!(
+                    "<ellipse cx=\"{}\" cy=\"{}\" rx=\"{}\" ry=\"{}\" transform=\"rotate({} {} {})\"",
+                    number(center.x),
+                    number(-center.y),
+                    number(radii.x),
+                    number(radii.y),
+                    number(-rotation.to_degrees()),
+                    number(center.x),
+                    number(-center.y)
+                )
+            } else {
+                let mut d = format!("M {}", point(shape.position(0.)));
+                arc_path(&mut d, radii, rotation, |t| shape.position(t), *sweep);
+                format!("<path d=\"{}\"", d)
+            }
+        }
+        Shape::Polyline(points) => {
+            let points: Vec<String> = points
+                .iter()
+                .map(|p| format!("{},{}", number(p.x), number(-p.y)))
+                .collect();
+            format!("<polyline points=\"{}\"", points.join(" "))
+        }
+    }
+}
+
+// one drawing in millimeters, the document size is given in the project unit so it opens in
+// true scale, styles with a color keep it
+pub fn export(shapes: &[SketchShape], styles: &[Component<Style>], unit: Unit) -> Result<String> {
+    let Some((min, max)) = bounds(shapes) else {
+        return Err(Error::Geometry("there is nothing to export on the sketch plane".into()));
+    };
+    let min = min - Vec2::splat(STROKE_WIDTH);
+    let size = max - min + Vec2::splat(STROKE_WIDTH);
+    // svg has no meters
+    let unit = match unit {
+        Unit::Meter => Unit::Millimeter,
+        unit => unit,
+    };
+    let scale = unit.to_mm() as f32;
+
+    let mut svg = String::new();
+    let _ = writeln!(svg, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}{}\" height=\"{}{}\" viewBox=\"{} {} {} {}\">",
+        number(size.x / scale),
+        unit.suffix(),
+        number(size.y / scale),
+        unit.suffix(),
+        number(min.x),
+        number(-max.y - STROKE_WIDTH),
+        number(size.x),
+        number(size.y)
+    );
+    let _ = writeln!(
+        svg,
+        " <g fill=\"none\" stroke=\"black\" stroke-width=\"{}\" stroke-linecap=\"round\">",
+        number(STROKE_WIDTH)
+    );
+    // a group per layer in the order they first appear
+    let mut layers: Vec<&str> = vec![];
+    for shape in shapes.iter() {
+        if !layers.contains(&shape.layer.as_str()) {
+            layers.push(&shape.layer);
+        }
+    }
+    for layer in layers {
+        let _ = writeln!(svg, "  <g id=\"{}\">", escape(layer));
+        for shape in shapes.iter().filter(|s| s.layer == layer) {
+            let stroke = styles
+                .get(shape.style as usize)
+                .and_then(|s| color(&s.data))
+                .map(|c| format!(" stroke=\"{}\"", c))
+                .unwrap_or_default();
+            let _ = writeln!(svg, "   {}{}/>", element(&shape.shape), stroke);
+        }
+        let _ = writeln!(svg, "  </g>");
+    }
+    let _ = writeln!(svg, " </g>");
+    let _ = writeln!(svg, "</svg>");
+    return Ok(svg);
+}
+
+// the unit of svg user units when importing
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SvgUnit {
+    // from the size and view box of the document
+    Document,
+    Pixel,
+    Millimeter,
+    Inch,
+}
+
+impl SvgUnit {
+    pub const ALL: [SvgUnit; 4] = [SvgUnit::Document, SvgUnit::Pixel, SvgUnit::Millimeter, SvgUnit::Inch];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SvgUnit::Document => "From document",
+            SvgUnit::Pixel => "Pixel (96 dpi)",
+            SvgUnit::Millimeter => "Millimeter",
+            SvgUnit::Inch => "Inch",
+        }
+    }
+}
+
+const PIXEL_MM: f32 = 25.4 / 96.;
+
+// x' = a x + c y + e, y' = b x + d y + f like the svg matrix(a b c d e f)
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Transform([f32; 6]);
+
+impl Transform {
+    const IDENTITY: Transform = Transform([1., 0., 0., 1., 0., 0.]);
+
+    fn apply(&self, p: Vec2) -> Vec2 {
+        let [a, b, c, d, e, f] = self.0;
+        return Vec2::new(a * p.x + c * p.y + e, b * p.x + d * p.y + f);
+    }
+
+    // applies inner first
+    fn then(&self, inner: &Transform) -> Transform {
+        let [a, b, c, d, e, f] = self.0;
+        let [a2, b2, c2, d2, e2, f2] = inner.0;
+        return Transform([
+            a * a2 + c * b2,
+            b * a2 + d * b2,
+            a * c2 + c * d2,
+            b * c2 + d * d2,
+            a * e2 + c * f2 + e,
+            b * e2 + d * f2 + f,
+        ]);
+    }
+
+    // the scale when circles stay circles
+    fn uniform_scale(&self) -> Option<f32> {
+        let [a, b, c, d, _, _] = self.0;
+        let scale = (a * d - b * c).abs().sqrt();
+        let tolerance = 1e-5 * scale.max(1.);
+        let rotation = (a - d).abs() < tolerance && (b + c).abs() < tolerance;
+        let reflection = (a + d).abs() < tolerance && (b - c).abs() < tolerance;
+        return (rotation || reflection).then_some(scale);
+    }
+}
+
+// numbers and single digit flags of path data and attribute lists
+struct Scanner<'a> {
+    text: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(text: &'a str) -> Scanner<'a> {
+        Scanner {
+            text: text.as_bytes(),
+            position: 0,
+        }
+    }
+
+    fn skip_separators(&mut self) {
+        while self.position < self.text.len() && (self.text[self.position].is_ascii_whitespace() || self.text[self.position] == b',') {
+            self.position += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_separators();
+        return self.text.get(self.position).copied();
+    }
+
+    fn command(&mut self) -> Option<u8> {
+        let c = self.peek().filter(|c| c.is_ascii_alphabetic())?;
+        self.position += 1;
+        return Some(c);
+    }
+
+    fn number(&mut self) -> Option<f32> {
+        self.peek()?;
+        let start = self.position;
+        let digits = |s: &mut Self| {
+            while s.position < s.text.len() && s.text[s.position].is_ascii_digit() {
+                s.position += 1;
+            }
+        };
+        if matches!(self.text[self.position], b'+' | b'-') {
+            self.position += 1;
+        }
+        digits(self);
+        if self.text.get(self.position) == Some(&b'.') {
+            self.position += 1;
+            digits(self);
+        }
+        if matches!(self.text.get(self.position), Some(b'e' | b'E')) {
+            let mantissa = self.position;
+            self.position += 1;
+            if matches!(self.text.get(self.position), Some(b'+' | b'-')) {
+                self.position += 1;
+            }
+            let exponent = self.position;
+            digits(self);
+            if self.position == exponent {
+                self.position = mantissa;
+            }
+        }
+        let number = std::str::from_utf8(&self.text[start..self.position]).ok()?.parse().ok();
+        if number.is_none() {
+            self.position = start;
+        }
+        return number;
+    }
+
+    fn pair(&mut self) -> Option<Vec2> {
+        Some(Vec2::new(self.number()?, self.number()?))
+    }
+
+    // arc flags can be written without separators like 0 01 10
+    fn flag(&mut self) -> Option<bool> {
+        let flag = match self.peek()? {
+            b'0' => false,
+            b'1' => true,
+            _ => return None,
+        };
+        self.position += 1;
+        return Some(flag);
+    }
+}
+
+fn numbers(text: &str) -> Vec<f32> {
+    let mut scanner = Scanner::new(text);
+    let mut numbers = vec![];
+    while let Some(n) = scanner.number() {
+        numbers.push(n);
+    }
+    return numbers;
+}
+
+fn parse_transform(text: &str) -> Transform {
+    let mut transform = Transform::IDENTITY;
+    for part in text.split(')') {
+        let Some((name, arguments)) = part.split_once('(') else {
+            continue;
+        };
+        let name = name.trim().trim_start_matches(',').trim();
+        let v = numbers(arguments);
+        let arg = |i: usize, default: f32| v.get(i).copied().unwrap_or(default);
+        let next = match name {
+            "matrix" if v.len() == 6 => Transform([v[0], v[1], v[2], v[3], v[4], v[5]]),
+            "translate" => Transform([1., 0., 0., 1., arg(0, 0.), arg(1, 0.)]),
+            "scale" => Transform([arg(0, 1.), 0., 0., arg(1, arg(0, 1.)), 0., 0.]),
+            "rotate" => {
+                let (sin, cos) = arg(0, 0.).to_radians().sin_cos();
+                let (cx, cy) = (arg(1, 0.), arg(2, 0.));
+                let rotation = Transform([cos, sin, -sin, cos, 0., 0.]);
+                Transform([1., 0., 0., 1., cx, cy])
+                    .then(&rotation)
+                    .then(&Transform([1., 0., 0., 1., -cx, -cy]))
+            }
+            "skewX" => Transform([1., 0., arg(0, 0.).to_radians().tan(), 1., 0., 0.]),
+            "skewY" => Transform([1., arg(0, 0.).to_radians().tan(), 0., 1., 0., 0.]),
+            _ => continue,
+        };
+        transform = transform.then(&next);
+    }
+    return transform;
+}
+
+// a length attribute in user units, units other than px are ignored like most viewers do
+fn length(value: Option<&str>) -> f32 {
+    value.and_then(|v| numbers(v).first().copied()).unwrap_or(0.)
+}
+
+// millimeters per unit of a length like 210mm
+fn unit_mm(value: &str) -> f32 {
+    let unit = value.trim().trim_start_matches(|c: char| c.is_ascii_digit() || "+-.eE".contains(c));
+    match unit.trim() {
+        "mm" => 1.,
+        "cm" => 10.,
+        "in" => 25.4,
+        "pt" => 25.4 / 72.,
+        "pc" => 25.4 / 6.,
+        _ => PIXEL_MM,
+    }
+}
+
+enum Segment {
+    Line(Vec2, Vec2),
+    Bezier([Vec2; 4]),
+    Circle(Vec2, f32),
+}
+
+// cubic pieces of at most a quarter turn of an ellipse rotated by rotation
+fn ellipse_beziers(center: Vec2, radii: Vec2, rotation: f32, start: f32, sweep: f32) -> Vec<Segment> {
+    let axis = Vec2::from_angle(rotation);
+    let position = |a: f32| center + axis.rotate(Vec2::new(radii.x * a.cos(), radii.y * a.sin()));
+    let derivative = |a: f32| axis.rotate(Vec2::new(-radii.x * a.sin(), radii.y * a.cos()));
+    let count = (sweep.abs() / (PI / 2.) - 1e-4).ceil().max(1.) as usize;
+    let delta = sweep / count as f32;
+    let k = 4. / 3. * (delta / 4.).tan();
+    return (0..count)
+        .map(|i| {
+            let (a, b) = (start + delta * i as f32, start + delta * (i + 1) as f32);
+            Segment::Bezier([
+                position(a),
+                position(a) + derivative(a) * k,
+                position(b) - derivative(b) * k,
+                position(b),
+            ])
+        })
+        .collect();
+}
+
+// endpoint to center parametrization from the svg implementation notes
+fn arc_segments(from: Vec2, radii: Vec2, rotation: f32, large: bool, sweep: bool, to: Vec2) -> Vec<Segment> {
+    if from.distance(to) < 1e-9 {
+        return vec![];
+    }
+    let mut radii = radii.abs();
+    if radii.x < 1e-9 || radii.y < 1e-9 {
+        return vec![Segment::Line(from, to)];
+    }
+    let rotation = rotation.to_radians();
+    let axis = Vec2::from_angle(rotation);
+    let p = Vec2::from_angle(-rotation).rotate((from - to) / 2.);
+    let lambda = (p / radii).length_squared();
+    if lambda > 1. {
+        radii *= lambda.sqrt();
+    }
+    let (rx2, ry2) = (radii.x * radii.x, radii.y * radii.y);
+    let numerator = rx2 * ry2 - rx2 * p.y * p.y - ry2 * p.x * p.x;
+    let denominator = rx2 * p.y * p.y + ry2 * p.x * p.x;
+    let sign = if large == sweep { -1. } else { 1. };
+    let coefficient = sign * (numerator / denominator).max(0.).sqrt();
+    let c = Vec2::new(radii.x * p.y / radii.y, -radii.y * p.x / radii.x) * coefficient;
+    let center = axis.rotate(c) + (from + to) / 2.;
+
+    let angle = |u: Vec2, v: Vec2| u.perp_dot(v).atan2(u.dot(v));
+    let u = (p - c) / radii;
+    let v = (-p - c) / radii;
+    let start = angle(Vec2::X, u);
+    let mut delta = angle(u, v);
+    if !sweep && delta > 0. {
+        delta -= 2. * PI;
+    } else if sweep && delta < 0. {
+        delta += 2. * PI;
+    }
+    let mut segments = ellipse_beziers(center, radii, rotation, start, delta);
+    // the end points have to meet exactly to be merged
+    if let Some(Segment::Bezier(first)) = segments.first_mut() {
+        first[0] = from;
+    }
+    if let Some(Segment::Bezier(last)) = segments.last_mut() {
+        last[3] = to;
+    }
+    return segments;
+}
+
+fn path_segments(d: &str) -> Result<Vec<Segment>> {
+    let invalid = || Error::File(format!("invalid path data '{}'", d));
+    let mut scanner = Scanner::new(d);
+    let mut segments = vec![];
+    let (mut current, mut start) = (Vec2::ZERO, Vec2::ZERO);
+    // the control points S and T reflect
+    let (mut last_cubic, mut last_quad): (Option<Vec2>, Option<Vec2>) = (None, None);
+    let mut command = 0u8;
+
+    while scanner.peek().is_some() {
+        match scanner.command() {
+            Some(c) => command = c,
+            // coordinates after a move are lines
+            None if command == b'M' => command = b'L',
+            None if command == b'm' => command = b'l',
+            None if command == 0 || command.to_ascii_uppercase() == b'Z' => return Err(invalid()),
+            None => {}
+        }
+        let base = if command.is_ascii_lowercase() { current } else { Vec2::ZERO };
+        let (mut cubic, mut quad) = (None, None);
+        match command.to_ascii_uppercase() {
+            b'M' => {
+                current = base + scanner.pair().ok_or_else(invalid)?;
+                start = current;
+            }
+            b'L' => {
+                let p = base + scanner.pair().ok_or_else(invalid)?;
+                segments.push(Segment::Line(current, p));
+                current = p;
+            }
+            b'H' | b'V' => {
+                let value = scanner.number().ok_or_else(invalid)?;
+                let mut p = current;
+                let relative = command.is_ascii_lowercase();
+                match (command.to_ascii_uppercase(), relative) {
+                    (b'H', true) => p.x += value,
+                    (b'H', false) => p.x = value,
+                    (_, true) => p.y += value,
+                    (_, false) => p.y = value,
+                }
+                segments.push(Segment::Line(current, p));
+                current = p;
+            }
+            b'C' | b'S' => {
+                let c1 = match command.to_ascii_uppercase() {
+                    b'C' => base + scanner.pair().ok_or_else(invalid)?,
+                    _ => last_cubic.map_or(current, |c| current * 2. - c),
+                };
+                let c2 = base + scanner.pair().ok_or_else(invalid)?;
+                let p = base + scanner.pair().ok_or_else(invalid)?;
+                segments.push(Segment::Bezier([current, c1, c2, p]));
+                cubic = Some(c2);
+                current = p;
+            }
+            b'Q' | b'T' => {
+                let q = match command.to_ascii_uppercase() {
+                    b'Q' => base + scanner.pair().ok_or_else(invalid)?,
+                    _ => last_quad.map_or(current, |q| current * 2. - q),
+                };
+                let p = base + scanner.pair().ok_or_else(invalid)?;
+                // degree elevation
+                let c1 = current.lerp(q, 2. / 3.);
+                let c2 = p.lerp(q, 2. / 3.);
+                segments.push(Segment::Bezier([current, c1, c2, p]));
+                quad = Some(q);
+                current = p;
+            }
+            b'A' => {
+                let radii = scanner.pair().ok_or_else(invalid)?;
+                let rotation = scanner.number().ok_or_else(invalid)?;
+                let large = scanner.flag().ok_or_else(invalid)?;
+                let sweep = scanner.flag().ok_or_else(invalid)?;
+                let p = base + scanner.pair().ok_or_else(invalid)?;
+                segments.extend(arc_segments(current, radii, rotation, large, sweep, p));
+                current = p;
+            }
+            b'Z' => {
+                if current.distance(start) > 1e-9 {
+                    segments.push(Segment::Line(current, start));
+                }
+                current = start;
+            }
+            _ => return Err(Error::File(format!("unknown path command '{}'", command as char))),
+        }
+        (last_cubic, last_quad) = (cubic, quad);
+    }
+    return Ok(segments);
+}
+
+fn point_list(points: &str, closed: bool) -> Vec<Segment> {
+    let values = numbers(points);
+    let mut points: Vec<Vec2> = values.chunks_exact(2).map(|p| Vec2::new(p[0], p[1])).collect();
+    if closed && points.len() > 2 {
+        points.push(points[0]);
+    }
+    return points.windows(2).map(|p| Segment::Line(p[0], p[1])).collect();
+}
+
+fn rect_segments(tag: &Tag) -> Vec<Segment> {
+    let (x, y) = (length(tag.attribute("x")), length(tag.attribute("y")));
+    let (w, h) = (length(tag.attribute("width")), length(tag.attribute("height")));
+    let (rx, ry) = (tag.attribute("rx").map(|v| length(Some(v))), tag.attribute("ry").map(|v| length(Some(v))));
+    let r = Vec2::new(rx.or(ry).unwrap_or(0.), ry.or(rx).unwrap_or(0.)).min(Vec2::new(w, h) / 2.);
+    if r.x <= 0. || r.y <= 0. {
+        let corners = format!("{},{} {},{} {},{} {},{}", x, y, x + w, y, x + w, y + h, x, y + h);
+        return point_list(&corners, true);
+    }
+    // straight sides between quarter ellipses, clockwise on screen from the top left
+    let mut segments = vec![];
+    let corners = [
+        (Vec2::new(x + w - r.x, y + r.y), -PI / 2.),
+        (Vec2::new(x + w - r.x, y + h - r.y), 0.),
+        (Vec2::new(x + r.x, y + h - r.y), PI / 2.),
+        (Vec2::new(x + r.x, y + r.y), PI),
+    ];
+    for (i, (center, angle)) in corners.iter().enumerate() {
+        let (next_center, next_angle) = corners[(i + 1) % 4];
+        segments.extend(ellipse_beziers(*center, r, 0., *angle, PI / 2.));
+        let end = *center + Vec2::from_angle(angle + PI / 2.) * r;
+        let next = next_center + Vec2::from_angle(next_angle) * r;
+        if end.distance(next) > 1e-9 {
+            segments.push(Segment::Line(end, next));
+        }
+    }
+    return segments;
+}
+
+// millimeters per user unit given by the size and view box of the root element
+fn document_scale(root: &Tag) -> f32 {
+    let Some(width) = root.attribute("width").filter(|w| !w.trim().ends_with('%')) else {
+        return PIXEL_MM;
+    };
+    let unit = unit_mm(width);
+    let view_box = root.attribute("viewBox").map(numbers).unwrap_or_default();
+    match view_box[..] {
+        [_, _, view_width, _] if view_width > 0. => length(Some(width)) * unit / view_width,
+        _ => unit,
+    }
+}
+
+// elements that are not drawn by themselves
+const HIDDEN: [&str; 9] = ["defs", "clipPath", "mask", "symbol", "marker", "pattern", "style", "title", "metadata"];
+
+// adds the outlines of the drawing onto the plane of the builder, y is flipped so the
+// drawing isn't mirrored and the scale multiplies the unit
+pub fn import(text: &str, builder: &mut SketchBuilder<'_>, unit: SvgUnit, scale: f32) -> Result<()> {
+    let tags = tags(text);
+    let Some(root) = tags.iter().find(|t| matches!(t, Tag::Start { name, .. } if name == "svg")) else {
+        return Err(Error::File("the file has no svg element".into()));
+    };
+    let mm = match unit {
+        SvgUnit::Document => document_scale(root),
+        SvgUnit::Pixel => PIXEL_MM,
+        SvgUnit::Millimeter => 1.,
+        SvgUnit::Inch => 25.4,
+    } * scale;
+
+    // transform of every open element and whether it hides its content
+    let mut stack = vec![(Transform([mm, 0., 0., -mm, 0., 0.]), false)];
+    let mut placed = vec![];
+    for tag in tags.iter() {
+        let Tag::Start { name, empty, .. } = tag else {
+            if stack.len() > 1 {
+                stack.pop();
+            }
+            continue;
+        };
+        let (parent, parent_hidden) = *stack.last().unwrap();
+        let transform = parent.then(&parse_transform(tag.attribute("transform").unwrap_or("")));
+        let hidden = parent_hidden
+            || HIDDEN.contains(&name.as_str())
+            || tag.attribute("display") == Some("none")
+            || tag.attribute("style").map_or(false, |s| s.replace(' ', "").contains("display:none"));
+        if !empty {
+            stack.push((transform, hidden));
+        }
+        if hidden {
+            continue;
+        }
+
+        let attribute = |key: &str| length(tag.attribute(key));
+        let segments = match name.as_str() {
+            "path" => path_segments(tag.attribute("d").unwrap_or(""))?,
+            "line" => vec![Segment::Line(
+                Vec2::new(attribute("x1"), attribute("y1")),
+                Vec2::new(attribute("x2"), attribute("y2")),
+            )],
+            "polyline" => point_list(tag.attribute("points").unwrap_or(""), false),
+            "polygon" => point_list(tag.attribute("points").unwrap_or(""), true),
+            "rect" => rect_segments(tag),
+            "circle" | "ellipse" => {
+                let center = Vec2::new(attribute("cx"), attribute("cy"));
+                let radii = match name.as_str() {
+                    "circle" => Vec2::splat(attribute("r")),
+                    _ => Vec2::new(attribute("rx"), attribute("ry")),
+                };
+                if radii.x <= 0. || radii.y <= 0. {
+                    continue;
+                }
+                match transform.uniform_scale() {
+                    Some(_) if radii.x == radii.y => vec![Segment::Circle(center, radii.x)],
+                    _ => ellipse_beziers(center, radii, 0., 0., 2. * PI),
+                }
+            }
+            _ => continue,
+        };
+
+        let scale = transform.uniform_scale().unwrap_or(1.);
+        placed.extend(segments.into_iter().map(|segment| match segment {
+            Segment::Line(a, b) => Segment::Line(transform.apply(a), transform.apply(b)),
+            Segment::Bezier(p) => Segment::Bezier(p.map(|p| transform.apply(p))),
+            Segment::Circle(center, radius) => Segment::Circle(transform.apply(center), radius * scale),
+        }));
+    }
+
+    // nothing is added when the file can't be read completely
+    for segment in placed {
+        match segment {
+            Segment::Line(a, b) => builder.line(a, b),
+            Segment::Bezier(p) => builder.bezier(p),
+            Segment::Circle(center, radius) => builder.circle(center, radius),
+        }
+    }
+    return Ok(());
+}
@@ -0,0 +1,73 @@
+use std::fmt::Write;
+
+use crate::core::units::Unit;
+
+use super::{
+    mesh::{index, NamedMesh},
+    xml::escape,
+    zip,
+};
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+  <Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+  <Default Extension="model" ContentType="application/vnd.ms-package.3dmanufacturing-3dmodel+xml"/>
+</Types>
+"#;
+
+const RELATIONSHIPS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Target="/3D/3dmodel.model" Id="rel0" Type="http://schemas.microsoft.com/3dmanufacturing/2013/01/3dmodel"/>
+</Relationships>
+"#;
+
+fn unit_name(unit: Unit) -> &'static str {
+    match unit {
+        Unit::Millimeter => "millimeter",
+        Unit::Centimeter => "centimeter",
+        Unit::Meter => "meter",
+        Unit::Inch => "inch",
+    }
+}
+
+// every mesh is an object placed once on the build plate, coordinates are in the project unit
+pub fn model(meshes: &[NamedMesh], unit: Unit) -> String {
+    let scale = 1. / unit.to_mm() as f32;
+    let mut xml = String::new();
+    let _ = writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    let _ = writeln!(
+        xml,
+        r#"<model unit="{}" xml:lang="en-US" xmlns="http://schemas.microsoft.com/3dmanufacturing/core/2015/02">"#,
+        unit_name(unit)
+    );
+    let _ = writeln!(xml, r#"  <metadata name="Application">f9</metadata>"#);
+    let _ = writeln!(xml, "  <resources>");
+    for (id, m) in meshes.iter().enumerate() {
+        let indexed = index(&m.mesh);
+        let _ = writeln!(xml, r#"    <object id="{}" type="model" name="{}">"#, id + 1, escape(&m.name));
+        let _ = writeln!(xml, "      <mesh>\n        <vertices>");
+        for v in indexed.positions.iter().map(|v| *v * scale) {
+            let _ = writeln!(xml, r#"          <vertex x="{}" y="{}" z="{}"/>"#, v.x, v.y, v.z);
+        }
+        let _ = writeln!(xml, "        </vertices>\n        <triangles>");
+        for t in indexed.triangles.iter() {
+            let _ = writeln!(xml, r#"          <triangle v1="{}" v2="{}" v3="{}"/>"#, t[0].0, t[1].0, t[2].0);
+        }
+        let _ = writeln!(xml, "        </triangles>\n      </mesh>\n    </object>");
+    }
+    let _ = writeln!(xml, "  </resources>\n  <build>");
+    for id in 0..meshes.len() {
+        let _ = writeln!(xml, r#"    <item objectid="{}"/>"#, id + 1);
+    }
+    let _ = writeln!(xml, "  </build>\n</model>");
+    return xml;
+}
+
+pub fn export(meshes: &[NamedMesh], unit: Unit) -> Vec<u8> {
+    let model = model(meshes, unit);
+    return zip::store(&[
+        ("[Content_Types].xml", CONTENT_TYPES.as_bytes()),
+        ("_rels/.rels", RELATIONSHIPS.as_bytes()),
+        ("3D/3dmodel.model", model.as_bytes()),
+    ]);
+}
@@ -0,0 +1,376 @@
+use glam::{vec2, Vec2};
+
+use crate::core::result::{Error, Result};
+
+// Ubuntu Light, see assets/fonts/UFL.txt
+pub const BUNDLED_FONT: &[u8] = include_bytes!("../../assets/fonts/Ubuntu-Light.ttf");
+
+// compound glyphs can nest, broken fonts could loop forever
+const MAX_COMPONENT_DEPTH: usize = 8;
+
+// a piece of a glyph outline in font units, truetype outlines only have quadratic curves
+#[derive(Clone, Copy, Debug)]
+pub enum GlyphSegment {
+    Line(Vec2, Vec2),
+    Quadratic(Vec2, Vec2, Vec2),
+}
+
+impl GlyphSegment {
+    pub fn start(&self) -> Vec2 {
+        match self {
+            GlyphSegment::Line(a, _) | GlyphSegment::Quadratic(a, _, _) => *a,
+        }
+    }
+
+    pub fn end(&self) -> Vec2 {
+        match self {
+            GlyphSegment::Line(_, b) | GlyphSegment::Quadratic(_, _, b) => *b,
+        }
+    }
+}
+
+// the same curve as a cubic bezier, the controls are 2/3 of the way to the quadratic control
+pub fn cubic(a: Vec2, control: Vec2, b: Vec2) -> [Vec2; 4] {
+    [a, a + (control - a) * (2. / 3.), b + (control - b) * (2. / 3.), b]
+}
+
+pub struct Glyph {
+    pub advance: f32,
+    // closed loops, the outer ones clockwise and the holes counter clockwise
+    pub contours: Vec<Vec<GlyphSegment>>,
+}
+
+// the tables of a truetype font that are needed for outlines, no hinting and no kerning
+pub struct Font<'a> {
+    data: &'a [u8],
+    glyf: usize,
+    loca: usize,
+    long_offsets: bool,
+    hmtx: usize,
+    metrics: u16,
+    glyphs: u16,
+    // the unicode subtable of the character map and its format, 4 or 12
+    cmap: usize,
+    cmap_format: u16,
+    pub units_per_em: f32,
+    pub ascender: f32,
+    pub descender: f32,
+    pub line_gap: f32,
+}
+
+fn broken() -> Error {
+    Error::File("the font is broken".into())
+}
+
+fn u16_at(data: &[u8], offset: usize) -> Result<u16> {
+    let bytes = data.get(offset..offset + 2).ok_or_else(broken)?;
+    return Ok(u16::from_be_bytes([bytes[0], bytes[1]]));
+}
+
+fn i16_at(data: &[u8], offset: usize) -> Result<i16> {
+    return Ok(u16_at(data, offset)? as i16);
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Result<u32> {
+    let bytes = data.get(offset..offset + 4).ok_or_else(broken)?;
+    return Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]));
+}
+
+// 2.14 fixed point
+fn f2dot14(data: &[u8], offset: usize) -> Result<f32> {
+    return Ok(i16_at(data, offset)? as f32 / 16384.);
+}
+
+fn table(data: &[u8], tag: &[u8; 4]) -> Result<usize> {
+    let count = u16_at(data, 4)? as usize;
+    for i in 0..count {
+        let record = 12 + 16 * i;
+        if data.get(record..record + 4) == Some(&tag[..]) {
+            return Ok(u32_at(data, record + 8)? as usize);
+        }
+    }
+    return Err(Error::File(format!("the font has no {} table", String::from_utf8_lossy(tag))));
+}
+
+// a unicode subtable, full unicode before the basic plane
+fn character_map(data: &[u8], cmap: usize) -> Result<(usize, u16)> {
+    let count = u16_at(data, cmap + 2)? as usize;
+    let mut found = None;
+    for i in 0..count {
+        let record = cmap + 4 + 8 * i;
+        let platform = u16_at(data, record)?;
+        let encoding = u16_at(data, record + 2)?;
+        let subtable = cmap + u32_at(data, record + 4)? as usize;
+        let format = u16_at(data, subtable)?;
+        let unicode = platform == 0 || (platform == 3 && (encoding == 1 || encoding == 10));
+        match format {
+            12 if unicode => return Ok((subtable, format)),
+            4 if unicode => found = Some((subtable, format)),
+            _ => {}
+        }
+    }
+    return found.ok_or_else(|| Error::File("the font has no unicode character map".into()));
+}
+
+impl<'a> Font<'a> {
+    pub fn parse(data: &'a [u8]) -> Result<Font<'a>> {
+        let head = table(data, b"head")?;
+        let hhea = table(data, b"hhea")?;
+        let maxp = table(data, b"maxp")?;
+        let cmap = table(data, b"cmap")?;
+        let (cmap, cmap_format) = character_map(data, cmap)?;
+        return Ok(Font {
+            data,
+            glyf: table(data, b"glyf")?,
+            loca: table(data, b"loca")?,
+            long_offsets: i16_at(data, head + 50)? == 1,
+            hmtx: table(data, b"hmtx")?,
+            metrics: u16_at(data, hhea + 34)?.max(1),
+            glyphs: u16_at(data, maxp + 4)?,
+            cmap,
+            cmap_format,
+            units_per_em: u16_at(data, head + 18)?.max(1) as f32,
+            ascender: i16_at(data, hhea + 4)? as f32,
+            descender: i16_at(data, hhea + 6)? as f32,
+            line_gap: i16_at(data, hhea + 8)? as f32,
+        });
+    }
+
+    pub fn bundled() -> Font<'static> {
+        return Font::parse(BUNDLED_FONT).expect("the bundled font is valid");
+    }
+
+    // 0 is the missing glyph
+    pub fn glyph_index(&self, c: char) -> Result<u16> {
+        let data = self.data;
+        let code = c as u32;
+        let subtable = self.cmap;
+        if self.cmap_format == 12 {
+            let groups = u32_at(data, subtable + 12)? as usize;
+            for i in 0..groups {
+                let group = subtable + 16 + 12 * i;
+                let start = u32_at(data, group)?;
+                let end = u32_at(data, group + 4)?;
+                if (start..=end).contains(&code) {
+                    return Ok((u32_at(data, group + 8)? + code - start) as u16);
+                }
+            }
+            return Ok(0);
+        }
+
+        if code > 0xffff {
+            return Ok(0);
+        }
+        let segments = u16_at(data, subtable + 6)? as usize;
+        let ends = subtable + 14;
+        let starts = ends + segments + 2;
+        let deltas = starts + segments;
+        let range_offsets = deltas + segments;
+        for i in 0..segments / 2 {
+            if (u16_at(data, ends + 2 * i)? as u32) < code {
+                continue;
+            }
+            let start = u16_at(data, starts + 2 * i)? as u32;
+            if start > code {
+                return Ok(0);
+            }
+            let delta = u16_at(data, deltas + 2 * i)?;
+            let range_offset = u16_at(data, range_offsets + 2 * i)? as usize;
+            if range_offset == 0 {
+                return Ok((code as u16).wrapping_add(delta));
+            }
+            // the offset is relative to its own position in the table
+            let address = range_offsets + 2 * i + range_offset + 2 * (code - start) as usize;
+            let glyph = u16_at(data, address)?;
+            return Ok(if glyph == 0 { 0 } else { glyph.wrapping_add(delta) });
+        }
+        return Ok(0);
+    }
+
+    pub fn advance(&self, glyph: u16) -> Result<f32> {
+        let metric = glyph.min(self.metrics - 1) as usize;
+        return Ok(u16_at(self.data, self.hmtx + 4 * metric)? as f32);
+    }
+
+    fn location(&self, glyph: u16) -> Result<(usize, usize)> {
+        let i = glyph as usize;
+        let (start, end) = match self.long_offsets {
+            true => (u32_at(self.data, self.loca + 4 * i)? as usize, u32_at(self.data, self.loca + 4 * i + 4)? as usize),
+            false => (
+                u16_at(self.data, self.loca + 2 * i)? as usize * 2,
+                u16_at(self.data, self.loca + 2 * i + 2)? as usize * 2,
+            ),
+        };
+        return Ok((self.glyf + start, self.glyf + end));
+    }
+
+    pub fn glyph(&self, c: char) -> Result<Glyph> {
+        let index = self.glyph_index(c)?;
+        let mut contours = vec![];
+        self.outline(index, [1., 0., 0., 1., 0., 0.], 0, &mut contours)?;
+        return Ok(Glyph {
+            advance: self.advance(index)?,
+            contours,
+        });
+    }
+
+    // the points of every contour with their on curve flag, transformed by a, b, c, d, dx, dy
+    fn outline(&self, glyph: u16, transform: [f32; 6], depth: usize, contours: &mut Vec<Vec<GlyphSegment>>) -> Result<()> {
+        if glyph >= self.glyphs || depth > MAX_COMPONENT_DEPTH {
+            return Err(broken());
+        }
+        let (start, end) = self.location(glyph)?;
+        // glyphs without outline like the space
+        if end <= start {
+            return Ok(());
+        }
+        let data = self.data;
+        let count = i16_at(data, start)?;
+        if count < 0 {
+            return self.compound(start + 10, transform, depth, contours);
+        }
+
+        let count = count as usize;
+        let mut ends = vec![];
+        for i in 0..count {
+            ends.push(u16_at(data, start + 10 + 2 * i)? as usize);
+        }
+        let points = ends.last().map_or(0, |e| e + 1);
+        let instructions = u16_at(data, start + 10 + 2 * count)? as usize;
+        let mut offset = start + 12 + 2 * count + instructions;
+
+        let mut flags = Vec::with_capacity(points);
+        while flags.len() < points {
+            let flag = *data.get(offset).ok_or_else(broken)?;
+            offset += 1;
+            flags.push(flag);
+            // the flag is repeated the number of times in the next byte
+            if flag & 8 != 0 {
+                let repeat = *data.get(offset).ok_or_else(broken)?;
+                offset += 1;
+                for _ in 0..repeat {
+                    flags.push(flag);
+                }
+            }
+        }
+        flags.truncate(points);
+
+        // coordinates are deltas, short ones are a byte with the sign in the flags
+        let mut coordinates = |short: u8, same: u8| -> Result<Vec<f32>> {
+            let mut values = Vec::with_capacity(points);
+            let mut value = 0i32;
+            for flag in flags.iter() {
+                if flag & short != 0 {
+                    let delta = *data.get(offset).ok_or_else(broken)? as i32;
+                    offset += 1;
+                    value += if flag & same != 0 { delta } else { -delta };
+                } else if flag & same == 0 {
+                    value += i16_at(data, offset)? as i32;
+                    offset += 2;
+                }
+                values.push(value as f32);
+            }
+            return Ok(values);
+        };
+        let xs = coordinates(2, 16)?;
+        let ys = coordinates(4, 32)?;
+
+        let [a, b, c, d, dx, dy] = transform;
+        let mut first = 0;
+        for end in ends {
+            let contour: Vec<(Vec2, bool)> = (first..=end.min(points - 1))
+                .map(|i| (vec2(a * xs[i] + c * ys[i] + dx, b * xs[i] + d * ys[i] + dy), flags[i] & 1 != 0))
+                .collect();
+            first = end + 1;
+            let segments = segments(&contour);
+            if !segments.is_empty() {
+                contours.push(segments);
+            }
+        }
+        return Ok(());
+    }
+
+    // glyphs built from other glyphs, like letters with accents
+    fn compound(&self, mut offset: usize, transform: [f32; 6], depth: usize, contours: &mut Vec<Vec<GlyphSegment>>) -> Result<()> {
+        let data = self.data;
+        loop {
+            let flags = u16_at(data, offset)?;
+            let glyph = u16_at(data, offset + 2)?;
+            offset += 4;
+            let (x, y) = if flags & 1 != 0 {
+                offset += 4;
+                (i16_at(data, offset - 4)? as f32, i16_at(data, offset - 2)? as f32)
+            } else {
+                let bytes = data.get(offset..offset + 2).ok_or_else(broken)?;
+                offset += 2;
+                (bytes[0] as i8 as f32, bytes[1] as i8 as f32)
+            };
+            // components placed by matching points are rare, they are left where they are
+            let (dx, dy) = if flags & 2 != 0 { (x, y) } else { (0., 0.) };
+            let (mut a, mut b, mut c, mut d) = (1., 0., 0., 1.);
+            if flags & 8 != 0 {
+                a = f2dot14(data, offset)?;
+                d = a;
+                offset += 2;
+            } else if flags & 0x40 != 0 {
+                a = f2dot14(data, offset)?;
+                d = f2dot14(data, offset + 2)?;
+                offset += 4;
+            } else if flags & 0x80 != 0 {
+                a = f2dot14(data, offset)?;
+                b = f2dot14(data, offset + 2)?;
+                c = f2dot14(data, offset + 4)?;
+                d = f2dot14(data, offset + 6)?;
+                offset += 8;
+            }
+            // the component transform followed by the one of the parent
+            let [pa, pb, pc, pd, pdx, pdy] = transform;
+            let combined = [
+                pa * a + pc * b,
+                pb * a + pd * b,
+                pa * c + pc * d,
+                pb * c + pd * d,
+                pa * dx + pc * dy + pdx,
+                pb * dx + pd * dy + pdy,
+            ];
+            self.outline(glyph, combined, depth + 1, contours)?;
+            if flags & 0x20 == 0 {
+                return Ok(());
+            }
+        }
+    }
+}
+
+// two off curve points in a row have an implied on curve point between them
+fn segments(contour: &[(Vec2, bool)]) -> Vec<GlyphSegment> {
+    let n = contour.len();
+    let mut points = vec![];
+    for i in 0..n {
+        let (p, on) = contour[i];
+        let (next, next_on) = contour[(i + 1) % n];
+        points.push((p, on));
+        if !on && !next_on {
+            points.push(((p + next) / 2., true));
+        }
+    }
+    let Some(start) = points.iter().position(|(_, on)| *on) else {
+        return vec![];
+    };
+    points.rotate_left(start);
+    points.push(points[0]);
+
+    let mut segments = vec![];
+    let mut i = 0;
+    while i + 1 < points.len() {
+        let (a, _) = points[i];
+        let (b, on) = points[i + 1];
+        if on {
+            segments.push(GlyphSegment::Line(a, b));
+            i += 1;
+        } else {
+            segments.push(GlyphSegment::Quadratic(a, b, points[i + 2].0));
+            i += 2;
+        }
+    }
+    return segments;
+}
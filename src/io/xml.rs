@@ -0,0 +1,91 @@
+// just enough xml for the drawings we read, tags with their attributes and no text content
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tag {
+    Start {
+        name: String,
+        attributes: Vec<(String, String)>,
+        // <tag/> has no end tag
+        empty: bool,
+    },
+    End(String),
+}
+
+impl Tag {
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        match self {
+            Tag::Start { attributes, .. } => {
+                attributes.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+            }
+            Tag::End(_) => None,
+        }
+    }
+}
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn attributes(text: &str) -> Vec<(String, String)> {
+    let mut attributes = vec![];
+    let mut rest = text.trim();
+    while let Some(equals) = rest.find('=') {
+        let key = rest[..equals].trim().to_string();
+        let value = rest[equals + 1..].trim_start();
+        let Some(quote) = value.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            break;
+        };
+        let Some(end) = value[1..].find(quote) else {
+            break;
+        };
+        attributes.push((key, unescape(&value[1..end + 1])));
+        rest = value[end + 2..].trim_start();
+    }
+    return attributes;
+}
+
+// comments, declarations, processing instructions and cdata are skipped
+pub fn tags(text: &str) -> Vec<Tag> {
+    let mut tags = vec![];
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start..];
+        let skip = [("<!--", "-->"), ("<![CDATA[", "]]>"), ("<?", "?>"), ("<!", ">")]
+            .iter()
+            .find(|(open, _)| rest.starts_with(open));
+        if let Some((_, close)) = skip {
+            match rest.find(close) {
+                Some(end) => rest = &rest[end + close.len()..],
+                None => break,
+            }
+            continue;
+        }
+
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+        if let Some(name) = tag.strip_prefix('/') {
+            tags.push(Tag::End(name.trim().to_string()));
+            continue;
+        }
+        let empty = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/');
+        let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+        tags.push(Tag::Start {
+            name: tag[..name_end].to_string(),
+            attributes: attributes(&tag[name_end..]),
+            empty,
+        });
+    }
+    return tags;
+}
@@ -0,0 +1,76 @@
+// a zip archive with stored entries, enough for office style packages like 3mf
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut table = [0u32; 256];
+    for (i, entry) in table.iter_mut().enumerate() {
+        let mut c = i as u32;
+        for _ in 0..8 {
+            c = if c & 1 != 0 { 0xedb88320 ^ (c >> 1) } else { c >> 1 };
+        }
+        *entry = c;
+    }
+    let mut crc = !0u32;
+    for byte in data {
+        crc = table[((crc ^ *byte as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+    return !crc;
+}
+
+fn u16le(zip: &mut Vec<u8>, v: u16) {
+    zip.extend_from_slice(&v.to_le_bytes());
+}
+
+fn u32le(zip: &mut Vec<u8>, v: u32) {
+    zip.extend_from_slice(&v.to_le_bytes());
+}
+
+// 1980-01-01 00:00, the earliest dos date
+const DOS_TIME: u16 = 0;
+const DOS_DATE: u16 = (1 << 5) | 1;
+
+pub fn store(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut zip = vec![];
+    let mut central = vec![];
+    for (name, data) in files {
+        let offset = zip.len() as u32;
+        let crc = crc32(data);
+        u32le(&mut zip, 0x04034b50);
+        // version 2.0, no flags, stored
+        for v in [20, 0, 0, DOS_TIME, DOS_DATE] {
+            u16le(&mut zip, v);
+        }
+        for v in [crc, data.len() as u32, data.len() as u32] {
+            u32le(&mut zip, v);
+        }
+        u16le(&mut zip, name.len() as u16);
+        u16le(&mut zip, 0);
+        zip.extend_from_slice(name.as_bytes());
+        zip.extend_from_slice(data);
+
+        u32le(&mut central, 0x02014b50);
+        for v in [20, 20, 0, 0, DOS_TIME, DOS_DATE] {
+            u16le(&mut central, v);
+        }
+        for v in [crc, data.len() as u32, data.len() as u32] {
+            u32le(&mut central, v);
+        }
+        // name, extra and comment length, disk, internal attributes
+        for v in [name.len() as u16, 0, 0, 0, 0] {
+            u16le(&mut central, v);
+        }
+        u32le(&mut central, 0);
+        u32le(&mut central, offset);
+        central.extend_from_slice(name.as_bytes());
+    }
+
+    let start = zip.len() as u32;
+    zip.extend_from_slice(&central);
+    u32le(&mut zip, 0x06054b50);
+    for v in [0, 0, files.len() as u16, files.len() as u16] {
+        u16le(&mut zip, v);
+    }
+    u32le(&mut zip, central.len() as u32);
+    u32le(&mut zip, start);
+    u16le(&mut zip, 0);
+    return zip;
+}
@@ -1,6 +1,10 @@
 #![warn(clippy::all, rust_2018_idioms)]
 pub mod core;
 pub mod camera;
+pub mod grid;
+pub mod parameters;
+pub mod solids;
+pub mod topology;
 pub mod app;
 pub mod rendering;
 pub mod ui;
@@ -9,5 +13,6 @@ pub mod component_collection;
 pub mod components;
 pub mod commands;
 pub mod dispatchers;
+pub mod io;
 
 pub use app::App;
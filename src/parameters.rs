@@ -0,0 +1,282 @@
+use std::collections::HashMap;
+
+use crate::{
+    component_collection::ComponentCollection,
+    core::{
+        expression::{rename_parameter, Expression, Quantity},
+        result::{Error, Result},
+        units::{Unit, UnitSystem},
+    },
+};
+
+pub struct Parameter {
+    pub name: String,
+    pub expression: String,
+    pub value: Result<Quantity>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BindingTarget {
+    PointX(u32),
+    PointY(u32),
+    PointZ(u32),
+    CircleRadius(u32),
+}
+
+pub struct Binding {
+    pub target: BindingTarget,
+    pub expression: String,
+    pub value: Result<f32>,
+}
+
+#[derive(Default)]
+pub struct Parameters {
+    pub parameters: Vec<Parameter>,
+    pub bindings: Vec<Binding>,
+    pub dirty: bool,
+}
+
+impl Parameters {
+    pub fn set(&mut self, name: &str, expression: &str) -> Result<()> {
+        check_name(name)?;
+        Expression::parse(expression)?;
+        match self.parameters.iter_mut().find(|p| p.name == name) {
+            Some(parameter) => parameter.expression = expression.into(),
+            None => self.parameters.push(Parameter {
+                name: name.into(),
+                expression: expression.into(),
+                value: Err(Error::NotFound),
+            }),
+        }
+        self.dirty = true;
+        return Ok(());
+    }
+
+    // a valid name that no other parameter has
+    pub fn check_rename(&self, index: usize, name: &str) -> Result<()> {
+        check_name(name)?;
+        if self
+            .parameters
+            .iter()
+            .enumerate()
+            .any(|(i, p)| i != index && p.name == name)
+        {
+            return Err(Error::Expression(format!("'{}' already exists", name)));
+        }
+        return Ok(());
+    }
+
+    // the expressions of the other parameters and the bindings follow the new name, ones that
+    // don't parse are left as they are
+    pub fn rename(&mut self, index: usize, name: &str) -> Result<()> {
+        self.check_rename(index, name)?;
+        let old = std::mem::replace(&mut self.parameters[index].name, name.into());
+        let expressions = self
+            .parameters
+            .iter_mut()
+            .map(|p| &mut p.expression)
+            .chain(self.bindings.iter_mut().map(|b| &mut b.expression));
+        for expression in expressions {
+            if let Ok(renamed) = rename_parameter(expression, &old, name) {
+                *expression = renamed;
+            }
+        }
+        self.dirty = true;
+        return Ok(());
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        self.parameters.remove(index);
+        self.dirty = true;
+    }
+
+    pub fn get(&self, name: &str) -> Option<Quantity> {
+        self.parameters
+            .iter()
+            .find(|p| p.name == name)
+            .and_then(|p| p.value.clone().ok())
+    }
+
+    pub fn binding(&self, target: BindingTarget) -> Option<&Binding> {
+        self.bindings.iter().find(|b| b.target == target)
+    }
+
+    pub fn evaluate_text(&self, expression: &str, unit: Unit) -> Result<f32> {
+        return self.evaluate_length(&Expression::parse(expression)?, unit);
+    }
+
+    // expressions without parameters are plain values and remove the binding
+    pub fn bind(&mut self, target: BindingTarget, expression: &str, unit: Unit) -> Result<f32> {
+        let parsed = Expression::parse(expression)?;
+        let value = self.evaluate_length(&parsed, unit)?;
+
+        self.bindings.retain(|b| b.target != target);
+        if !parsed.identifiers().is_empty() {
+            self.bindings.push(Binding {
+                target,
+                expression: expression.into(),
+                value: Ok(value),
+            });
+        }
+        return Ok(value);
+    }
+
+    fn evaluate_length(&self, expression: &Expression, unit: Unit) -> Result<f32> {
+        let quantity = expression.evaluate(unit, &|name| self.get(name))?;
+        return Ok(quantity.to_mm(unit)? as f32);
+    }
+
+    // evaluates all parameters in dependency order and writes bound values into the geometry
+    pub fn update(&mut self, units: &UnitSystem, components: &mut ComponentCollection) {
+        self.dirty = false;
+        self.evaluate(units.unit);
+
+        for i in 0..self.bindings.len() {
+            let value = Expression::parse(&self.bindings[i].expression)
+                .and_then(|e| self.evaluate_length(&e, units.unit));
+            if let Ok(value) = value {
+                apply(self.bindings[i].target, value, components);
+            }
+            self.bindings[i].value = value;
+        }
+    }
+
+    fn evaluate(&mut self, unit: Unit) {
+        let expressions: Vec<Result<Expression>> = self
+            .parameters
+            .iter()
+            .map(|p| Expression::parse(&p.expression))
+            .collect();
+        let index: HashMap<String, usize> = self
+            .parameters
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (p.name.clone(), i))
+            .collect();
+        let dependencies: Vec<Vec<usize>> = expressions
+            .iter()
+            .map(|e| match e {
+                Ok(e) => e
+                    .identifiers()
+                    .iter()
+                    .filter_map(|name| index.get(name).copied())
+                    .collect(),
+                Err(_) => vec![],
+            })
+            .collect();
+
+        // kahn's algorithm, whatever is left over is part of or depends on a cycle
+        let mut incoming: Vec<usize> = dependencies.iter().map(|d| d.len()).collect();
+        let mut order = vec![];
+        let mut queue: Vec<usize> = (0..incoming.len()).filter(|i| incoming[*i] == 0).collect();
+        while let Some(i) = queue.pop() {
+            order.push(i);
+            for (j, deps) in dependencies.iter().enumerate() {
+                for dep in deps.iter() {
+                    if *dep == i {
+                        incoming[j] -= 1;
+                        if incoming[j] == 0 {
+                            queue.push(j);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut values: HashMap<String, Quantity> = HashMap::new();
+        for i in order.iter() {
+            let broken = dependencies[*i]
+                .iter()
+                .find(|dep| self.parameters[**dep].value.is_err());
+            let value = match &expressions[*i] {
+                Ok(_) if broken.is_some() => Err(Error::Expression(format!(
+                    "'{}' has an error",
+                    self.parameters[*broken.unwrap()].name
+                ))),
+                Ok(expression) => expression.evaluate(unit, &|name| {
+                    if index.contains_key(name) && !values.contains_key(name) {
+                        return None;
+                    }
+                    values.get(name).copied()
+                }),
+                Err(error) => Err(error.clone()),
+            };
+            if let Ok(value) = value {
+                values.insert(self.parameters[*i].name.clone(), value);
+            }
+            self.parameters[*i].value = value;
+        }
+
+        for i in 0..self.parameters.len() {
+            if !order.contains(&i) {
+                let cycle = find_cycle(i, &dependencies)
+                    .iter()
+                    .map(|j| self.parameters[*j].name.clone())
+                    .collect();
+                self.parameters[i].value = Err(Error::Cycle(cycle));
+            }
+        }
+    }
+}
+
+fn find_cycle(start: usize, dependencies: &Vec<Vec<usize>>) -> Vec<usize> {
+    let mut path = vec![start];
+    let mut visited = vec![false; dependencies.len()];
+    if walk(start, dependencies, &mut visited, &mut path) {
+        return path;
+    }
+    return vec![start];
+}
+
+fn walk(
+    current: usize,
+    dependencies: &Vec<Vec<usize>>,
+    visited: &mut Vec<bool>,
+    path: &mut Vec<usize>,
+) -> bool {
+    visited[current] = true;
+    for dep in dependencies[current].iter() {
+        if let Some(position) = path.iter().position(|p| p == dep) {
+            path.drain(..position);
+            path.push(*dep);
+            return true;
+        }
+        if !visited[*dep] {
+            path.push(*dep);
+            if walk(*dep, dependencies, visited, path) {
+                return true;
+            }
+            path.pop();
+        }
+    }
+    return false;
+}
+
+fn apply(target: BindingTarget, value: f32, components: &mut ComponentCollection) {
+    match target {
+        BindingTarget::PointX(i) => {
+            components.points.update(i as usize, |p| p.data.position.x = value);
+        }
+        BindingTarget::PointY(i) => {
+            components.points.update(i as usize, |p| p.data.position.y = value);
+        }
+        BindingTarget::PointZ(i) => {
+            components.points.update(i as usize, |p| p.data.position.z = value);
+        }
+        BindingTarget::CircleRadius(i) => {
+            components.circles.update(i as usize, |c| c.data.radius = value);
+        }
+    }
+}
+
+pub fn check_name(name: &str) -> Result<()> {
+    let mut chars = name.chars();
+    let valid = match chars.next() {
+        Some(c) => (c.is_alphabetic() || c == '_') && chars.all(|c| c.is_alphanumeric() || c == '_'),
+        None => false,
+    };
+    if !valid || name == "pi" || Unit::from_suffix(name).is_some() {
+        return Err(Error::Expression(format!("invalid name '{}'", name)));
+    }
+    return Ok(());
+}
@@ -7,17 +7,34 @@ use uuid::Uuid;
 
 use crate::{
     camera::Camera,
-    component_collection::{ComponentArray, ComponentCollection},
-    components::{bezier, circle, line, point, vertex},
+    core::units::UnitSystem,
+    component_collection::{ComponentArray, ComponentCollection, Layers},
+    components::{bezier, circle, line, point, style, vertex},
+    topology::{fill::Fills, tessellation::Tessellation, text::Texts},
     dispatchers::dispatcher::{Disp, DispatcherEvent},
+    grid::Grid,
+    parameters::Parameters,
     rendering::{
         buffer::UniformBuffer,
+        line_style::LineStyle,
+        theme::Theme,
+        curve_tessellation::CurveTessellation,
+        index_buffer::IndexBuffer,
+        pixel_buffer::PixelBuffer,
         renderer::{get_layout, storage, storage_writeable, uniform, self, Renderer},
     },
 };
 
 pub struct ProjectState {
     pub camera: Camera,
+    pub grid: Grid,
+    pub line_style: LineStyle,
+    pub theme: Theme,
+    pub units: UnitSystem,
+    pub parameters: Parameters,
+    pub fills: Fills,
+    pub texts: Texts,
+    pub tessellation: Tessellation,
     pub components: ComponentCollection,
     pub uniform_buffer: Arc<UniformBuffer>,
     pub hover_pos: Vec2,
@@ -45,15 +62,8 @@ impl Project {
             ],
             device, queue,
         );
-        let grids = ComponentArray::new(
-            vec![
-                vertex::x().notvisible(),
-                vertex::x().notvisible(),
-                vertex::y(),
-                vertex::z().notvisible(),
-            ],
-            device, queue,
-        );
+        // the active sketch plane, synced from the camera plane every frame
+        let grids = ComponentArray::new(vec![vertex::y()], device, queue,);
         let arrows = ComponentArray::new(
             vec![
                 vertex::x().notvisible(),
@@ -88,6 +98,26 @@ impl Project {
             device, queue,
         );
 
+        let arcs = ComponentArray::new(vec![], device, queue,);
+        let ellipses = ComponentArray::new(vec![], device, queue,);
+        let nurbs = ComponentArray::new(vec![], device, queue,);
+        let nurbs_controls = ComponentArray::new(vec![], device, queue,);
+        let nurbs_knots = ComponentArray::new(vec![], device, queue,);
+        let faces = ComponentArray::new(vec![], device, queue,);
+        let face_vertices = ComponentArray::new(vec![], device, queue,);
+        let meshes = ComponentArray::new(vec![], device, queue,);
+        let mesh_vertices = ComponentArray::new(vec![], device, queue,);
+        let mesh_indices = IndexBuffer::new(device, queue);
+        let styles = ComponentArray::new(vec![style::default()], device, queue,);
+        let images = ComponentArray::new(vec![], device, queue,);
+        let image_pixels = PixelBuffer::new(device, queue);
+
+        let bezier_segments = CurveTessellation::new(beziers.array.len(), device, queue);
+        let circle_segments = CurveTessellation::new(circles.array.len(), device, queue);
+        let arc_segments = CurveTessellation::new(arcs.array.len(), device, queue);
+        let ellipse_segments = CurveTessellation::new(ellipses.array.len(), device, queue);
+        let nurbs_segments = CurveTessellation::new(nurbs.array.len(), device, queue);
+
         let components = ComponentCollection {
             axises,
             grids,
@@ -97,11 +127,30 @@ impl Project {
             lines,
             beziers,
             circles,
+            arcs,
+            ellipses,
+            nurbs,
+            nurbs_controls,
+            nurbs_knots,
+            faces,
+            face_vertices,
+            meshes,
+            mesh_vertices,
+            mesh_indices,
+            styles,
+            images,
+            image_pixels,
+            layers: Layers::default(),
+            bezier_segments,
+            circle_segments,
+            arc_segments,
+            ellipse_segments,
+            nurbs_segments,
             hovers: vec![],
             selected: vec![],
         };
 
-        let buffer = UniformBuffer::new(device, 4 * 16 + 4 * 16, &components, renderer);
+        let buffer = UniformBuffer::new(device, 4 * 16 * 4, &components, renderer);
 
         let (s, r): (Sender<DispatcherEvent>, Receiver<DispatcherEvent>) =
             async_channel::unbounded();
@@ -114,6 +163,14 @@ impl Project {
             name: "New Project".into(),
             state: ProjectState {
                 camera: Camera::default(),
+                grid: Grid::default(),
+                line_style: LineStyle::default(),
+                theme: Theme::default(),
+                units: UnitSystem::default(),
+                parameters: Parameters::default(),
+                fills: Fills::default(),
+                texts: Texts::default(),
+                tessellation: Tessellation::default(),
                 components,
                 uniform_buffer: Arc::new(buffer),
                 hover_pos: Vec2::ZERO,
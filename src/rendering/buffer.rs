@@ -9,6 +9,11 @@ use crate::{component_collection::ComponentCollection, components::component::Ho
 
 use super::renderer::{get_layout, storage_writeable, uniform, Renderer};
 
+// the most bytes a shader can bind of one storage buffer
+pub fn binding_limit(device: &Device) -> usize {
+    return device.limits().max_storage_buffer_binding_size as usize;
+}
+
 pub struct UniformBuffer {
     pub device: Arc<Device>,
     pub uniform_buffer: wgpu::Buffer,
@@ -67,13 +72,7 @@ impl UniformBuffer {
             ],
         });
 
-        let mut bind_groups = HashMap::new();
-        for shader in renderer.shaders.iter() {
-            bind_groups.insert(shader.label.to_string(), shader.get_bindgroup(device, components));
-        }
-        for shader in renderer.compute_shaders.iter() {
-            bind_groups.insert(shader.label.to_string(), shader.get_bindgroup(device, components));
-        }
+        let bind_groups = get_bind_groups(device, components, renderer);
 
         return Self {
             bind_groups,
@@ -86,6 +85,11 @@ impl UniformBuffer {
         };
     }
 
+    // component buffers are recreated when they grow, the bind groups have to follow
+    pub fn update_bind_groups(&mut self, components: &ComponentCollection, renderer: &Renderer) {
+        self.bind_groups = get_bind_groups(&self.device, components, renderer);
+    }
+
     pub fn clear_hover_counter(&self, queue: &wgpu::Queue) {
         queue.write_buffer(&self.atomic_buffer, 0, &[0, 0, 0, 0]);
     }
@@ -100,3 +104,18 @@ impl UniformBuffer {
         queue.write_buffer(&self.uniform_buffer, offset, bytemuck::cast_slice(mx_ref));
     }
 }
+
+fn get_bind_groups(
+    device: &Arc<Device>,
+    components: &ComponentCollection,
+    renderer: &Renderer,
+) -> HashMap<String, wgpu::BindGroup> {
+    let mut bind_groups = HashMap::new();
+    for shader in renderer.shaders.iter() {
+        bind_groups.insert(shader.label.to_string(), shader.get_bindgroup(device, components));
+    }
+    for shader in renderer.compute_shaders.iter() {
+        bind_groups.insert(shader.label.to_string(), shader.get_bindgroup(device, components));
+    }
+    return bind_groups;
+}
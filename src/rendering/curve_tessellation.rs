@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use eframe::wgpu::{self, BufferUsages, Device, Queue};
+
+use crate::core::result::{Error, Result};
+
+use super::buffer::binding_limit;
+
+// has to match MAX_SEGMENTS in common.wgsl
+pub const MAX_SEGMENTS: usize = 512;
+
+// the compute shader of a curve type picks a segment count for every curve from its size on
+// screen and appends the segments to one list, the render shader draws that list indirectly
+pub struct CurveTessellation {
+    // curve index, segment number with segment count and screen distance along the curve
+    pub segments: wgpu::Buffer,
+    // vertex count, instance count, first vertex and first instance of the draw
+    pub indirect: wgpu::Buffer,
+    capacity: usize,
+    // the most curves that were refused, so a refusal is only reported once
+    refused: usize,
+    // set when the segment buffer was recreated, bind groups using it have to be rebuilt
+    pub resized: bool,
+    device: Arc<Device>,
+    queue: Arc<Queue>,
+}
+
+fn segment_buffer(device: &Device, curves: usize) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("curve_segments"),
+        usage: BufferUsages::STORAGE,
+        mapped_at_creation: false,
+        size: (curves.max(1) * MAX_SEGMENTS * 12) as u64,
+    })
+}
+
+impl CurveTessellation {
+    pub fn new(curves: usize, device: &Arc<Device>, queue: &Arc<Queue>) -> CurveTessellation {
+        let indirect = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("curve_indirect"),
+            usage: BufferUsages::COPY_DST | BufferUsages::STORAGE | BufferUsages::INDIRECT,
+            mapped_at_creation: false,
+            size: 16,
+        });
+        let tessellation = CurveTessellation {
+            segments: segment_buffer(device, curves),
+            indirect,
+            capacity: curves.max(1),
+            refused: 0,
+            resized: false,
+            device: device.clone(),
+            queue: queue.clone(),
+        };
+        tessellation.reset();
+        return tessellation;
+    }
+
+    // the most curves whose segments fit into a buffer the shaders can bind
+    fn limit(&self) -> usize {
+        let bytes = binding_limit(&self.device).min(self.device.limits().max_buffer_size as usize);
+        return bytes / (MAX_SEGMENTS * 12);
+    }
+
+    // every curve can use up to MAX_SEGMENTS, so the list can't overflow, the buffer stops
+    // growing at the device limit and the curves past it are refused
+    pub fn reserve(&mut self, curves: usize) -> Result<()> {
+        let limit = self.limit();
+        if curves > self.capacity && self.capacity < limit {
+            self.capacity = curves.next_power_of_two().min(limit);
+            self.segments = segment_buffer(&self.device, self.capacity);
+            self.resized = true;
+        }
+        if curves > limit && curves > self.refused {
+            self.refused = curves;
+            return Err(Error::Memory(format!(
+                "only {} of {} curves can be drawn, there is no more gpu memory for their segments",
+                limit, curves
+            )));
+        }
+        return Ok(());
+    }
+
+    // the compute shaders count the vertices up from zero every frame
+    pub fn reset(&self) {
+        self.queue
+            .write_buffer(&self.indirect, 0, bytemuck::cast_slice(&[0u32, 1, 0, 0]));
+    }
+}
@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use eframe::wgpu::{self, BufferUsages, Device, Queue};
+
+use crate::core::result::{Error, Result};
+
+use super::buffer::binding_limit;
+
+// the triangle corners of all meshes as indices into the mesh vertices, meshes only get appended
+pub struct IndexBuffer {
+    pub indices: Vec<u32>,
+    pub buffer: wgpu::Buffer,
+    capacity: usize,
+    // set when the buffer was recreated, bind groups using it have to be rebuilt
+    pub resized: bool,
+    device: Arc<Device>,
+    queue: Arc<Queue>,
+}
+
+fn index_buffer(device: &Device, indices: usize) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("mesh_indices"),
+        usage: BufferUsages::COPY_DST | BufferUsages::STORAGE,
+        mapped_at_creation: false,
+        size: (indices.max(1) * 4) as u64,
+    })
+}
+
+impl IndexBuffer {
+    pub fn new(device: &Arc<Device>, queue: &Arc<Queue>) -> IndexBuffer {
+        IndexBuffer {
+            indices: vec![],
+            buffer: index_buffer(device, 1),
+            capacity: 1,
+            resized: false,
+            device: device.clone(),
+            queue: queue.clone(),
+        }
+    }
+
+    pub fn fits(&self, count: usize) -> bool {
+        return (self.indices.len() + count) * 4 <= binding_limit(&self.device);
+    }
+
+    // returns the position of the first appended index
+    pub fn append(&mut self, indices: &[u32]) -> Result<u32> {
+        if !self.fits(indices.len()) {
+            return Err(Error::Memory("the triangles don't fit into the mesh index buffer".into()));
+        }
+        let first = self.indices.len();
+        self.indices.extend_from_slice(indices);
+        if self.indices.len() > self.capacity {
+            // doubled to keep appending cheap, but never beyond what can be bound
+            let limit = binding_limit(&self.device) / 4;
+            self.capacity = self.indices.len().next_power_of_two().min(limit);
+            self.buffer = index_buffer(&self.device, self.capacity);
+            self.queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(&self.indices));
+            self.resized = true;
+        } else {
+            self.queue
+                .write_buffer(&self.buffer, (first * 4) as u64, bytemuck::cast_slice(indices));
+        }
+        return Ok(first as u32);
+    }
+}
@@ -0,0 +1,30 @@
+// how curves are drawn, widths and dashes are in screen pixels
+pub struct LineStyle {
+    pub width: f32,
+    // parts behind faces and solids are drawn dashed
+    pub hidden: bool,
+    pub dash: f32,
+    pub gap: f32,
+}
+
+impl Default for LineStyle {
+    fn default() -> Self {
+        Self {
+            width: 1.5,
+            hidden: true,
+            dash: 6.0,
+            gap: 4.0,
+        }
+    }
+}
+
+impl LineStyle {
+    pub fn uniforms(&self) -> [f32; 4] {
+        [
+            self.width,
+            if self.hidden { 1.0 } else { 0.0 },
+            self.dash,
+            self.gap,
+        ]
+    }
+}
@@ -1,3 +1,8 @@
 pub mod renderer;
 pub mod buffer;
-pub mod buffer_reader;
\ No newline at end of file
+pub mod buffer_reader;
+pub mod curve_tessellation;
+pub mod index_buffer;
+pub mod line_style;
+pub mod pixel_buffer;
+pub mod theme;
\ No newline at end of file
@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use eframe::wgpu::{self, BufferUsages, Device, Queue};
+
+use crate::core::result::{Error, Result};
+
+use super::buffer::binding_limit;
+
+// the texels of all reference images, rgba8 packed into one u32 each, the texels of removed
+// images are reused by the next ones that fit
+pub struct PixelBuffer {
+    pub pixels: Vec<u32>,
+    pub buffer: wgpu::Buffer,
+    capacity: usize,
+    // unused ranges (first, count) below the end, sorted and never touching each other
+    free: Vec<(usize, usize)>,
+    // set when the buffer was recreated, bind groups using it have to be rebuilt
+    pub resized: bool,
+    device: Arc<Device>,
+    queue: Arc<Queue>,
+}
+
+fn pixel_buffer(device: &Device, pixels: usize) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("image_pixels"),
+        usage: BufferUsages::COPY_DST | BufferUsages::STORAGE,
+        mapped_at_creation: false,
+        size: (pixels.max(1) * 4) as u64,
+    })
+}
+
+impl PixelBuffer {
+    pub fn new(device: &Arc<Device>, queue: &Arc<Queue>) -> PixelBuffer {
+        PixelBuffer {
+            pixels: vec![],
+            buffer: pixel_buffer(device, 1),
+            capacity: 1,
+            free: vec![],
+            resized: false,
+            device: device.clone(),
+            queue: queue.clone(),
+        }
+    }
+
+    // returns the index of the first pixel, the smallest free range that fits is used before the
+    // buffer grows, it grows only as much as needed and never beyond what a shader can bind
+    pub fn append(&mut self, pixels: &[u32]) -> Result<u32> {
+        let fitting = self
+            .free
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, count))| *count >= pixels.len())
+            .min_by_key(|(_, (_, count))| *count)
+            .map(|(i, _)| i);
+        if let Some(i) = fitting {
+            let (first, count) = self.free[i];
+            if count == pixels.len() {
+                self.free.remove(i);
+            } else {
+                self.free[i] = (first + pixels.len(), count - pixels.len());
+            }
+            self.pixels[first..first + pixels.len()].copy_from_slice(pixels);
+            self.queue
+                .write_buffer(&self.buffer, (first * 4) as u64, bytemuck::cast_slice(pixels));
+            return Ok(first as u32);
+        }
+
+        let first = self.pixels.len();
+        if (first + pixels.len()) * 4 > binding_limit(&self.device) {
+            return Err(Error::Memory(
+                "the picture doesn't fit into the gpu memory left for pictures, remove others first".into(),
+            ));
+        }
+        self.pixels.extend_from_slice(pixels);
+        if self.pixels.len() > self.capacity {
+            self.capacity = self.pixels.len();
+            self.buffer = pixel_buffer(&self.device, self.capacity);
+            self.queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(&self.pixels));
+            self.resized = true;
+        } else {
+            self.queue
+                .write_buffer(&self.buffer, (first * 4) as u64, bytemuck::cast_slice(pixels));
+        }
+        return Ok(first as u32);
+    }
+
+    // gives the pixels of a removed image back, neighbouring free ranges are merged
+    pub fn release(&mut self, first: usize, count: usize) {
+        let i = self.free.partition_point(|(f, _)| *f < first);
+        self.free.insert(i, (first, count));
+        if i + 1 < self.free.len() && first + count == self.free[i + 1].0 {
+            self.free[i].1 += self.free.remove(i + 1).1;
+        }
+        if i > 0 && self.free[i - 1].0 + self.free[i - 1].1 == first {
+            self.free[i - 1].1 += self.free.remove(i).1;
+        }
+        // a free range at the end shrinks the pixels, the buffer keeps its size
+        if let Some((first, count)) = self.free.last().copied() {
+            if first + count == self.pixels.len() {
+                self.pixels.truncate(first);
+                self.free.pop();
+            }
+        }
+    }
+}
@@ -16,6 +16,8 @@ use crate::{
     project::{self, Project},
 };
 
+use super::curve_tessellation::CurveTessellation;
+
 pub struct ComputeShader<T> {
     // RenderPipeline, ComputePipeline
     pub pipeline: T,
@@ -23,6 +25,8 @@ pub struct ComputeShader<T> {
     pub storage_count: u32,
     pub get_buffers: Box<dyn Fn(&ComponentCollection) -> Vec<&wgpu::Buffer> + Send + Sync>,
     pub get_bindgroup: Box<dyn Fn(&Device, u32) -> BindGroupLayout + Send + Sync>,
+    // draw arguments written on the gpu, replaces get_draw_count for render shaders
+    pub get_indirect: Option<Box<dyn Fn(&ComponentCollection) -> &wgpu::Buffer + Send + Sync>>,
     pub label: &'static str,
 }
 
@@ -79,11 +83,22 @@ pub fn new_compute_shader(
                 wgpu::BufferBindingType::Storage { read_only: false },
             )
         }),
+        get_indirect: None,
         storage_count,
         label,
     }
 }
 
+#[derive(Clone, Copy, PartialEq)]
+pub enum ShaderPass {
+    // vert_main and frag_main with the usual depth test
+    Visible,
+    // vert_hidden and frag_hidden, only where something else is in front
+    Hidden,
+    // vert_main and frag_main without writing depth, everything drawn later covers it
+    Underlay,
+}
+
 pub fn new_shader(
     device: &Arc<Device>,
     state: &RenderState,
@@ -93,6 +108,30 @@ pub fn new_shader(
     get_draw_count: &'static (dyn Fn(&Project) -> u32 + Send + Sync),
     get_buffers: &'static (dyn Fn(&ComponentCollection) -> Vec<&wgpu::Buffer> + Send + Sync),
     storage_count: u32,
+) -> ComputeShader<RenderPipeline> {
+    return new_pass_shader(
+        device,
+        state,
+        label,
+        source,
+        topology,
+        ShaderPass::Visible,
+        get_draw_count,
+        get_buffers,
+        storage_count,
+    );
+}
+
+pub fn new_pass_shader(
+    device: &Arc<Device>,
+    state: &RenderState,
+    label: &'static str,
+    source: &str,
+    topology: PrimitiveTopology,
+    pass: ShaderPass,
+    get_draw_count: &'static (dyn Fn(&Project) -> u32 + Send + Sync),
+    get_buffers: &'static (dyn Fn(&ComponentCollection) -> Vec<&wgpu::Buffer> + Send + Sync),
+    storage_count: u32,
 ) -> ComputeShader<RenderPipeline> {
     ComputeShader {
         pipeline: build_shader(
@@ -107,6 +146,7 @@ pub fn new_shader(
                 wgpu::BufferBindingType::Storage { read_only: true },
             ),
             topology,
+            pass,
         ),
         get_draw_count: Box::new(|p| {
             let count = (get_draw_count)(p);
@@ -121,11 +161,38 @@ pub fn new_shader(
                 wgpu::BufferBindingType::Storage { read_only: true },
             )
         }),
+        get_indirect: None,
         storage_count,
         label,
     }
 }
 
+// a curve shader drawing the segments its compute shader listed in the tessellation
+pub fn new_curve_shader(
+    device: &Arc<Device>,
+    state: &RenderState,
+    label: &'static str,
+    source: &str,
+    pass: ShaderPass,
+    get_tessellation: &'static (dyn Fn(&ComponentCollection) -> &CurveTessellation + Send + Sync),
+    get_buffers: &'static (dyn Fn(&ComponentCollection) -> Vec<&wgpu::Buffer> + Send + Sync),
+    storage_count: u32,
+) -> ComputeShader<RenderPipeline> {
+    let mut shader = new_pass_shader(
+        device,
+        state,
+        label,
+        source,
+        PrimitiveTopology::TriangleList,
+        pass,
+        &|_| 0,
+        get_buffers,
+        storage_count,
+    );
+    shader.get_indirect = Some(Box::new(|components| &get_tessellation(components).indirect));
+    return shader;
+}
+
 pub struct Renderer {
     pub shaders: Vec<ComputeShader<RenderPipeline>>,
     pub compute_shaders: Vec<ComputeShader<ComputePipeline>>,
@@ -150,6 +217,18 @@ impl Renderer {
                 include_str!("./../shaders/uv_sphere.wgsl"),
                 &|project| 8 * 8 * 4,
             ),*/
+            // reference images first, they have no compute shader so they are never hovered
+            new_pass_shader(
+                device,
+                state,
+                "image",
+                include_str!("./../shaders/image.wgsl"),
+                PrimitiveTopology::TriangleList,
+                ShaderPass::Underlay,
+                &|project| project.state.components.images.array.len() as u32 * 6,
+                &|components| vec![&components.images.buffer, &components.image_pixels.buffer],
+                2,
+            ),
             new_shader(
                 device,
                 state,
@@ -160,13 +239,47 @@ impl Renderer {
                 &|components| vec![&components.axises.buffer],
                 1,
             ),
+            new_shader(
+                device,
+                state,
+                "mesh",
+                include_str!("./../shaders/mesh.wgsl"),
+                PrimitiveTopology::TriangleList,
+                &|project| project.state.components.mesh_indices.indices.len() as u32,
+                &|components| {
+                    vec![
+                        &components.meshes.buffer,
+                        &components.mesh_vertices.buffer,
+                        &components.mesh_indices.buffer,
+                        &components.styles.buffer,
+                    ]
+                },
+                4,
+            ),
+            // before the grid, which writes depth on the sketch plane
+            new_shader(
+                device,
+                state,
+                "fill",
+                include_str!("./../shaders/fill.wgsl"),
+                PrimitiveTopology::TriangleList,
+                &|project| project.state.components.face_vertices.array.len() as u32,
+                &|components| vec![&components.faces.buffer, &components.face_vertices.buffer],
+                2,
+            ),
             new_shader(
                 device,
                 state,
                 "grid",
                 include_str!("./../shaders/grid.wgsl"),
-                PrimitiveTopology::LineList,
-                &|project| project.state.components.grids.array.len() as u32 * 11 * 2 * 2,
+                PrimitiveTopology::TriangleList,
+                &|project| {
+                    if project.state.grid.visible {
+                        project.state.components.grids.array.len() as u32 * 6
+                    } else {
+                        0
+                    }
+                },
                 &|components| vec![&components.grids.buffer],
                 1,
             ),
@@ -177,38 +290,235 @@ impl Renderer {
                 include_str!("./../shaders/point.wgsl"),
                 PrimitiveTopology::TriangleList,
                 &|project| project.state.components.points.array.len() as u32 * 6,
-                &|components| vec![&components.points.buffer],
-                1,
+                &|components| vec![&components.points.buffer, &components.styles.buffer],
+                2,
             ),
-            new_shader(
+            new_pass_shader(
                 device,
                 state,
                 "line",
                 include_str!("./../shaders/line.wgsl"),
-                PrimitiveTopology::LineList,
-                &|project| project.state.components.lines.array.len() as u32 * 2,
-                &|components| vec![&components.points.buffer, &components.lines.buffer],
-                2,
+                PrimitiveTopology::TriangleList,
+                ShaderPass::Visible,
+                &|project| project.state.components.lines.array.len() as u32 * 6,
+                &|components| {
+                    vec![
+                        &components.points.buffer,
+                        &components.lines.buffer,
+                        &components.styles.buffer,
+                    ]
+                },
+                3,
             ),
-            new_shader(
+            // the curves are drawn with the segments their compute shaders listed
+            new_curve_shader(
                 device,
                 state,
                 "bezier",
                 include_str!("./../shaders/bezier.wgsl"),
-                PrimitiveTopology::LineList,
-                &|project| project.state.components.beziers.array.len() as u32 * 2 * 51,
-                &|components| vec![&components.points.buffer, &components.beziers.buffer],
-                2,
+                ShaderPass::Visible,
+                &|components| &components.bezier_segments,
+                &|components| {
+                    vec![
+                        &components.points.buffer,
+                        &components.beziers.buffer,
+                        &components.bezier_segments.segments,
+                        &components.styles.buffer,
+                    ]
+                },
+                4,
             ),
-            new_shader(
+            new_curve_shader(
                 device,
                 state,
                 "circle",
                 include_str!("./../shaders/circle.wgsl"),
-                PrimitiveTopology::LineList,
-                &|project| project.state.components.circles.array.len() as u32 * 2 * 51,
-                &|components| vec![&components.points.buffer, &components.circles.buffer],
-                2,
+                ShaderPass::Visible,
+                &|components| &components.circle_segments,
+                &|components| {
+                    vec![
+                        &components.points.buffer,
+                        &components.circles.buffer,
+                        &components.circle_segments.segments,
+                        &components.styles.buffer,
+                    ]
+                },
+                4,
+            ),
+            new_curve_shader(
+                device,
+                state,
+                "arc",
+                include_str!("./../shaders/arc.wgsl"),
+                ShaderPass::Visible,
+                &|components| &components.arc_segments,
+                &|components| {
+                    vec![
+                        &components.points.buffer,
+                        &components.arcs.buffer,
+                        &components.arc_segments.segments,
+                        &components.styles.buffer,
+                    ]
+                },
+                4,
+            ),
+            new_curve_shader(
+                device,
+                state,
+                "ellipse",
+                include_str!("./../shaders/ellipse.wgsl"),
+                ShaderPass::Visible,
+                &|components| &components.ellipse_segments,
+                &|components| {
+                    vec![
+                        &components.points.buffer,
+                        &components.ellipses.buffer,
+                        &components.ellipse_segments.segments,
+                        &components.styles.buffer,
+                    ]
+                },
+                4,
+            ),
+            new_curve_shader(
+                device,
+                state,
+                "nurbs",
+                include_str!("./../shaders/nurbs.wgsl"),
+                ShaderPass::Visible,
+                &|components| &components.nurbs_segments,
+                &|components| {
+                    vec![
+                        &components.points.buffer,
+                        &components.nurbs.buffer,
+                        &components.nurbs_controls.buffer,
+                        &components.nurbs_knots.buffer,
+                        &components.nurbs_segments.segments,
+                        &components.styles.buffer,
+                    ]
+                },
+                6,
+            ),
+            new_shader(
+                device,
+                state,
+                "nurbs_polygon",
+                include_str!("./../shaders/nurbs_polygon.wgsl"),
+                PrimitiveTopology::TriangleList,
+                &|project| project.state.components.nurbs_controls.array.len() as u32 * 6,
+                &|components| {
+                    vec![
+                        &components.points.buffer,
+                        &components.nurbs.buffer,
+                        &components.nurbs_controls.buffer,
+                        &components.nurbs_knots.buffer,
+                    ]
+                },
+                4,
+            ),
+            // dashed where faces or solids are in front, after everything that writes depth
+            new_pass_shader(
+                device,
+                state,
+                "line_hidden",
+                include_str!("./../shaders/line.wgsl"),
+                PrimitiveTopology::TriangleList,
+                ShaderPass::Hidden,
+                &|project| project.state.components.lines.array.len() as u32 * 6,
+                &|components| {
+                    vec![
+                        &components.points.buffer,
+                        &components.lines.buffer,
+                        &components.styles.buffer,
+                    ]
+                },
+                3,
+            ),
+            new_curve_shader(
+                device,
+                state,
+                "bezier_hidden",
+                include_str!("./../shaders/bezier.wgsl"),
+                ShaderPass::Hidden,
+                &|components| &components.bezier_segments,
+                &|components| {
+                    vec![
+                        &components.points.buffer,
+                        &components.beziers.buffer,
+                        &components.bezier_segments.segments,
+                        &components.styles.buffer,
+                    ]
+                },
+                4,
+            ),
+            new_curve_shader(
+                device,
+                state,
+                "circle_hidden",
+                include_str!("./../shaders/circle.wgsl"),
+                ShaderPass::Hidden,
+                &|components| &components.circle_segments,
+                &|components| {
+                    vec![
+                        &components.points.buffer,
+                        &components.circles.buffer,
+                        &components.circle_segments.segments,
+                        &components.styles.buffer,
+                    ]
+                },
+                4,
+            ),
+            new_curve_shader(
+                device,
+                state,
+                "arc_hidden",
+                include_str!("./../shaders/arc.wgsl"),
+                ShaderPass::Hidden,
+                &|components| &components.arc_segments,
+                &|components| {
+                    vec![
+                        &components.points.buffer,
+                        &components.arcs.buffer,
+                        &components.arc_segments.segments,
+                        &components.styles.buffer,
+                    ]
+                },
+                4,
+            ),
+            new_curve_shader(
+                device,
+                state,
+                "ellipse_hidden",
+                include_str!("./../shaders/ellipse.wgsl"),
+                ShaderPass::Hidden,
+                &|components| &components.ellipse_segments,
+                &|components| {
+                    vec![
+                        &components.points.buffer,
+                        &components.ellipses.buffer,
+                        &components.ellipse_segments.segments,
+                        &components.styles.buffer,
+                    ]
+                },
+                4,
+            ),
+            new_curve_shader(
+                device,
+                state,
+                "nurbs_hidden",
+                include_str!("./../shaders/nurbs.wgsl"),
+                ShaderPass::Hidden,
+                &|components| &components.nurbs_segments,
+                &|components| {
+                    vec![
+                        &components.points.buffer,
+                        &components.nurbs.buffer,
+                        &components.nurbs_controls.buffer,
+                        &components.nurbs_knots.buffer,
+                        &components.nurbs_segments.segments,
+                        &components.styles.buffer,
+                    ]
+                },
+                6,
             ),
             new_shader(
                 device,
@@ -246,25 +556,114 @@ impl Renderer {
                 "line_com",
                 include_str!("./../shaders/line_com.wgsl"),
                 &|project| (project.state.components.lines.array.len() as u32, 1, 1),
-                &|components| vec![&components.points.buffer, &components.lines.buffer],
-                2,
+                &|components| {
+                    vec![
+                        &components.points.buffer,
+                        &components.lines.buffer,
+                        &components.styles.buffer,
+                    ]
+                },
+                3,
             ),
             new_compute_shader(
                 device,
                 "bezier_com",
                 include_str!("./../shaders/bezier_com.wgsl"),
                 &|project| (project.state.components.beziers.array.len() as u32, 1, 1),
-                &|components| vec![&components.points.buffer, &components.beziers.buffer],
-                2,
+                &|components| {
+                    vec![
+                        &components.points.buffer,
+                        &components.beziers.buffer,
+                        &components.bezier_segments.segments,
+                        &components.bezier_segments.indirect,
+                    ]
+                },
+                4,
             ),
             new_compute_shader(
                 device,
                 "circle_com",
                 include_str!("./../shaders/circle_com.wgsl"),
                 &|project| (project.state.components.circles.array.len() as u32, 1, 1),
-                &|components| vec![&components.points.buffer, &components.circles.buffer],
+                &|components| {
+                    vec![
+                        &components.points.buffer,
+                        &components.circles.buffer,
+                        &components.circle_segments.segments,
+                        &components.circle_segments.indirect,
+                    ]
+                },
+                4,
+            ),
+            new_compute_shader(
+                device,
+                "arc_com",
+                include_str!("./../shaders/arc_com.wgsl"),
+                &|project| (project.state.components.arcs.array.len() as u32, 1, 1),
+                &|components| {
+                    vec![
+                        &components.points.buffer,
+                        &components.arcs.buffer,
+                        &components.arc_segments.segments,
+                        &components.arc_segments.indirect,
+                    ]
+                },
+                4,
+            ),
+            new_compute_shader(
+                device,
+                "ellipse_com",
+                include_str!("./../shaders/ellipse_com.wgsl"),
+                &|project| (project.state.components.ellipses.array.len() as u32, 1, 1),
+                &|components| {
+                    vec![
+                        &components.points.buffer,
+                        &components.ellipses.buffer,
+                        &components.ellipse_segments.segments,
+                        &components.ellipse_segments.indirect,
+                    ]
+                },
+                4,
+            ),
+            new_compute_shader(
+                device,
+                "nurbs_com",
+                include_str!("./../shaders/nurbs_com.wgsl"),
+                &|project| (project.state.components.nurbs.array.len() as u32, 1, 1),
+                &|components| {
+                    vec![
+                        &components.points.buffer,
+                        &components.nurbs.buffer,
+                        &components.nurbs_controls.buffer,
+                        &components.nurbs_knots.buffer,
+                        &components.nurbs_segments.segments,
+                        &components.nurbs_segments.indirect,
+                    ]
+                },
+                6,
+            ),
+            new_compute_shader(
+                device,
+                "fill_com",
+                include_str!("./../shaders/fill_com.wgsl"),
+                &|project| (project.state.components.faces.array.len() as u32, 1, 1),
+                &|components| vec![&components.faces.buffer, &components.face_vertices.buffer],
                 2,
             ),
+            new_compute_shader(
+                device,
+                "mesh_com",
+                include_str!("./../shaders/mesh_com.wgsl"),
+                &|project| (project.state.components.meshes.array.len() as u32, 1, 1),
+                &|components| {
+                    vec![
+                        &components.meshes.buffer,
+                        &components.mesh_vertices.buffer,
+                        &components.mesh_indices.buffer,
+                    ]
+                },
+                3,
+            ),
             new_compute_shader(
                 device,
                 "arrow_com",
@@ -323,8 +722,13 @@ impl Renderer {
                 &[],
             );
             pass.set_pipeline(&shader.pipeline);
-            let draw_count = (shader.get_draw_count)(&project);
-            pass.draw(0..draw_count.0, 0..1);
+            match &shader.get_indirect {
+                Some(get_indirect) => pass.draw_indirect(get_indirect(&project.state.components), 0),
+                None => {
+                    let draw_count = (shader.get_draw_count)(&project);
+                    pass.draw(0..draw_count.0, 0..1);
+                }
+            }
         }
     }
 }
@@ -437,7 +841,14 @@ pub fn build_shader(
     source: &str,
     layout: &BindGroupLayout,
     topology: wgpu::PrimitiveTopology,
+    pass: ShaderPass,
 ) -> RenderPipeline {
+    let (vertex_entry, fragment_entry, depth_compare) = match pass {
+        ShaderPass::Visible => ("vert_main", "frag_main", wgpu::CompareFunction::Less),
+        ShaderPass::Hidden => ("vert_hidden", "frag_hidden", wgpu::CompareFunction::Greater),
+        ShaderPass::Underlay => ("vert_main", "frag_main", wgpu::CompareFunction::Less),
+    };
+
     let shader = device.create_shader_module(ShaderModuleDescriptor {
         label: Some(label),
         source: ShaderSource::Wgsl(source.into()),
@@ -454,12 +865,12 @@ pub fn build_shader(
         ),
         vertex: wgpu::VertexState {
             module: &shader,
-            entry_point: "vert_main",
+            entry_point: vertex_entry,
             buffers: &[],
         },
         fragment: Some(wgpu::FragmentState {
             module: &shader,
-            entry_point: "frag_main",
+            entry_point: fragment_entry,
             targets: //&[Some(state.target_format.into())],
             &[
                 Some(wgpu::ColorTargetState{
@@ -487,8 +898,8 @@ pub fn build_shader(
         },
         depth_stencil: Some(DepthStencilState {
             format: TextureFormat::Depth32Float,
-            depth_write_enabled: true,
-            depth_compare: wgpu::CompareFunction::Less,
+            depth_write_enabled: pass == ShaderPass::Visible,
+            depth_compare,
             stencil: wgpu::StencilState::default(),
             bias: wgpu::DepthBiasState::default(),
         }),
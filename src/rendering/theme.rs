@@ -0,0 +1,26 @@
+// colors that are not part of a component style, unmultiplied rgba
+pub struct Theme {
+    pub normal: [f32; 4],
+    pub hover: [f32; 4],
+    pub selected: [f32; 4],
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            normal: [1.0, 1.0, 1.0, 1.0],
+            hover: [1.0, 0.0, 0.0, 1.0],
+            selected: [0.0, 0.0, 1.0, 1.0],
+        }
+    }
+}
+
+impl Theme {
+    pub fn uniforms(&self) -> [f32; 12] {
+        let mut uniforms = [0.0; 12];
+        uniforms[0..4].copy_from_slice(&self.normal);
+        uniforms[4..8].copy_from_slice(&self.hover);
+        uniforms[8..12].copy_from_slice(&self.selected);
+        return uniforms;
+    }
+}
@@ -0,0 +1,42 @@
+@group(0) @binding(0) var<uniform> uniforms : Uniforms;
+@group(1) @binding(0) var<storage, read> pointBuffer : PointBuffer;
+@group(1) @binding(1) var<storage, read> arcBuffer : ArcBuffer;
+@group(1) @binding(2) var<storage, read> segmentBuffer : SegmentBuffer;
+@group(1) @binding(3) var<storage, read> styleBuffer : StyleBuffer;
+
+fn curve_position(arc : Arc, t : f32) -> vec3f {
+  let center = point_position(pointBuffer.values[arc.center]);
+  let start = point_position(pointBuffer.values[arc.start]);
+  let end = point_position(pointBuffer.values[arc.end]);
+  let orientation = vec3f(arc.orientation_x, arc.orientation_y, arc.orientation_z);
+  return arc_position(center, start, end, orientation, t);
+}
+
+fn segment_vertex(i : u32, depth_offset : f32) -> LineVertex {
+  let segment = segmentBuffer.values[i / 6u];
+  let arc = arcBuffer.values[segment.curve];
+  let a = curve_position(arc, segment_parameter(segment, 0u));
+  let b = curve_position(arc, segment_parameter(segment, 1u));
+  return line_vertex(a, b, i, segment.offset, arc.flags, styleBuffer.values[arc.style], depth_offset);
+}
+
+// the segments were listed by arc_com.wgsl
+@vertex
+fn vert_main(@builtin(vertex_index) i : u32) -> LineVertex {
+  return segment_vertex(i, 0.0);
+}
+
+@vertex
+fn vert_hidden(@builtin(vertex_index) i : u32) -> LineVertex {
+  return segment_vertex(i, HIDDEN_DEPTH_OFFSET);
+}
+
+@fragment
+fn frag_main(v : LineVertex) -> @location(0) vec4f {
+  return line_fragment(v);
+}
+
+@fragment
+fn frag_hidden(v : LineVertex) -> @location(0) vec4f {
+  return hidden_line_fragment(v);
+}
@@ -0,0 +1,66 @@
+@group(0) @binding(0) var<uniform> uniforms : Uniforms;
+@group(1) @binding(0) var<storage, read_write> hoverCounter : AtomicCounter;
+@group(1) @binding(1) var<storage, read_write> hoverBuffer : HoverBuffer;
+@group(2) @binding(0) var<storage, read_write> pointBuffer : PointBuffer;
+@group(2) @binding(1) var<storage, read_write> arcBuffer : ArcBuffer;
+@group(2) @binding(2) var<storage, read_write> segmentBuffer : SegmentBuffer;
+@group(2) @binding(3) var<storage, read_write> drawIndirect : DrawIndirect;
+
+fn curve_position(arc : Arc, t : f32) -> vec3f {
+  let center = point_position(pointBuffer.values[arc.center]);
+  let start = point_position(pointBuffer.values[arc.start]);
+  let end = point_position(pointBuffer.values[arc.end]);
+  let orientation = vec3f(arc.orientation_x, arc.orientation_y, arc.orientation_z);
+  return arc_position(center, start, end, orientation, t);
+}
+
+@compute @workgroup_size(1, 1)
+fn main(@builtin(global_invocation_id) i : vec3<u32>) {
+  let arc = arcBuffer.values[i.x];
+
+  // the segment count follows the length on screen
+  let samples = MEASURE_SAMPLES;
+  let start = to_screen_position(curve_position(arc, 0.0));
+  var a = start;
+  var length = 0.0;
+  for (var k : u32 = 1u; k <= samples; k = k + 1u) {
+    let b = to_screen_position(curve_position(arc, f32(k) / f32(samples)));
+    length = length + distance(a, b);
+    a = b;
+  }
+  let segments = segment_count(length);
+  // six vertices for the quad of every segment
+  let first = atomicAdd(&drawIndirect.vertex_count, segments * 6u) / 6u;
+
+  // list the segments for arc.wgsl and test the mouse against them
+  let mouse = vec2(uniforms.mouse_x, uniforms.mouse_y);
+  var d = 1000000.0;
+  var t = 0.0;
+  var offset = 0.0;
+  a = start;
+  for (var s : u32 = 0u; s < segments; s = s + 1u) {
+    segmentBuffer.values[first + s] = pack_segment(i.x, s, segments, offset);
+    let b = to_screen_position(curve_position(arc, f32(s + 1u) / f32(segments)));
+    offset = offset + distance(a, b);
+    let closest = segment_distance(a, b, mouse);
+    if (closest.x < d) {
+      d = closest.x;
+      t = (f32(s) + closest.y) / f32(segments);
+    }
+    a = b;
+  }
+  let pos = curve_position(arc, t);
+
+  if(d <= 20. && (arc.flags & 8) == 0){
+    arcBuffer.values[i.x].flags = arcBuffer.values[i.x].flags | 2;
+    let hover_index = atomicAdd(&hoverCounter.counter, 1u);
+    hoverBuffer.values[hover_index].index = i.x;
+    hoverBuffer.values[hover_index].ctype = 9; // arc
+    hoverBuffer.values[hover_index].distance = distance(pos, vec3f(uniforms.camera_origin_x, uniforms.camera_origin_y, uniforms.camera_origin_z));
+    hoverBuffer.values[hover_index].position_x = pos.x;
+    hoverBuffer.values[hover_index].position_y = pos.y;
+    hoverBuffer.values[hover_index].position_z = pos.z;
+  } else {
+    arcBuffer.values[i.x].flags = arcBuffer.values[i.x].flags & (~2);
+  }
+}
@@ -41,7 +41,7 @@ fn vert_main(@builtin(vertex_index) i : u32) -> VertexOutput {
 fn frag_main(v: VertexOutput) -> @location(0) vec4f {
   var color = vec4f(1.0, 1.0, 1.0, 1.0);
   if ((v.flags & 2) == 2){ // hover
-    color =  vec4f(1.0, 0.0, 0.0, 1.0);
+    color =  uniforms.theme_hover;
   }
   return color;
 }
\ No newline at end of file
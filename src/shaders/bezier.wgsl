@@ -1,44 +1,45 @@
-struct VertexOutput {
-  @builtin(position) position : vec4f,
-  @location(0) @interpolate(flat) flags : i32,
-};
-
 @group(0) @binding(0) var<uniform> uniforms : Uniforms;
 @group(1) @binding(0) var<storage, read> pointBuffer : PointBuffer;
 @group(1) @binding(1) var<storage, read> bezierBuffer : BezierBuffer;
+@group(1) @binding(2) var<storage, read> segmentBuffer : SegmentBuffer;
+@group(1) @binding(3) var<storage, read> styleBuffer : StyleBuffer;
+
+fn curve_position(bezier : Bezier, t : f32) -> vec3f {
+  let point_a = point_position(pointBuffer.values[bezier.point_a]);
+  let point_b = point_position(pointBuffer.values[bezier.point_b]);
+  let control_a = point_position(pointBuffer.values[bezier.control_a]);
+  let control_b = point_position(pointBuffer.values[bezier.control_b]);
+  return pow(1.0 - t, 3.0) * point_a
+      + 3.0 * pow(1.0 - t, 2.0) * t * control_a
+      + 3.0 * (1.0 - t) * pow(t, 2.0) * control_b
+      + pow(t, 3.0) * point_b;
+}
 
-fn get_position(t : f32, point_a : vec3f, point_b : vec3f, control_a : vec3f, control_b : vec3f) -> vec3f{
-    let position = pow(1.0 - t, 3.0) * point_a
-        + 3.0 * pow(1.0 - t, 2.0) * t * control_a
-        + 3.0 * (1.0 - t) * pow(t, 2.0) * control_b
-        + pow(t, 3.0) * point_b;
-    return position;
+fn segment_vertex(i : u32, depth_offset : f32) -> LineVertex {
+  let segment = segmentBuffer.values[i / 6u];
+  let bezier = bezierBuffer.values[segment.curve];
+  let a = curve_position(bezier, segment_parameter(segment, 0u));
+  let b = curve_position(bezier, segment_parameter(segment, 1u));
+  return line_vertex(a, b, i, segment.offset, bezier.flags, styleBuffer.values[bezier.style], depth_offset);
 }
 
+// the segments were listed by bezier_com.wgsl
 @vertex
-fn vert_main(@builtin(vertex_index) i : u32) -> VertexOutput {
-  let bezier = bezierBuffer.values[i / u32(51)];
-  let point_a = vec3f(pointBuffer.values[bezier.point_a].px, pointBuffer.values[bezier.point_a].py, pointBuffer.values[bezier.point_a].pz);
-  let point_b = vec3f(pointBuffer.values[bezier.point_b].px, pointBuffer.values[bezier.point_b].py, pointBuffer.values[bezier.point_b].pz);
-  let control_a = vec3f(pointBuffer.values[bezier.control_a].px, pointBuffer.values[bezier.control_a].py, pointBuffer.values[bezier.control_a].pz);
-  let control_b = vec3f(pointBuffer.values[bezier.control_b].px, pointBuffer.values[bezier.control_b].py, pointBuffer.values[bezier.control_b].pz);
+fn vert_main(@builtin(vertex_index) i : u32) -> LineVertex {
+  return segment_vertex(i, 0.0);
+}
 
-  let t = f32(i % u32(51)) / 50.0;
+@vertex
+fn vert_hidden(@builtin(vertex_index) i : u32) -> LineVertex {
+  return segment_vertex(i, HIDDEN_DEPTH_OFFSET);
+}
 
-  var output : VertexOutput;
-  output.position = uniforms.matrix * vec4f(get_position(t, point_a, point_b, control_a, control_b), 1.0);
-  output.flags = bezier.flags;
-  return output;
+@fragment
+fn frag_main(v : LineVertex) -> @location(0) vec4f {
+  return line_fragment(v);
 }
 
 @fragment
-fn frag_main(v: VertexOutput) -> @location(0) vec4f {
-  var color = vec4f(1.0, 1.0, 1.0, 1.0);
-  if ((v.flags & 4) == 4){ // selected
-    color =  vec4f(0.0, 0.0, 1.0, 1.0);
-  }
-  if ((v.flags & 2) == 2){ // hover
-    color =  vec4f(1.0, 0.0, 0.0, 1.0);
-  }
-  return color;
-}
\ No newline at end of file
+fn frag_hidden(v : LineVertex) -> @location(0) vec4f {
+  return hidden_line_fragment(v);
+}
@@ -3,72 +3,59 @@
 @group(1) @binding(1) var<storage, read_write> hoverBuffer : HoverBuffer;
 @group(2) @binding(0) var<storage, read_write> pointBuffer : PointBuffer;
 @group(2) @binding(1) var<storage, read_write> bezierBuffer : BezierBuffer;
+@group(2) @binding(2) var<storage, read_write> segmentBuffer : SegmentBuffer;
+@group(2) @binding(3) var<storage, read_write> drawIndirect : DrawIndirect;
 
-fn get_position(P0: vec3f, P1: vec3f, P2: vec3f, P3: vec3f, t : f32) -> vec3f {
-    let position = pow(1.0 - t, 3.0) * P0
-        + 3.0 * pow(1.0 - t, 2.0) * t * P1
-        + 3.0 * (1.0 - t) * pow(t, 2.0) * P2
-        + pow(t, 3.0) * P3;
-    return position;
-}
-
-fn cubicBezier(P0 : vec2f, P1 : vec2f, P2 : vec2f, P3 : vec2f, t : f32) -> vec2f {
-    let u = 1.0 - t;
-    let tt = t * t;
-    let uu = u * u;
-    let uuu = uu * u;
-    let ttt = tt * t;
-    let Q = uuu * P0 + 3.0 * uu * t * P1 + 3.0 * u * tt * P2 + ttt * P3;
-    return Q;
-}
-
-fn cubicBezierDerivative(P0 : vec2f, P1 : vec2f, P2 : vec2f, P3 : vec2f, t : f32) -> vec2f {
-    return -3.0 * pow(1.0 - t, 2.0) * P0 + 
-           (3.0 * pow(1.0 - t, 2.0) - 6.0 * t * (1.0 - t)) * P1 + 
-           (6.0 * t * (1.0 - t) - 3.0 * t * t) * P2 + 
-           3.0 * t * t * P3;
-}
-
-fn findClosestT(P0 : vec2f, P1 : vec2f, P2 : vec2f, P3 : vec2f, target_: vec2f, epsilon : f32, maxIterations : u32) -> f32 {
-    var t = 0.5; // Initial guess
-    for(var i: u32 = 0u; i < maxIterations; i = i + 1u) {
-        let Q = cubicBezier(P0, P1, P2, P3, t);
-        let Qprime = cubicBezierDerivative(P0, P1, P2, P3, t);
-        let numerator = dot(Q - target_, Qprime);
-        let denominator = dot(Qprime, Qprime);
-        if (abs(numerator) < epsilon || denominator == 0.0) {
-            break;
-        }
-        t -= numerator / denominator;
-    }
-
-    return min(1.0, max(0.0, t));
+fn curve_position(bezier : Bezier, t : f32) -> vec3f {
+  let point_a = point_position(pointBuffer.values[bezier.point_a]);
+  let point_b = point_position(pointBuffer.values[bezier.point_b]);
+  let control_a = point_position(pointBuffer.values[bezier.control_a]);
+  let control_b = point_position(pointBuffer.values[bezier.control_b]);
+  return pow(1.0 - t, 3.0) * point_a
+      + 3.0 * pow(1.0 - t, 2.0) * t * control_a
+      + 3.0 * (1.0 - t) * pow(t, 2.0) * control_b
+      + pow(t, 3.0) * point_b;
 }
 
 @compute @workgroup_size(1, 1)
 fn main(@builtin(global_invocation_id) i : vec3<u32>) {
   let bezier = bezierBuffer.values[i.x];
 
-  let point_a = vec3f(pointBuffer.values[bezier.point_a].px, pointBuffer.values[bezier.point_a].py, pointBuffer.values[bezier.point_a].pz);
-  let point_b = vec3f(pointBuffer.values[bezier.point_b].px, pointBuffer.values[bezier.point_b].py, pointBuffer.values[bezier.point_b].pz);
-  let control_a = vec3f(pointBuffer.values[bezier.control_a].px, pointBuffer.values[bezier.control_a].py, pointBuffer.values[bezier.control_a].pz);
-  let control_b = vec3f(pointBuffer.values[bezier.control_b].px, pointBuffer.values[bezier.control_b].py, pointBuffer.values[bezier.control_b].pz);
-
-  let p_a = to_screen_position(point_a);
-  let p_b = to_screen_position(point_b);
-  let c_a = to_screen_position(control_a);
-  let c_b = to_screen_position(control_b);
-
-  let mouse_pos = vec2(uniforms.mouse_x, uniforms.mouse_y);
+  // the segment count follows the length on screen
+  let samples = MEASURE_SAMPLES;
+  let start = to_screen_position(curve_position(bezier, 0.0));
+  var a = start;
+  var length = 0.0;
+  for (var k : u32 = 1u; k <= samples; k = k + 1u) {
+    let b = to_screen_position(curve_position(bezier, f32(k) / f32(samples)));
+    length = length + distance(a, b);
+    a = b;
+  }
+  let segments = segment_count(length);
+  // six vertices for the quad of every segment
+  let first = atomicAdd(&drawIndirect.vertex_count, segments * 6u) / 6u;
 
-  let t = findClosestT(p_a, c_a, c_b, p_b, mouse_pos, 1.0, 30u);
-  let position = cubicBezier(p_a, c_a, c_b, p_b, t);
-  let d = distance(position, mouse_pos);
+  // list the segments for bezier.wgsl and test the mouse against them
+  let mouse = vec2(uniforms.mouse_x, uniforms.mouse_y);
+  var d = 1000000.0;
+  var t = 0.0;
+  var offset = 0.0;
+  a = start;
+  for (var s : u32 = 0u; s < segments; s = s + 1u) {
+    segmentBuffer.values[first + s] = pack_segment(i.x, s, segments, offset);
+    let b = to_screen_position(curve_position(bezier, f32(s + 1u) / f32(segments)));
+    offset = offset + distance(a, b);
+    let closest = segment_distance(a, b, mouse);
+    if (closest.x < d) {
+      d = closest.x;
+      t = (f32(s) + closest.y) / f32(segments);
+    }
+    a = b;
+  }
+  let pos = curve_position(bezier, t);
 
-  if(d <= 20.){
+  if(d <= 20. && (bezier.flags & 8) == 0){
     bezierBuffer.values[i.x].flags = bezierBuffer.values[i.x].flags | 2;
-
-    let pos = get_position(point_a, control_a, control_b, point_b, t);
     let hover_index = atomicAdd(&hoverCounter.counter, 1u);
     hoverBuffer.values[hover_index].index = i.x;
     hoverBuffer.values[hover_index].ctype = 4; // bezier
@@ -79,5 +66,4 @@ fn main(@builtin(global_invocation_id) i : vec3<u32>) {
   } else {
     bezierBuffer.values[i.x].flags = bezierBuffer.values[i.x].flags & (~2);
   }
-
-}
\ No newline at end of file
+}
@@ -3,52 +3,51 @@
 @group(1) @binding(1) var<storage, read_write> hoverBuffer : HoverBuffer;
 @group(2) @binding(0) var<storage, read_write> pointBuffer : PointBuffer;
 @group(2) @binding(1) var<storage, read_write> circleBuffer : CircleBuffer;
+@group(2) @binding(2) var<storage, read_write> segmentBuffer : SegmentBuffer;
+@group(2) @binding(3) var<storage, read_write> drawIndirect : DrawIndirect;
 
-fn get_circle(t : f32, orientation : vec3f, heightFactor : f32, center : vec3f, radius : f32) -> vec3f {
-    let x = (2.0 * PI * t);
-    let orient = shift(orientation) * cos(x) + double_shift(orientation) * sin(x) + orientation * heightFactor * t;
-    let position = center + radius * orient;
-    return position;
-}
-
-fn newtonMinDist(c : vec3f, o1 : vec3f, o2: vec3f, o3: vec3f, r : f32, h : f32, m : vec3f, b : vec3f) -> f32 {
-    var t1 = 0.0;
-    var min_dist: f32 = 1000000.0;
-    for (var i : u32 = 0u; i < 360u; i = i + 1u) {
-        let angle = f32(i) * 0.0174532925; // Umrechnung von Grad in Radiant
-        let t1_temp = angle / (2.0 * PI);
-        let k_t1 = c + r * (o1 * cos(2.0 * PI * t1_temp) + o2 * sin(2.0 * PI * t1_temp) + o3 * h * t1_temp);
-        let t2_temp = dot(k_t1 - b, m) / dot(m, m);
-        let s_t2 = m * t2_temp + b;
-        let dist = distance(k_t1, s_t2);
-
-        if (dist < min_dist) {
-            min_dist = dist;
-            t1 = t1_temp;
-        }
-    }
-
-return t1;
+fn curve_position(circle : Circle, t : f32) -> vec3f {
+  return circle_position(circle, point_position(pointBuffer.values[circle.center]), t);
 }
 
 @compute @workgroup_size(1, 1)
 fn main(@builtin(global_invocation_id) i : vec3<u32>) {
   let circle = circleBuffer.values[i.x];
 
-  let center = vec3f(pointBuffer.values[circle.center].px, pointBuffer.values[circle.center].py, pointBuffer.values[circle.center].pz);
-  let radius = circle.radius;
-  let orient = vec3f(circle.orientation_x, circle.orientation_y, circle.orientation_z);
-  let heightfactor = circle.heightfactor;
-
-  let camera_dir = vec3f(uniforms.camera_orient_x, uniforms.camera_orient_y, uniforms.camera_orient_z);
-  let camera_origin = vec3f(uniforms.camera_origin_x, uniforms.camera_origin_y, uniforms.camera_origin_z);
-
-  let t = newtonMinDist(center, shift(orient), double_shift(orient), orient, radius, heightfactor, camera_dir, camera_origin);
-  let pos = get_circle(t, orient, heightfactor, center, radius);
-  let position = to_screen_position(pos);
-  let d = distance(position, vec2(uniforms.mouse_x, uniforms.mouse_y));
+  // the segment count follows the length on screen, helices are measured per turn
+  let samples = MEASURE_SAMPLES * u32(clamp(ceil(abs(circle.turns)), 1.0, 20.0));
+  let start = to_screen_position(curve_position(circle, 0.0));
+  var a = start;
+  var length = 0.0;
+  for (var k : u32 = 1u; k <= samples; k = k + 1u) {
+    let b = to_screen_position(curve_position(circle, f32(k) / f32(samples)));
+    length = length + distance(a, b);
+    a = b;
+  }
+  let segments = segment_count(length);
+  // six vertices for the quad of every segment
+  let first = atomicAdd(&drawIndirect.vertex_count, segments * 6u) / 6u;
+
+  // list the segments for circle.wgsl and test the mouse against them
+  let mouse = vec2(uniforms.mouse_x, uniforms.mouse_y);
+  var d = 1000000.0;
+  var t = 0.0;
+  var offset = 0.0;
+  a = start;
+  for (var s : u32 = 0u; s < segments; s = s + 1u) {
+    segmentBuffer.values[first + s] = pack_segment(i.x, s, segments, offset);
+    let b = to_screen_position(curve_position(circle, f32(s + 1u) / f32(segments)));
+    offset = offset + distance(a, b);
+    let closest = segment_distance(a, b, mouse);
+    if (closest.x < d) {
+      d = closest.x;
+      t = (f32(s) + closest.y) / f32(segments);
+    }
+    a = b;
+  }
+  let pos = curve_position(circle, t);
 
-  if(d <= 20.){
+  if(d <= 20. && (circle.flags & 8) == 0){
     circleBuffer.values[i.x].flags = circleBuffer.values[i.x].flags | 2;
     let hover_index = atomicAdd(&hoverCounter.counter, 1u);
     hoverBuffer.values[hover_index].index = i.x;
@@ -60,7 +59,4 @@ fn main(@builtin(global_invocation_id) i : vec3<u32>) {
   } else {
     circleBuffer.values[i.x].flags = circleBuffer.values[i.x].flags & (~2);
   }
-
-  
-
-}
\ No newline at end of file
+}
@@ -17,6 +17,21 @@ struct Uniforms {
   camera_orient_y : f32,
   camera_orient_z : f32,
   matrix: mat4x4<f32>,
+  grid_major : f32,
+  grid_minor : f32,
+  grid_minor_alpha : f32,
+  grid_extent : f32,
+  target_x : f32,
+  target_y : f32,
+  target_z : f32,
+  grid_fade : f32,
+  line_width : f32,
+  hidden_lines : f32,
+  dash_length : f32,
+  gap_length : f32,
+  theme_normal : vec4f,
+  theme_hover : vec4f,
+  theme_selected : vec4f,
 };
 struct Vertex {
   px : f32,
@@ -26,17 +41,20 @@ struct Vertex {
   dy : f32,
   dz : f32,
   flags : i32,
+  style : u32,
 }
 struct Point {
   px : f32,
   py : f32,
   pz : f32,
   flags : i32,
+  style : u32,
 }
 struct Line {
   point_a : u32,
   point_b : u32,
   flags : i32,
+  style : u32,
 }
 struct Bezier {
   point_a : u32,
@@ -44,6 +62,7 @@ struct Bezier {
   control_a : u32,
   control_b : u32,
   flags : i32,
+  style : u32,
 }
 struct Circle {
   center: u32,
@@ -52,7 +71,118 @@ struct Circle {
   orientation_y : f32,
   orientation_z : f32,
   heightfactor: f32,
+  turns: f32,
+  growth: f32,
   flags : i32,
+  style : u32,
+}
+struct Arc {
+  center : u32,
+  start : u32,
+  end : u32,
+  orientation_x : f32,
+  orientation_y : f32,
+  orientation_z : f32,
+  flags : i32,
+  style : u32,
+}
+struct Ellipse {
+  center : u32,
+  radius_a : f32,
+  radius_b : f32,
+  orientation_x : f32,
+  orientation_y : f32,
+  orientation_z : f32,
+  rotation : f32,
+  start_angle : f32,
+  sweep_angle : f32,
+  flags : i32,
+  style : u32,
+}
+struct Nurbs {
+  first : u32,
+  count : u32,
+  first_knot : u32,
+  degree : u32,
+  point_a : u32,
+  point_b : u32,
+  flags : i32,
+  style : u32,
+}
+struct NurbsControl {
+  point : u32,
+  weight : f32,
+  nurbs : u32,
+  flags : i32,
+  style : u32,
+}
+struct Knot {
+  value : f32,
+  flags : i32,
+  style : u32,
+}
+struct Face {
+  first : u32,
+  count : u32,
+  flags : i32,
+  style : u32,
+}
+struct FaceVertex {
+  px : f32,
+  py : f32,
+  pz : f32,
+  face : u32,
+  flags : i32,
+  style : u32,
+}
+struct Mesh {
+  first : u32,
+  count : u32,
+  wireframe : u32,
+  flags : i32,
+  style : u32,
+}
+struct MeshVertex {
+  px : f32,
+  py : f32,
+  pz : f32,
+  nx : f32,
+  ny : f32,
+  nz : f32,
+  mesh : u32,
+  flags : i32,
+  style : u32,
+}
+// see components/image.rs
+struct Image {
+  px : f32,
+  py : f32,
+  pz : f32,
+  nx : f32,
+  ny : f32,
+  nz : f32,
+  rotation : f32,
+  scale : f32,
+  opacity : f32,
+  width : u32,
+  height : u32,
+  first : u32,
+  flags : i32,
+  style : u32,
+}
+// see components/style.rs, zero values fall back to the theme and the line settings
+struct Style {
+  color_r : f32,
+  color_g : f32,
+  color_b : f32,
+  color_a : f32,
+  width : f32,
+  dash : f32,
+  gap : f32,
+  point_size : f32,
+  point_shape : u32,
+  flags : i32,
+  style : u32,
 }
 
 struct HoverElement{
@@ -80,10 +210,68 @@ struct BezierBuffer {
 struct CircleBuffer {
   values: array<Circle>,
 };
+struct ArcBuffer {
+  values: array<Arc>,
+};
+struct EllipseBuffer {
+  values: array<Ellipse>,
+};
+struct NurbsBuffer {
+  values: array<Nurbs>,
+};
+struct NurbsControlBuffer {
+  values: array<NurbsControl>,
+};
+struct KnotBuffer {
+  values: array<Knot>,
+};
+struct FaceBuffer {
+  values: array<Face>,
+};
+struct FaceVertexBuffer {
+  values: array<FaceVertex>,
+};
+struct MeshBuffer {
+  values: array<Mesh>,
+};
+struct MeshVertexBuffer {
+  values: array<MeshVertex>,
+};
+// the triangle corners of the meshes, indices into the mesh vertices
+struct MeshIndexBuffer {
+  values: array<u32>,
+};
+struct ImageBuffer {
+  values: array<Image>,
+};
+// rgba8 texels of all images
+struct PixelBuffer {
+  values: array<u32>,
+};
+
+struct StyleBuffer {
+  values: array<Style>,
+};
 
 struct AtomicCounter {
     counter: atomic<u32>,
 };
+struct Segment {
+  curve : u32,
+  // segment number with the segment count in the upper 16 bits
+  index : u32,
+  // screen distance along the curve to the start of the segment
+  offset : f32,
+};
+struct SegmentBuffer {
+  values: array<Segment>,
+};
+struct DrawIndirect {
+  vertex_count : atomic<u32>,
+  instance_count : u32,
+  first_vertex : u32,
+  first_instance : u32,
+};
 struct HoverBuffer {
   values: array<HoverElement>,
 };
@@ -100,3 +288,255 @@ fn double_shift(v : vec3f)-> vec3f{
     return vec3f(v.y, v.z, v.x);
 }
 
+fn orthogonal(v : vec3f) -> vec3f {
+    if (abs(v.x) > abs(v.y)){
+        return vec3f(-v.z, 0.0, v.x); // cross(v, y)
+    } else {
+        return vec3f(0.0, v.z, -v.y);  // cross(v, x)
+    }
+}
+
+fn plane_basis(normal : vec3f) -> mat2x3<f32> {
+    let n = normalize(normal);
+    let u = normalize(orthogonal(n));
+    return mat2x3<f32>(u, cross(n, u));
+}
+
+// same parametrization as Circle::position, helices rise and spirals grow per turn
+fn circle_position(circle : Circle, center : vec3f, t : f32) -> vec3f {
+    let orientation = vec3f(circle.orientation_x, circle.orientation_y, circle.orientation_z);
+    let basis = plane_basis(orientation);
+    let turns = circle.turns * t;
+    let angle = 2.0 * PI * turns;
+    let radius = circle.radius + circle.growth * turns;
+    let height = circle.heightfactor * turns;
+    return center + (basis[0] * cos(angle) + basis[1] * sin(angle)) * radius + normalize(orientation) * height;
+}
+
+fn point_position(point : Point) -> vec3f {
+    return vec3f(point.px, point.py, point.pz);
+}
+
+// same parametrization as Arc::position, counter clockwise from the start to the end point
+fn arc_position(center : vec3f, start : vec3f, end : vec3f, orientation : vec3f, t : f32) -> vec3f {
+    let radius = distance(start, center);
+    let u = normalize(start - center);
+    let v = cross(normalize(orientation), u);
+    var angle = atan2(dot(end - center, v), dot(end - center, u));
+    if (angle <= 0.000001) {
+        angle = angle + 2.0 * PI;
+    }
+    return center + (u * cos(angle * t) + v * sin(angle * t)) * radius;
+}
+
+// same parametrization as Ellipse::position
+fn ellipse_position(ellipse : Ellipse, center : vec3f, t : f32) -> vec3f {
+    let basis = plane_basis(vec3f(ellipse.orientation_x, ellipse.orientation_y, ellipse.orientation_z));
+    let a = basis[0] * cos(ellipse.rotation) + basis[1] * sin(ellipse.rotation);
+    let b = basis[1] * cos(ellipse.rotation) - basis[0] * sin(ellipse.rotation);
+    let angle = ellipse.start_angle + ellipse.sweep_angle * t;
+    return center + a * ellipse.radius_a * cos(angle) + b * ellipse.radius_b * sin(angle);
+}
+
+// higher degrees are drawn with this one, has to match MAX_DEGREE in nurbs.rs
+const MAX_NURBS_DEGREE : u32 = 7u;
+
+// what a parameter in the span from knot k on depends on, the degree + 1 homogeneous control
+// points from k - degree on and the 2 * degree knots from k - degree + 1 on
+struct NurbsSpan {
+    controls : array<vec4f, 8>,
+    knots : array<f32, 14>,
+    degree : u32,
+}
+
+// same parametrization as Nurbs::position, de boor on the homogeneous control points
+fn nurbs_span_position(span : NurbsSpan, u : f32) -> vec3f {
+    var d = span.controls;
+    var knots = span.knots;
+    let p = span.degree;
+    for (var r = 1u; r <= p; r = r + 1u) {
+        for (var j = p; j >= r; j = j - 1u) {
+            let length = knots[j + p - r] - knots[j - 1u];
+            var alpha = 0.0;
+            if (length > 0.000001) {
+                alpha = (u - knots[j - 1u]) / length;
+            }
+            d[j] = mix(d[j - 1u], d[j], alpha);
+        }
+    }
+    return d[p].xyz / d[p].w;
+}
+
+// curves get one segment per SEGMENT_PIXELS of their length on screen, MAX_SEGMENTS has to
+// match curve_tessellation.rs
+const MAX_SEGMENTS : u32 = 512u;
+const MIN_SEGMENTS : u32 = 4u;
+const SEGMENT_PIXELS : f32 = 4.0;
+// samples for measuring a curve on screen
+const MEASURE_SAMPLES : u32 = 16u;
+
+fn segment_count(screen_length : f32) -> u32 {
+    return u32(clamp(ceil(screen_length / SEGMENT_PIXELS), f32(MIN_SEGMENTS), f32(MAX_SEGMENTS)));
+}
+
+fn pack_segment(curve : u32, segment : u32, segments : u32, offset : f32) -> Segment {
+    return Segment(curve, segment | (segments << 16u), offset);
+}
+
+// parameter at the start (end = 0) or end (end = 1) of a segment
+fn segment_parameter(segment : Segment, end : u32) -> f32 {
+    return f32((segment.index & 0xffffu) + end) / f32(segment.index >> 16u);
+}
+
+// distance of p to the screen segment from a to b and how far along it the closest point is
+fn segment_distance(a : vec2f, b : vec2f, p : vec2f) -> vec2f {
+    let ab = b - a;
+    var f = 0.0;
+    if (dot(ab, ab) > 0.0) {
+        f = clamp(dot(p - a, ab) / dot(ab, ab), 0.0, 1.0);
+    }
+    return vec2f(distance(a + ab * f, p), f);
+}
+
+// lines are screen space quads around each segment with round ends, consecutive segments
+// overlap in their ends which gives round joins
+struct LineVertex {
+  @builtin(position) position : vec4f,
+  // segment ends in framebuffer pixels
+  @location(0) @interpolate(flat) a : vec2f,
+  @location(1) @interpolate(flat) b : vec2f,
+  // screen distance along the curve at a, keeps dashes going across segments
+  @location(2) @interpolate(flat) offset : f32,
+  @location(3) @interpolate(flat) flags : i32,
+  @location(4) @interpolate(flat) color : vec4f,
+  // width, dash and gap in pixels
+  @location(5) @interpolate(flat) pattern : vec3f,
+};
+
+// segments reaching behind the camera are cut at this clip space w
+const LINE_NEAR : f32 = 0.0001;
+// the hidden pass is pulled toward the camera so lines lying on faces don't count as hidden
+const HIDDEN_DEPTH_OFFSET : f32 = 0.000001;
+
+// which end of the segment and which side of it each of the six quad vertices is on
+fn line_corner(vertex : u32) -> vec2f {
+    switch (vertex % 6u) {
+        case 0u, 3u: { return vec2f(0.0, -1.0); }
+        case 1u: { return vec2f(0.0, 1.0); }
+        case 2u, 4u: { return vec2f(1.0, 1.0); }
+        default: { return vec2f(1.0, -1.0); }
+    }
+}
+
+fn line_width(style : Style) -> f32 {
+    if (style.width > 0.0) {
+        return style.width;
+    }
+    return uniforms.line_width;
+}
+
+fn line_vertex(a : vec3f, b : vec3f, vertex : u32, offset : f32, flags : i32, style : Style, depth_offset : f32) -> LineVertex {
+    var ca = uniforms.matrix * vec4f(a, 1.0);
+    var cb = uniforms.matrix * vec4f(b, 1.0);
+
+    var output : LineVertex;
+    output.flags = flags;
+    output.offset = offset;
+    output.color = style_color(style, uniforms.theme_normal);
+    output.pattern = vec3f(line_width(style), style.dash, style.gap);
+    if ((flags & 16) == 16) { // construction, dimmed with longer dashes than hidden lines
+        output.color.a = output.color.a * 0.45;
+        if (style.dash <= 0.0) {
+            output.pattern.y = uniforms.dash_length * 2.0;
+            output.pattern.z = uniforms.gap_length;
+        }
+    }
+    if ((flags & 8) == 8 || (ca.w < LINE_NEAR && cb.w < LINE_NEAR)) { // deleted or behind the camera
+        output.position = vec4f(0.0);
+        return output;
+    }
+    if (ca.w < LINE_NEAR) {
+        ca = mix(ca, cb, (LINE_NEAR - ca.w) / (cb.w - ca.w));
+    }
+    if (cb.w < LINE_NEAR) {
+        cb = mix(cb, ca, (LINE_NEAR - cb.w) / (ca.w - cb.w));
+    }
+
+    let size = vec2f(uniforms.width, uniforms.height);
+    let sa = (ca.xy / ca.w * 0.5 + 0.5) * size;
+    let sb = (cb.xy / cb.w * 0.5 + 0.5) * size;
+    var direction = vec2f(1.0, 0.0);
+    if (distance(sa, sb) > 0.0001) {
+        direction = normalize(sb - sa);
+    }
+    let normal = vec2f(-direction.y, direction.x);
+    // half the width and a pixel for the anti aliased edge
+    let radius = output.pattern.x * 0.5 + 1.0;
+
+    let corner = line_corner(vertex);
+    var clip = ca;
+    var screen = sa - direction * radius;
+    if (corner.x > 0.5) {
+        clip = cb;
+        screen = sb + direction * radius;
+    }
+    screen = screen + normal * radius * corner.y;
+
+    output.position = vec4f((screen / size - 0.5) / 0.5 * clip.w, clip.z - depth_offset * clip.w, clip.w);
+    output.a = vec2f(sa.x, uniforms.height - sa.y);
+    output.b = vec2f(sb.x, uniforms.height - sb.y);
+    return output;
+}
+
+// how much of the pixel the line covers, zero in the gaps when dash is not zero
+fn line_coverage(v : LineVertex, dash : f32, gap : f32) -> f32 {
+    let p = v.position.xy - vec2f(0.0, uniforms.height_top);
+    let closest = segment_distance(v.a, v.b, p);
+    var coverage = clamp(v.pattern.x * 0.5 + 0.5 - closest.x, 0.0, 1.0);
+    if (dash > 0.0) {
+        let along = v.offset + distance(v.a, v.b) * closest.y;
+        let phase = along % (dash + gap);
+        coverage = coverage * clamp(min(phase, dash - phase) + 0.5, 0.0, 1.0);
+    }
+    return coverage;
+}
+
+fn style_color(style : Style, fallback : vec4f) -> vec4f {
+    if (style.color_a > 0.0) {
+        return vec4f(style.color_r, style.color_g, style.color_b, style.color_a);
+    }
+    return fallback;
+}
+
+// the theme colors for selected and hovered components
+fn highlight(color : vec4f, flags : i32) -> vec4f {
+    if ((flags & 2) == 2){ // hover
+        return uniforms.theme_hover;
+    }
+    if ((flags & 4) == 4){ // selected
+        return uniforms.theme_selected;
+    }
+    return color;
+}
+
+fn line_fragment(v : LineVertex) -> vec4f {
+    let coverage = line_coverage(v, v.pattern.y, v.pattern.z);
+    if (coverage <= 0.0) {
+        discard;
+    }
+    let color = highlight(v.color, v.flags);
+    return vec4f(color.rgb, color.a * coverage);
+}
+
+// dashed and faded, nothing when hidden lines are switched off
+fn hidden_line_fragment(v : LineVertex) -> vec4f {
+    var coverage = 0.0;
+    if (uniforms.hidden_lines > 0.5) {
+        coverage = line_coverage(v, uniforms.dash_length, uniforms.gap_length) * 0.5;
+    }
+    if (coverage <= 0.0) {
+        discard;
+    }
+    let color = highlight(v.color, v.flags);
+    return vec4f(color.rgb, color.a * coverage);
+}
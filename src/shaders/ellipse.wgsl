@@ -0,0 +1,38 @@
+@group(0) @binding(0) var<uniform> uniforms : Uniforms;
+@group(1) @binding(0) var<storage, read> pointBuffer : PointBuffer;
+@group(1) @binding(1) var<storage, read> ellipseBuffer : EllipseBuffer;
+@group(1) @binding(2) var<storage, read> segmentBuffer : SegmentBuffer;
+@group(1) @binding(3) var<storage, read> styleBuffer : StyleBuffer;
+
+fn curve_position(ellipse : Ellipse, t : f32) -> vec3f {
+  return ellipse_position(ellipse, point_position(pointBuffer.values[ellipse.center]), t);
+}
+
+fn segment_vertex(i : u32, depth_offset : f32) -> LineVertex {
+  let segment = segmentBuffer.values[i / 6u];
+  let ellipse = ellipseBuffer.values[segment.curve];
+  let a = curve_position(ellipse, segment_parameter(segment, 0u));
+  let b = curve_position(ellipse, segment_parameter(segment, 1u));
+  return line_vertex(a, b, i, segment.offset, ellipse.flags, styleBuffer.values[ellipse.style], depth_offset);
+}
+
+// the segments were listed by ellipse_com.wgsl
+@vertex
+fn vert_main(@builtin(vertex_index) i : u32) -> LineVertex {
+  return segment_vertex(i, 0.0);
+}
+
+@vertex
+fn vert_hidden(@builtin(vertex_index) i : u32) -> LineVertex {
+  return segment_vertex(i, HIDDEN_DEPTH_OFFSET);
+}
+
+@fragment
+fn frag_main(v : LineVertex) -> @location(0) vec4f {
+  return line_fragment(v);
+}
+
+@fragment
+fn frag_hidden(v : LineVertex) -> @location(0) vec4f {
+  return hidden_line_fragment(v);
+}
@@ -0,0 +1,33 @@
+struct VertexOutput {
+  @builtin(position) position : vec4f,
+  @location(0) @interpolate(flat) flags : i32,
+};
+
+@group(0) @binding(0) var<uniform> uniforms : Uniforms;
+@group(1) @binding(0) var<storage, read> faceBuffer : FaceBuffer;
+@group(1) @binding(1) var<storage, read> faceVertexBuffer : FaceVertexBuffer;
+
+@vertex
+fn vert_main(@builtin(vertex_index) i : u32) -> VertexOutput {
+  let vertex = faceVertexBuffer.values[i];
+  let face = faceBuffer.values[vertex.face];
+
+  var output : VertexOutput;
+  output.position = uniforms.matrix * vec4f(vertex.px, vertex.py, vertex.pz, 1.0);
+  // pushed back a little so the curves on the boundary stay in front of the fill
+  output.position.z = output.position.z + 0.00001 * output.position.w;
+  output.flags = face.flags;
+  return output;
+}
+
+@fragment
+fn frag_main(v: VertexOutput) -> @location(0) vec4f {
+  var color = vec4f(0.5, 0.6, 0.8, 0.25);
+  if ((v.flags & 4) == 4){ // selected
+    color =  vec4f(uniforms.theme_selected.rgb, 0.35);
+  }
+  if ((v.flags & 2) == 2){ // hover
+    color =  vec4f(uniforms.theme_hover.rgb, 0.35);
+  }
+  return color;
+}
@@ -0,0 +1,58 @@
+@group(0) @binding(0) var<uniform> uniforms : Uniforms;
+@group(1) @binding(0) var<storage, read_write> hoverCounter : AtomicCounter;
+@group(1) @binding(1) var<storage, read_write> hoverBuffer : HoverBuffer;
+@group(2) @binding(0) var<storage, read_write> faceBuffer : FaceBuffer;
+@group(2) @binding(1) var<storage, read_write> faceVertexBuffer : FaceVertexBuffer;
+
+fn vertex_position(index : u32) -> vec3f {
+  let v = faceVertexBuffer.values[index];
+  return vec3f(v.px, v.py, v.pz);
+}
+
+// barycentric coordinates of point in the triangle, negative if outside
+fn barycentric(a : vec2f, b : vec2f, c : vec2f, point : vec2f) -> vec3f {
+  let v0 = b - a;
+  let v1 = c - a;
+  let v2 = point - a;
+  let denom = v0.x * v1.y - v1.x * v0.y;
+  if (abs(denom) < 0.000001){
+    return vec3f(-1.0, -1.0, -1.0);
+  }
+  let v = (v2.x * v1.y - v1.x * v2.y) / denom;
+  let w = (v0.x * v2.y - v2.x * v0.y) / denom;
+  return vec3f(1.0 - v - w, v, w);
+}
+
+@compute @workgroup_size(1, 1)
+fn main(@builtin(global_invocation_id) i : vec3<u32>) {
+  let face = faceBuffer.values[i.x];
+  let mouse_pos = vec2(uniforms.mouse_x, uniforms.mouse_y);
+
+  var hit = false;
+  var point = vec3f(0.0, 0.0, 0.0);
+  for (var t = face.first; t < face.first + face.count; t = t + u32(3)) {
+    let a = vertex_position(t);
+    let b = vertex_position(t + u32(1));
+    let c = vertex_position(t + u32(2));
+    let weights = barycentric(to_screen_position(a), to_screen_position(b), to_screen_position(c), mouse_pos);
+    if (weights.x >= 0.0 && weights.y >= 0.0 && weights.z >= 0.0){
+      hit = true;
+      point = a * weights.x + b * weights.y + c * weights.z;
+      break;
+    }
+  }
+
+  if(hit && (face.flags & 8) == 0){
+    faceBuffer.values[i.x].flags = faceBuffer.values[i.x].flags | 2;
+    let hover_index = atomicAdd(&hoverCounter.counter, 1u);
+    hoverBuffer.values[hover_index].index = i.x;
+    hoverBuffer.values[hover_index].ctype = 7; // face
+    // slightly further away so curves on the face win
+    hoverBuffer.values[hover_index].distance = distance(point, vec3f(uniforms.camera_origin_x, uniforms.camera_origin_y, uniforms.camera_origin_z)) * 1.001;
+    hoverBuffer.values[hover_index].position_x = point.x;
+    hoverBuffer.values[hover_index].position_y = point.y;
+    hoverBuffer.values[hover_index].position_z = point.z;
+  } else {
+    faceBuffer.values[i.x].flags = faceBuffer.values[i.x].flags & (~2);
+  }
+}
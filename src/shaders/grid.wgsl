@@ -1,35 +1,69 @@
+struct VertexOutput {
+  @builtin(position) position : vec4f,
+  @location(0) world : vec3f,
+  @location(1) origin : vec3f,
+  @location(2) center : vec3f,
+  @location(3) normal : vec3f,
+};
+
 @group(0) @binding(0) var<uniform> uniforms : Uniforms;
 @group(1) @binding(0) var<storage, read> vertexBuffer : VertexBuffer;
 
 @vertex
-fn vert_main(@builtin(vertex_index) i : u32) -> @builtin(position) vec4f {
-  let length = 10.0;
-  let size = u32(11);
-  let axis = vertexBuffer.values[i / u32(size * u32(4))];
-  let p = vec3f(axis.px, axis.py, axis.pz);
-  let d = vec3f(axis.dx, axis.dy, axis.dz);
-
-  let i2 = i % u32(size * u32(2));
-  let a = f32(i2 % u32(2));
-  let b = f32(i2 / u32(2)) / f32(size - u32(1));
-
-  var c1 = shift(d);
-  var c2 = double_shift(d);
-
-  if((i % u32(size * u32(4))) / (size * u32(2)) == u32(0)) {
-    c1 = double_shift(d);
-    c2 = shift(d);
-  }
+fn vert_main(@builtin(vertex_index) i : u32) -> VertexOutput {
+  let plane = vertexBuffer.values[i / u32(6)];
+  let origin = vec3f(plane.px, plane.py, plane.pz);
+  let normal = normalize(vec3f(plane.dx, plane.dy, plane.dz));
+  let basis = plane_basis(normal);
+
+  // the quad follows the camera target, the lines stay fixed to the plane origin
+  let target_ = vec3f(uniforms.target_x, uniforms.target_y, uniforms.target_z);
+  let center = target_ - dot(target_ - origin, normal) * normal;
+
+  var corners = array<vec2f, 6>();
+  corners[0] = vec2f(-1.0, -1.0);
+  corners[1] = vec2f(1.0, -1.0);
+  corners[2] = vec2f(1.0, 1.0);
+  corners[3] = vec2f(-1.0, -1.0);
+  corners[4] = vec2f(1.0, 1.0);
+  corners[5] = vec2f(-1.0, 1.0);
+  let corner = corners[i % u32(6)] * uniforms.grid_extent;
+
+  let world = center + basis[0] * corner.x + basis[1] * corner.y;
+
+  var output : VertexOutput;
+  output.position = uniforms.matrix * vec4f(world, 1.0);
+  output.world = world;
+  output.origin = origin;
+  output.center = center;
+  output.normal = normal;
+  return output;
+}
 
-  let result = p 
-    + c1 * a * length * 0.5
-    - c1 * (1.0 - a) * length * 0.5
-    + c2 * (b - 0.5) * length;
-  return uniforms.matrix * vec4f(result, 1.0);
+fn grid_line(coord : vec2f, spacing : f32) -> f32 {
+  let scaled = coord / spacing;
+  let width = fwidth(scaled);
+  let g = abs(fract(scaled - 0.5) - 0.5) / width;
+  return 1.0 - min(min(g.x, g.y), 1.0);
 }
 
 @fragment
-fn frag_main(@builtin(position) coord: vec4f) -> @location(0) vec4f {
-  let color = vec4f(1.0, 1.0, 1.0, 1.0);
-  return color;
-}
\ No newline at end of file
+fn frag_main(v: VertexOutput) -> @location(0) vec4f {
+  let basis = plane_basis(v.normal);
+  let local = v.world - v.origin;
+  let coord = vec2f(dot(local, basis[0]), dot(local, basis[1]));
+
+  let major = grid_line(coord, uniforms.grid_major);
+  let minor = grid_line(coord, uniforms.grid_minor) * uniforms.grid_minor_alpha * 0.35;
+  var alpha = max(major * 0.8, minor);
+
+  if (uniforms.grid_fade > 0.5) {
+    let d = distance(v.world, v.center);
+    alpha = alpha * (1.0 - smoothstep(uniforms.grid_extent * 0.25, uniforms.grid_extent, d));
+  }
+
+  if (alpha < 0.01) {
+    discard;
+  }
+  return vec4f(1.0, 1.0, 1.0, alpha);
+}
@@ -0,0 +1,67 @@
+struct VertexOutput {
+  @builtin(position) position : vec4f,
+  // texel coordinates, x to the right and y down from the top left corner
+  @location(0) texel : vec2f,
+  @location(1) @interpolate(flat) image : u32,
+};
+
+@group(0) @binding(0) var<uniform> uniforms : Uniforms;
+@group(1) @binding(0) var<storage, read> imageBuffer : ImageBuffer;
+@group(1) @binding(1) var<storage, read> pixelBuffer : PixelBuffer;
+
+@vertex
+fn vert_main(@builtin(vertex_index) i : u32) -> VertexOutput {
+  let index = i / 6u;
+  let image = imageBuffer.values[index];
+  let center = vec3f(image.px, image.py, image.pz);
+
+  // same as Image::axes
+  let basis = plane_basis(vec3f(image.nx, image.ny, image.nz));
+  let c = cos(image.rotation);
+  let s = sin(image.rotation);
+  let u = basis[0] * c + basis[1] * s;
+  let v = basis[1] * c - basis[0] * s;
+
+  var corners = array<vec2f, 6>();
+  corners[0] = vec2f(0.0, 0.0);
+  corners[1] = vec2f(1.0, 0.0);
+  corners[2] = vec2f(1.0, 1.0);
+  corners[3] = vec2f(0.0, 0.0);
+  corners[4] = vec2f(1.0, 1.0);
+  corners[5] = vec2f(0.0, 1.0);
+  let corner = corners[i % 6u];
+  let size = vec2f(f32(image.width), f32(image.height));
+  let offset = (corner - 0.5) * size * image.scale;
+  // the first row is the top of the picture
+  let world = center + u * offset.x - v * offset.y;
+
+  var output : VertexOutput;
+  output.position = uniforms.matrix * vec4f(world, 1.0);
+  output.texel = corner * size;
+  output.image = index;
+  if ((image.flags & 1) == 0 || (image.flags & 8) == 8){ // hidden or deleted
+    output.position = vec4f(0.0, 0.0, 0.0, 0.0);
+  }
+  return output;
+}
+
+fn texel(image : Image, x : i32, y : i32) -> vec4f {
+  let column = u32(clamp(x, 0, i32(image.width) - 1));
+  let row = u32(clamp(y, 0, i32(image.height) - 1));
+  return unpack4x8unorm(pixelBuffer.values[image.first + row * image.width + column]);
+}
+
+// bilinear filtering between the four nearest texel centers
+@fragment
+fn frag_main(v: VertexOutput) -> @location(0) vec4f {
+  let image = imageBuffer.values[v.image];
+  let p = v.texel - 0.5;
+  let base = floor(p);
+  let f = p - base;
+  let x = i32(base.x);
+  let y = i32(base.y);
+  let top = mix(texel(image, x, y), texel(image, x + 1, y), f.x);
+  let bottom = mix(texel(image, x, y + 1), texel(image, x + 1, y + 1), f.x);
+  let color = mix(top, bottom, f.y);
+  return vec4f(color.rgb, color.a * image.opacity);
+}
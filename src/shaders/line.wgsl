@@ -1,34 +1,31 @@
-struct VertexOutput {
-  @builtin(position) position : vec4f,
-  @location(0) @interpolate(flat) flags : i32,
-};
-
 @group(0) @binding(0) var<uniform> uniforms : Uniforms;
 @group(1) @binding(0) var<storage, read> pointBuffer : PointBuffer;
 @group(1) @binding(1) var<storage, read> lineBuffer : LineBuffer;
+@group(1) @binding(2) var<storage, read> styleBuffer : StyleBuffer;
+
+fn segment_vertex(i : u32, depth_offset : f32) -> LineVertex {
+  let line = lineBuffer.values[i / 6u];
+  let a = point_position(pointBuffer.values[line.point_a]);
+  let b = point_position(pointBuffer.values[line.point_b]);
+  return line_vertex(a, b, i, 0.0, line.flags, styleBuffer.values[line.style], depth_offset);
+}
 
+@vertex
+fn vert_main(@builtin(vertex_index) i : u32) -> LineVertex {
+  return segment_vertex(i, 0.0);
+}
 
 @vertex
-fn vert_main(@builtin(vertex_index) i : u32) -> VertexOutput {
-  let line = lineBuffer.values[i / u32(2)];
-  var pos = array<vec3f, 2>();
-  pos[0] = vec3f(pointBuffer.values[line.point_a].px, pointBuffer.values[line.point_a].py, pointBuffer.values[line.point_a].pz);
-  pos[1] = vec3f(pointBuffer.values[line.point_b].px, pointBuffer.values[line.point_b].py, pointBuffer.values[line.point_b].pz);
+fn vert_hidden(@builtin(vertex_index) i : u32) -> LineVertex {
+  return segment_vertex(i, HIDDEN_DEPTH_OFFSET);
+}
 
-  var output : VertexOutput;
-  output.position = uniforms.matrix * vec4f(pos[i % u32(2)], 1.0);
-  output.flags = line.flags;
-  return output;
+@fragment
+fn frag_main(v : LineVertex) -> @location(0) vec4f {
+  return line_fragment(v);
 }
 
 @fragment
-fn frag_main(v: VertexOutput) -> @location(0) vec4f {
-  var color = vec4f(1.0, 1.0, 1.0, 1.0);
-  if ((v.flags & 4) == 4){ // selected
-    color =  vec4f(0.0, 0.0, 1.0, 1.0);
-  }
-  if ((v.flags & 2) == 2){ // hover
-    color =  vec4f(1.0, 0.0, 0.0, 1.0);
-  }
-  return color;
-}
\ No newline at end of file
+fn frag_hidden(v : LineVertex) -> @location(0) vec4f {
+  return hidden_line_fragment(v);
+}
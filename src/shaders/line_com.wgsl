@@ -39,7 +39,7 @@ fn main(@builtin(global_invocation_id) i : vec3<u32>) {
 
   let point = point_a + t * (point_b - point_a);
 
-  if(d <= 20.){
+  if(d <= 20. && (line.flags & 8) == 0){
     lineBuffer.values[i.x].flags = lineBuffer.values[i.x].flags | 2;
     let hover_index = atomicAdd(&hoverCounter.counter, 1u);
     hoverBuffer.values[hover_index].index = i.x;
@@ -0,0 +1,52 @@
+struct VertexOutput {
+  @builtin(position) position : vec4f,
+  @location(0) normal : vec3f,
+  @location(1) @interpolate(flat) flags : i32,
+  @location(2) @interpolate(flat) color : vec4f,
+  @location(3) barycentric : vec3f,
+  @location(4) @interpolate(flat) wireframe : u32,
+};
+
+@group(0) @binding(0) var<uniform> uniforms : Uniforms;
+@group(1) @binding(0) var<storage, read> meshBuffer : MeshBuffer;
+@group(1) @binding(1) var<storage, read> meshVertexBuffer : MeshVertexBuffer;
+@group(1) @binding(2) var<storage, read> meshIndexBuffer : MeshIndexBuffer;
+@group(1) @binding(3) var<storage, read> styleBuffer : StyleBuffer;
+
+@vertex
+fn vert_main(@builtin(vertex_index) i : u32) -> VertexOutput {
+  // i counts the triangle corners, the vertices are shared
+  let vertex = meshVertexBuffer.values[meshIndexBuffer.values[i]];
+  let mesh = meshBuffer.values[vertex.mesh];
+
+  var output : VertexOutput;
+  output.position = uniforms.matrix * vec4f(vertex.px, vertex.py, vertex.pz, 1.0);
+  output.normal = vec3f(vertex.nx, vertex.ny, vertex.nz);
+  output.flags = mesh.flags;
+  output.wireframe = mesh.wireframe;
+  // every corner of a triangle gets its own axis, the distance to an edge is the smallest
+  let corner = (i - mesh.first) % 3u;
+  output.barycentric = vec3f(f32(corner == 0u), f32(corner == 1u), f32(corner == 2u));
+  output.color = style_color(styleBuffer.values[mesh.style], vec4f(0.7, 0.7, 0.75, 1.0));
+  if ((mesh.flags & 8) == 8){ // deleted
+    output.position = vec4f(0.0, 0.0, 0.0, 0.0);
+  }
+  return output;
+}
+
+@fragment
+fn frag_main(v: VertexOutput) -> @location(0) vec4f {
+  let color = highlight(v.color, v.flags).rgb;
+  // derivatives only work in uniform control flow, they are taken before the branch
+  let edge = v.barycentric / max(fwidth(v.barycentric), vec3f(0.000001));
+  if (v.wireframe == 1u) {
+    if (min(edge.x, min(edge.y, edge.z)) > 1.0) {
+      discard;
+    }
+    return vec4f(color, 1.0);
+  }
+  // headlight, both sides are lit so open surfaces stay visible
+  let light = normalize(vec3f(uniforms.camera_orientation_x, uniforms.camera_orientation_y, uniforms.camera_orientation_z));
+  let diffuse = abs(dot(normalize(v.normal), light));
+  return vec4f(color * (0.25 + 0.75 * diffuse), 1.0);
+}
@@ -0,0 +1,101 @@
+@group(0) @binding(0) var<uniform> uniforms : Uniforms;
+@group(1) @binding(0) var<storage, read_write> hoverCounter : AtomicCounter;
+@group(1) @binding(1) var<storage, read_write> hoverBuffer : HoverBuffer;
+@group(2) @binding(0) var<storage, read_write> meshBuffer : MeshBuffer;
+@group(2) @binding(1) var<storage, read_write> meshVertexBuffer : MeshVertexBuffer;
+@group(2) @binding(2) var<storage, read_write> meshIndexBuffer : MeshIndexBuffer;
+
+// the position of a triangle corner
+fn vertex_position(corner : u32) -> vec3f {
+  let v = meshVertexBuffer.values[meshIndexBuffer.values[corner]];
+  return vec3f(v.px, v.py, v.pz);
+}
+
+// distance along the camera ray to the triangle, negative if it is missed
+fn intersect(origin : vec3f, direction : vec3f, a : vec3f, b : vec3f, c : vec3f) -> f32 {
+  // https://en.wikipedia.org/wiki/M%C3%B6ller%E2%80%93Trumbore_intersection_algorithm
+  let edge1 = b - a;
+  let edge2 = c - a;
+  let h = cross(direction, edge2);
+  let det = dot(edge1, h);
+  if (abs(det) < 0.0000001){
+    return -1.0;
+  }
+  let f = 1.0 / det;
+  let s = origin - a;
+  let u = f * dot(s, h);
+  if (u < 0.0 || u > 1.0){
+    return -1.0;
+  }
+  let q = cross(s, edge1);
+  let v = f * dot(direction, q);
+  if (v < 0.0 || u + v > 1.0){
+    return -1.0;
+  }
+  return f * dot(edge2, q);
+}
+
+const THREADS = 64u;
+
+// the bits of positive floats sort like the floats
+var<workgroup> nearest_bits : atomic<u32>;
+var<workgroup> winner : atomic<u32>;
+
+// one workgroup per mesh, its threads share the triangles so large meshes stay fast
+@compute @workgroup_size(64, 1)
+fn main(@builtin(workgroup_id) group : vec3<u32>, @builtin(local_invocation_index) local : u32) {
+  let m = group.x;
+  let mesh = meshBuffer.values[m];
+  let origin = vec3f(uniforms.camera_origin_x, uniforms.camera_origin_y, uniforms.camera_origin_z);
+  let direction = normalize(vec3f(uniforms.camera_orient_x, uniforms.camera_orient_y, uniforms.camera_orient_z));
+  if (local == 0u) {
+    atomicStore(&nearest_bits, 0xffffffffu);
+    atomicStore(&winner, THREADS);
+  }
+  workgroupBarrier();
+
+  var nearest = -1.0;
+  var triangle = 0u;
+  if ((mesh.flags & 8) == 0){
+    for (var t = mesh.first + local * 3u; t < mesh.first + mesh.count; t = t + THREADS * 3u) {
+      let d = intersect(origin, direction, vertex_position(t), vertex_position(t + 1u), vertex_position(t + 2u));
+      if (d > 0.0 && (nearest < 0.0 || d < nearest)){
+        nearest = d;
+        triangle = t;
+      }
+    }
+  }
+  if (nearest > 0.0) {
+    atomicMin(&nearest_bits, bitcast<u32>(nearest));
+  }
+  workgroupBarrier();
+  let bits = atomicLoad(&nearest_bits);
+  if (nearest > 0.0 && bitcast<u32>(nearest) == bits) {
+    atomicMin(&winner, local);
+  }
+  workgroupBarrier();
+  let first = atomicLoad(&winner);
+
+  if (bits == 0xffffffffu) {
+    if (local == 0u) {
+      meshBuffer.values[m].flags = meshBuffer.values[m].flags & (~2);
+    }
+  } else if (local == first) {
+    // tools snap to the corner of the hit triangle closest to the hit
+    let point = origin + direction * nearest;
+    var corner = vertex_position(triangle);
+    for (var c = 1u; c < 3u; c = c + 1u) {
+      if (distance(vertex_position(triangle + c), point) < distance(corner, point)) {
+        corner = vertex_position(triangle + c);
+      }
+    }
+    meshBuffer.values[m].flags = meshBuffer.values[m].flags | 2;
+    let hover_index = atomicAdd(&hoverCounter.counter, 1u);
+    hoverBuffer.values[hover_index].index = m;
+    hoverBuffer.values[hover_index].ctype = 8; // mesh
+    hoverBuffer.values[hover_index].distance = nearest;
+    hoverBuffer.values[hover_index].position_x = corner.x;
+    hoverBuffer.values[hover_index].position_y = corner.y;
+    hoverBuffer.values[hover_index].position_z = corner.z;
+  }
+}
@@ -0,0 +1,67 @@
+@group(0) @binding(0) var<uniform> uniforms : Uniforms;
+@group(1) @binding(0) var<storage, read> pointBuffer : PointBuffer;
+@group(1) @binding(1) var<storage, read> nurbsBuffer : NurbsBuffer;
+@group(1) @binding(2) var<storage, read> controlBuffer : NurbsControlBuffer;
+@group(1) @binding(3) var<storage, read> knotBuffer : KnotBuffer;
+@group(1) @binding(4) var<storage, read> segmentBuffer : SegmentBuffer;
+@group(1) @binding(5) var<storage, read> styleBuffer : StyleBuffer;
+
+fn knot(nurbs : Nurbs, i : u32) -> f32 {
+  return knotBuffer.values[nurbs.first_knot + i].value;
+}
+
+// finds the span of the parameter for nurbs_span_position
+fn nurbs_position(nurbs : Nurbs, t : f32) -> vec3f {
+  let p = min(nurbs.degree, min(nurbs.count - 1u, MAX_NURBS_DEGREE));
+  let n = nurbs.count;
+  let u = mix(knot(nurbs, p), knot(nurbs, n), t);
+  var k = p;
+  while (k < n - 1u && knot(nurbs, k + 1u) <= u) {
+    k = k + 1u;
+  }
+
+  var span : NurbsSpan;
+  span.degree = p;
+  for (var j = 0u; j <= p; j = j + 1u) {
+    let control = controlBuffer.values[nurbs.first + j + k - p];
+    let point = point_position(pointBuffer.values[control.point]);
+    span.controls[j] = vec4f(point * control.weight, control.weight);
+  }
+  for (var j = 0u; j < 2u * p; j = j + 1u) {
+    span.knots[j] = knot(nurbs, k - p + 1u + j);
+  }
+  return nurbs_span_position(span, u);
+}
+
+fn curve_position(nurbs : Nurbs, t : f32) -> vec3f {
+  return nurbs_position(nurbs, t);
+}
+
+fn segment_vertex(i : u32, depth_offset : f32) -> LineVertex {
+  let segment = segmentBuffer.values[i / 6u];
+  let nurbs = nurbsBuffer.values[segment.curve];
+  let a = curve_position(nurbs, segment_parameter(segment, 0u));
+  let b = curve_position(nurbs, segment_parameter(segment, 1u));
+  return line_vertex(a, b, i, segment.offset, nurbs.flags, styleBuffer.values[nurbs.style], depth_offset);
+}
+
+// the segments were listed by nurbs_com.wgsl
+@vertex
+fn vert_main(@builtin(vertex_index) i : u32) -> LineVertex {
+  return segment_vertex(i, 0.0);
+}
+
+@vertex
+fn vert_hidden(@builtin(vertex_index) i : u32) -> LineVertex {
+  return segment_vertex(i, HIDDEN_DEPTH_OFFSET);
+}
+
+@fragment
+fn frag_main(v : LineVertex) -> @location(0) vec4f {
+  return line_fragment(v);
+}
+
+@fragment
+fn frag_hidden(v : LineVertex) -> @location(0) vec4f {
+  return hidden_line_fragment(v);
+}
@@ -0,0 +1,91 @@
+@group(0) @binding(0) var<uniform> uniforms : Uniforms;
+@group(1) @binding(0) var<storage, read_write> hoverCounter : AtomicCounter;
+@group(1) @binding(1) var<storage, read_write> hoverBuffer : HoverBuffer;
+@group(2) @binding(0) var<storage, read_write> pointBuffer : PointBuffer;
+@group(2) @binding(1) var<storage, read_write> nurbsBuffer : NurbsBuffer;
+@group(2) @binding(2) var<storage, read_write> controlBuffer : NurbsControlBuffer;
+@group(2) @binding(3) var<storage, read_write> knotBuffer : KnotBuffer;
+@group(2) @binding(4) var<storage, read_write> segmentBuffer : SegmentBuffer;
+@group(2) @binding(5) var<storage, read_write> drawIndirect : DrawIndirect;
+
+fn knot(nurbs : Nurbs, i : u32) -> f32 {
+  return knotBuffer.values[nurbs.first_knot + i].value;
+}
+
+// finds the span of the parameter for nurbs_span_position
+fn nurbs_position(nurbs : Nurbs, t : f32) -> vec3f {
+  let p = min(nurbs.degree, min(nurbs.count - 1u, MAX_NURBS_DEGREE));
+  let n = nurbs.count;
+  let u = mix(knot(nurbs, p), knot(nurbs, n), t);
+  var k = p;
+  while (k < n - 1u && knot(nurbs, k + 1u) <= u) {
+    k = k + 1u;
+  }
+
+  var span : NurbsSpan;
+  span.degree = p;
+  for (var j = 0u; j <= p; j = j + 1u) {
+    let control = controlBuffer.values[nurbs.first + j + k - p];
+    let point = point_position(pointBuffer.values[control.point]);
+    span.controls[j] = vec4f(point * control.weight, control.weight);
+  }
+  for (var j = 0u; j < 2u * p; j = j + 1u) {
+    span.knots[j] = knot(nurbs, k - p + 1u + j);
+  }
+  return nurbs_span_position(span, u);
+}
+
+fn curve_position(nurbs : Nurbs, t : f32) -> vec3f {
+  return nurbs_position(nurbs, t);
+}
+
+@compute @workgroup_size(1, 1)
+fn main(@builtin(global_invocation_id) i : vec3<u32>) {
+  let nurbs = nurbsBuffer.values[i.x];
+
+  // the segment count follows the length on screen
+  let samples = MEASURE_SAMPLES;
+  let start = to_screen_position(curve_position(nurbs, 0.0));
+  var a = start;
+  var length = 0.0;
+  for (var k : u32 = 1u; k <= samples; k = k + 1u) {
+    let b = to_screen_position(curve_position(nurbs, f32(k) / f32(samples)));
+    length = length + distance(a, b);
+    a = b;
+  }
+  let segments = segment_count(length);
+  // six vertices for the quad of every segment
+  let first = atomicAdd(&drawIndirect.vertex_count, segments * 6u) / 6u;
+
+  // list the segments for nurbs.wgsl and test the mouse against them
+  let mouse = vec2(uniforms.mouse_x, uniforms.mouse_y);
+  var d = 1000000.0;
+  var t = 0.0;
+  var offset = 0.0;
+  a = start;
+  for (var s : u32 = 0u; s < segments; s = s + 1u) {
+    segmentBuffer.values[first + s] = pack_segment(i.x, s, segments, offset);
+    let b = to_screen_position(curve_position(nurbs, f32(s + 1u) / f32(segments)));
+    offset = offset + distance(a, b);
+    let closest = segment_distance(a, b, mouse);
+    if (closest.x < d) {
+      d = closest.x;
+      t = (f32(s) + closest.y) / f32(segments);
+    }
+    a = b;
+  }
+  let pos = curve_position(nurbs, t);
+
+  if(d <= 20. && (nurbs.flags & 8) == 0){
+    nurbsBuffer.values[i.x].flags = nurbsBuffer.values[i.x].flags | 2;
+    let hover_index = atomicAdd(&hoverCounter.counter, 1u);
+    hoverBuffer.values[hover_index].index = i.x;
+    hoverBuffer.values[hover_index].ctype = 11; // nurbs
+    hoverBuffer.values[hover_index].distance = distance(pos, vec3f(uniforms.camera_origin_x, uniforms.camera_origin_y, uniforms.camera_origin_z));
+    hoverBuffer.values[hover_index].position_x = pos.x;
+    hoverBuffer.values[hover_index].position_y = pos.y;
+    hoverBuffer.values[hover_index].position_z = pos.z;
+  } else {
+    nurbsBuffer.values[i.x].flags = nurbsBuffer.values[i.x].flags & (~2);
+  }
+}
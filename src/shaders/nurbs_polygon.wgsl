@@ -0,0 +1,32 @@
+@group(0) @binding(0) var<uniform> uniforms : Uniforms;
+@group(1) @binding(0) var<storage, read> pointBuffer : PointBuffer;
+@group(1) @binding(1) var<storage, read> nurbsBuffer : NurbsBuffer;
+@group(1) @binding(2) var<storage, read> controlBuffer : NurbsControlBuffer;
+@group(1) @binding(3) var<storage, read> knotBuffer : KnotBuffer;
+
+// the control polygon of selected curves, one dashed line from every control point to the next
+@vertex
+fn vert_main(@builtin(vertex_index) i : u32) -> LineVertex {
+  let index = i / 6u;
+  let control = controlBuffer.values[index];
+  let nurbs = nurbsBuffer.values[control.nurbs];
+
+  if ((nurbs.flags & 4) == 4 && index + 1u < nurbs.first + nurbs.count) {
+    let a = point_position(pointBuffer.values[control.point]);
+    let b = point_position(pointBuffer.values[controlBuffer.values[index + 1u].point]);
+    let style = Style(0.5, 0.5, 1.0, 0.6, 0.0, uniforms.dash_length, uniforms.gap_length, 0.0, 0u, 0, 0u);
+    return line_vertex(a, b, i, 0.0, nurbs.flags, style, 0.0);
+  }
+  var output : LineVertex;
+  output.position = vec4f(0.0, 0.0, 0.0, 0.0);
+  return output;
+}
+
+@fragment
+fn frag_main(v: LineVertex) -> @location(0) vec4f {
+  let coverage = line_coverage(v, v.pattern.y, v.pattern.z);
+  if (coverage <= 0.0) {
+    discard;
+  }
+  return vec4f(v.color.rgb, v.color.a * coverage);
+}
@@ -2,10 +2,13 @@ struct VertexOutput {
   @builtin(position) position : vec4f,
   @location(0) vCenter : vec2f,
   @location(1) @interpolate(flat) flags : i32,
+  @location(2) @interpolate(flat) color : vec4f,
+  @location(3) @interpolate(flat) shape : vec2f,
 };
 
 @group(0) @binding(0) var<uniform> uniforms : Uniforms;
 @group(1) @binding(0) var<storage, read> pointBuffer : PointBuffer;
+@group(1) @binding(1) var<storage, read> styleBuffer : StyleBuffer;
 
 @vertex
 fn vert_main(@builtin(vertex_index) i : u32) -> VertexOutput {
@@ -16,7 +19,11 @@ fn vert_main(@builtin(vertex_index) i : u32) -> VertexOutput {
 
     let vCenter = vec4f(((center.xyz/center.w) * 0.5 + 0.5) * vec3f(size, 1.0), center.w).xy;
 
-    let radius = 5.0;
+    let style = styleBuffer.values[point.style];
+    var radius = 5.0;
+    if (style.point_size > 0.0) {
+      radius = style.point_size;
+    }
 
     let diff_x = vec2f(radius, 0.0);
     let diff_y = vec2f(0.0, radius);
@@ -37,24 +44,39 @@ fn vert_main(@builtin(vertex_index) i : u32) -> VertexOutput {
     output.position = vec4f(center.w * ((pos[i % u32(6)])/size - 0.5) / 0.5, center.z, center.w);
     output.vCenter = vec2f(vCenter.x, uniforms.height - vCenter.y);
     output.flags = point.flags;
+    output.color = style_color(style, uniforms.theme_normal);
+    output.shape = vec2f(radius, f32(style.point_shape));
+    if ((point.flags & 8) == 8){ // deleted
+      output.position = vec4f(0.0, 0.0, 0.0, 0.0);
+    }
     return output;
 }
 
-@fragment
-fn frag_main( v: VertexOutput) -> @location(0) vec4f {
-    var color = vec4f(1.0, 1.0, 1.0, 1.0);
-
-    if ((v.flags & 4) == 4){ // selected
-      color =  vec4f(0.0, 0.0, 1.0, 1.0);
-    }
-    if ((v.flags & 2) == 2){ // hover
-      color = vec4f(1.0, 0.0, 0.0, 1.0);
+// distance of the shape edge to the center in the direction of d, the point is inside below 0
+fn point_shape(d : vec2f, radius : f32, shape : u32) -> f32 {
+    let a = abs(d);
+    switch shape {
+      case 1u: { // square
+        return max(a.x, a.y) - radius;
+      }
+      case 2u: { // diamond
+        return a.x + a.y - radius;
+      }
+      case 3u: { // cross
+        return min(a.x, a.y) - max(1.0, radius * 0.25) + max(0.0, max(a.x, a.y) - radius);
+      }
+      default: { // circle
+        return length(d) - radius;
+      }
     }
+}
 
-    let distance = distance(v.position.xy - vec2f(0.0, uniforms.height_top), v.vCenter);
-    if (distance > 5.0){
-      color = vec4f(0.0, 0.0, 0.0, 0.0);
+@fragment
+fn frag_main( v: VertexOutput) -> @location(0) vec4f {
+    let color = highlight(v.color, v.flags);
+    let d = v.position.xy - vec2f(0.0, uniforms.height_top) - v.vCenter;
+    if (point_shape(d, v.shape.x, u32(v.shape.y)) > 0.0){
+      return vec4f(0.0, 0.0, 0.0, 0.0);
     }
-    
     return color;
-}
\ No newline at end of file
+}
@@ -12,7 +12,7 @@ fn main(@builtin(global_invocation_id) i : vec3<u32>) {
   let position = to_screen_position(pos);
   let d = distance(position, vec2(uniforms.mouse_x, uniforms.mouse_y));
 
-  if(d <= 20.){
+  if(d <= 20. && (point.flags & 8) == 0){
     pointBuffer.values[i.x].flags = pointBuffer.values[i.x].flags | 2;
     let hover_index = atomicAdd(&hoverCounter.counter, 1u);
     hoverBuffer.values[hover_index].index = i.x;
@@ -0,0 +1,42 @@
+use glam::{Vec2, Vec3};
+
+use crate::{
+    core::result::{Error, Result},
+    topology::{
+        profile::{from_plane, Profile},
+        triangulation::triangulate,
+    },
+};
+
+use super::mesh::{TriangleMesh, SMOOTH_ANGLE};
+
+// closed solid between the offsets -back and front along the plane normal of the profile
+pub fn extrude(profile: &Profile, front: f32, back: f32) -> Result<TriangleMesh> {
+    let (start, end) = ((-back).min(front), (-back).max(front));
+    if end - start < 1e-6 {
+        return Err(Error::Geometry("the extrusion has no thickness".into()));
+    }
+
+    let plane = &profile.plane;
+    let normal = plane.orientation.normalize();
+    let at = |p: Vec2, offset: f32| from_plane(plane, p) + normal * offset;
+
+    let mut mesh = TriangleMesh::default();
+
+    let holes: Vec<_> = profile.holes.iter().map(|h| h.polygon.clone()).collect();
+    let (points, triangles) = triangulate(&profile.outer.polygon, &holes);
+    for [a, b, c] in triangles {
+        mesh.push(at(points[a], end), at(points[b], end), at(points[c], end));
+        mesh.push(at(points[a], start), at(points[c], start), at(points[b], start));
+    }
+
+    // the outer loop runs counter clockwise and the holes clockwise, so the walls face outwards
+    for ring in [&profile.outer].into_iter().chain(profile.holes.iter()) {
+        let bottom: Vec<Vec3> = ring.polygon.iter().map(|p| at(*p, start)).collect();
+        let top: Vec<Vec3> = ring.polygon.iter().map(|p| at(*p, end)).collect();
+        mesh.stitch(&bottom, &top, true);
+    }
+
+    mesh.smooth(SMOOTH_ANGLE);
+    return Ok(mesh);
+}
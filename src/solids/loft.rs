@@ -0,0 +1,180 @@
+use glam::{Vec2, Vec3};
+
+use crate::{
+    core::result::{Error, Result},
+    topology::{
+        profile::{from_plane, signed_area, Profile},
+        triangulation::triangulate,
+    },
+};
+
+use super::mesh::{TriangleMesh, SMOOTH_ANGLE};
+
+const MIN_POINTS: usize = 64;
+const MAX_POINTS: usize = 512;
+const SMOOTH_STEPS: usize = 8;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum LoftMode {
+    // straight walls between neighbouring profiles
+    Ruled,
+    // catmull rom spline through all profiles
+    Smooth,
+}
+
+// count points evenly spread along the closed polygon, starting at its first point
+fn resample(polygon: &[Vec2], count: usize) -> Vec<Vec2> {
+    let n = polygon.len();
+    let lengths: Vec<f32> = (0..n)
+        .map(|i| polygon[i].distance(polygon[(i + 1) % n]))
+        .collect();
+    let total: f32 = lengths.iter().sum();
+
+    let mut points = vec![];
+    let mut segment = 0;
+    let mut start = 0.;
+    for k in 0..count {
+        let target = total * k as f32 / count as f32;
+        while segment < n - 1 && start + lengths[segment] < target {
+            start += lengths[segment];
+            segment += 1;
+        }
+        let t = ((target - start) / lengths[segment].max(1e-12)).clamp(0., 1.);
+        points.push(polygon[segment].lerp(polygon[(segment + 1) % n], t));
+    }
+    return points;
+}
+
+fn catmull_rom(p0: Vec3, p1: Vec3, p2: Vec3, p3: Vec3, t: f32) -> Vec3 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    return 0.5
+        * (2. * p1
+            + (p2 - p0) * t
+            + (2. * p0 - 5. * p1 + 4. * p2 - p3) * t2
+            + (3. * p1 - p0 - 3. * p2 + p3) * t3);
+}
+
+struct Section {
+    offset: f32,
+    // counter clockwise around the normal of the first profile
+    polygon: Vec<Vec2>,
+    profile: Profile,
+}
+
+// blends between parallel closed profiles without holes
+pub fn loft(profiles: &[Profile], mode: LoftMode) -> Result<TriangleMesh> {
+    if profiles.len() < 2 {
+        return Err(Error::Geometry("a loft needs at least two profiles".into()));
+    }
+    if profiles.iter().any(|p| !p.holes.is_empty()) {
+        return Err(Error::Geometry("profiles with holes can't be lofted".into()));
+    }
+    let normal = profiles[0].plane.orientation.normalize();
+    if profiles
+        .iter()
+        .any(|p| p.plane.orientation.normalize().dot(normal).abs() < 1. - 1e-4)
+    {
+        return Err(Error::Geometry("the loft profiles have to be parallel".into()));
+    }
+
+    let count = profiles
+        .iter()
+        .map(|p| p.outer.polygon.len())
+        .max()
+        .unwrap()
+        .clamp(MIN_POINTS, MAX_POINTS);
+
+    let mut sections: Vec<Section> = profiles
+        .iter()
+        .map(|profile| {
+            let plane = &profile.plane;
+            let mut polygon = profile.outer.polygon.clone();
+            // a plane facing the other way mirrors the winding
+            if plane.orientation.dot(normal) < 0. {
+                polygon.reverse();
+            }
+            // every section starts at its point furthest along the same direction
+            let (u, _) = profiles[0].plane.basis();
+            let center = polygon.iter().sum::<Vec2>() / polygon.len() as f32;
+            let start = (0..polygon.len())
+                .max_by(|a, b| {
+                    let a = (from_plane(plane, polygon[*a]) - from_plane(plane, center)).dot(u);
+                    let b = (from_plane(plane, polygon[*b]) - from_plane(plane, center)).dot(u);
+                    a.partial_cmp(&b).unwrap()
+                })
+                .unwrap();
+            polygon.rotate_left(start);
+            Section {
+                offset: plane.position.dot(normal),
+                polygon: resample(&polygon, count),
+                profile: profile.clone(),
+            }
+        })
+        .collect();
+    sections.sort_by(|a, b| a.offset.partial_cmp(&b.offset).unwrap());
+    if sections.windows(2).any(|w| w[1].offset - w[0].offset < 1e-4) {
+        return Err(Error::Geometry("the loft profiles have to lie in different planes".into()));
+    }
+
+    let rings: Vec<Vec<Vec3>> = sections
+        .iter()
+        .map(|s| s.polygon.iter().map(|p| from_plane(&s.profile.plane, *p)).collect())
+        .collect();
+    let rings = match mode {
+        LoftMode::Ruled => rings,
+        LoftMode::Smooth => {
+            let last = rings.len() - 1;
+            let mut smooth = vec![];
+            for k in 0..last {
+                let (p0, p1, p2, p3) = (
+                    &rings[k.saturating_sub(1)],
+                    &rings[k],
+                    &rings[k + 1],
+                    &rings[(k + 2).min(last)],
+                );
+                for step in 0..SMOOTH_STEPS {
+                    let t = step as f32 / SMOOTH_STEPS as f32;
+                    smooth.push(
+                        (0..count)
+                            .map(|i| catmull_rom(p0[i], p1[i], p2[i], p3[i], t))
+                            .collect(),
+                    );
+                }
+            }
+            smooth.push(rings[last].clone());
+            smooth
+        }
+    };
+
+    let mut mesh = TriangleMesh::default();
+    for k in 0..rings.len() - 1 {
+        mesh.stitch(&rings[k], &rings[k + 1], true);
+    }
+
+    // caps facing away from the loft, the polygons run counter clockwise around the normal
+    for (section, ring, outwards) in [
+        (&sections[0], &rings[0], false),
+        (&sections[sections.len() - 1], &rings[rings.len() - 1], true),
+    ] {
+        let mut polygon = section.polygon.clone();
+        let mirrored = signed_area(&polygon) < 0.;
+        if mirrored {
+            polygon.reverse();
+        }
+        let (_, triangles) = triangulate(&polygon, &[]);
+        let index = |i: usize| if mirrored { count - 1 - i } else { i };
+        for [a, b, c] in triangles {
+            let (a, b, c) = (ring[index(a)], ring[index(b)], ring[index(c)]);
+            let facing = (b - a).cross(c - a).dot(normal) > 0.;
+            if facing == outwards {
+                mesh.push(a, b, c);
+            } else {
+                mesh.push(a, c, b);
+            }
+        }
+    }
+
+    mesh.smooth(SMOOTH_ANGLE);
+    return Ok(mesh);
+}
@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+
+use glam::Vec3;
+
+use crate::{
+    component_collection::ComponentCollection,
+    components::{
+        component::Component,
+        mesh::{self, MeshVertex},
+    },
+    core::result::{Error, Result},
+    rendering::buffer::binding_limit,
+};
+
+// normals of neighbouring triangles closer than this are averaged
+pub const SMOOTH_ANGLE: f32 = 30.;
+// positions closer than this are the same vertex when welding, in mm
+const WELD_TOLERANCE: f32 = 1e-4;
+
+// an unindexed triangle list, three positions and normals per triangle
+#[derive(Clone, Debug, Default)]
+pub struct TriangleMesh {
+    pub positions: Vec<Vec3>,
+    pub normals: Vec<Vec3>,
+}
+
+impl TriangleMesh {
+    pub fn triangle_count(&self) -> usize {
+        self.positions.len() / 3
+    }
+
+    pub fn triangle(&self, index: usize) -> [Vec3; 3] {
+        [
+            self.positions[index * 3],
+            self.positions[index * 3 + 1],
+            self.positions[index * 3 + 2],
+        ]
+    }
+
+    // counter clockwise seen from the side the normal points to
+    pub fn push(&mut self, a: Vec3, b: Vec3, c: Vec3) {
+        let normal = (b - a).cross(c - a).normalize_or_zero();
+        self.positions.extend([a, b, c]);
+        self.normals.extend([normal; 3]);
+    }
+
+    pub fn push_quad(&mut self, a: Vec3, b: Vec3, c: Vec3, d: Vec3) {
+        self.push(a, b, c);
+        self.push(a, c, d);
+    }
+
+    pub fn append(&mut self, other: &TriangleMesh) {
+        self.positions.extend(other.positions.iter());
+        self.normals.extend(other.normals.iter());
+    }
+
+    // quads between two rows of points facing along (a[i + 1] - a[i]) x (b[i] - a[i])
+    pub fn stitch(&mut self, a: &[Vec3], b: &[Vec3], closed: bool) {
+        let n = a.len().min(b.len());
+        let segments = if closed { n } else { n.saturating_sub(1) };
+        for i in 0..segments {
+            let j = (i + 1) % n;
+            self.push_quad(a[i], a[j], b[j], b[i]);
+        }
+    }
+
+    pub fn flip(&mut self) {
+        for triangle in self.positions.chunks_mut(3) {
+            triangle.swap(1, 2);
+        }
+        for triangle in self.normals.chunks_mut(3) {
+            triangle.swap(1, 2);
+            for normal in triangle.iter_mut() {
+                *normal = -*normal;
+            }
+        }
+    }
+
+    // shared vertices and triangles as indices into them
+    pub fn weld(&self) -> (Vec<Vec3>, Vec<[u32; 3]>) {
+        let (vertices, mut triangles) = self.weld_all();
+        // triangles collapsed by welding have no area
+        triangles.retain(|t| t[0] != t[1] && t[1] != t[2] && t[2] != t[0]);
+        return (vertices, triangles);
+    }
+
+    // averages the normals of neighbouring triangles unless they meet at a sharp edge
+    pub fn smooth(&mut self, angle: f32) {
+        let (_, triangles) = self.weld_all();
+        let faces: Vec<Vec3> = (0..self.triangle_count())
+            .map(|i| {
+                let [a, b, c] = self.triangle(i);
+                (b - a).cross(c - a)
+            })
+            .collect();
+
+        let mut around: HashMap<u32, Vec<usize>> = HashMap::new();
+        for (i, triangle) in triangles.iter().enumerate() {
+            for vertex in triangle {
+                around.entry(*vertex).or_insert(vec![]).push(i);
+            }
+        }
+
+        let limit = angle.to_radians().cos();
+        for (i, triangle) in triangles.iter().enumerate() {
+            let face = faces[i].normalize_or_zero();
+            for (corner, vertex) in triangle.iter().enumerate() {
+                let normal: Vec3 = around[vertex]
+                    .iter()
+                    .filter(|j| faces[**j].normalize_or_zero().dot(face) >= limit)
+                    .map(|j| faces[*j])
+                    .sum();
+                self.normals[i * 3 + corner] = normal.normalize_or_zero();
+            }
+        }
+    }
+
+    // corners with the same position and normal share one vertex, the triangles index them in order
+    pub fn indexed(&self) -> (Vec<(Vec3, Vec3)>, Vec<u32>) {
+        let mut index: HashMap<[u32; 6], u32> = HashMap::new();
+        let mut vertices = vec![];
+        let indices = self
+            .positions
+            .iter()
+            .zip(self.normals.iter())
+            .map(|(p, n)| {
+                // adding zero turns -0 into 0, they are the same coordinate
+                let key = [p.x, p.y, p.z, n.x, n.y, n.z].map(|v| (v + 0.).to_bits());
+                *index.entry(key).or_insert_with(|| {
+                    vertices.push((*p, *n));
+                    vertices.len() as u32 - 1
+                })
+            })
+            .collect();
+        return (vertices, indices);
+    }
+
+    // like weld but keeps degenerate triangles so the indices match the triangle list
+    fn weld_all(&self) -> (Vec<Vec3>, Vec<[u32; 3]>) {
+        let mut index: HashMap<[i64; 3], u32> = HashMap::new();
+        let mut vertices = vec![];
+        let triangles = (0..self.triangle_count())
+            .map(|i| {
+                self.triangle(i).map(|p| {
+                    let key = p.to_array().map(|v| (v / WELD_TOLERANCE).round() as i64);
+                    *index.entry(key).or_insert_with(|| {
+                        vertices.push(p);
+                        vertices.len() as u32 - 1
+                    })
+                })
+            })
+            .collect();
+        return (vertices, triangles);
+    }
+
+    pub fn from_component(components: &ComponentCollection, index: usize) -> TriangleMesh {
+        let mesh = &components.meshes.array[index].data;
+        let indices = &components.mesh_indices.indices[mesh.first as usize..(mesh.first + mesh.count) as usize];
+        let vertices: Vec<_> = indices.iter().map(|i| &components.mesh_vertices.array[*i as usize].data).collect();
+        TriangleMesh {
+            positions: vertices.iter().map(|v| v.position).collect(),
+            normals: vertices.iter().map(|v| v.normal).collect(),
+        }
+    }
+}
+
+// the vertices and indices of all meshes each have to fit into one storage buffer binding
+pub fn push_mesh(components: &mut ComponentCollection, mesh: &TriangleMesh) -> Result<usize> {
+    let index = components.meshes.array.len() as u32;
+    let base = components.mesh_vertices.array.len();
+    let (shared, indices) = mesh.indexed();
+
+    let vertex_size = std::mem::size_of::<Component<MeshVertex>>();
+    if (base + shared.len()) * vertex_size > binding_limit(&components.mesh_vertices.device)
+        || !components.mesh_indices.fits(indices.len())
+    {
+        return Err(Error::Memory(format!(
+            "the {} triangle(s) don't fit into the gpu memory for meshes",
+            mesh.triangle_count()
+        )));
+    }
+
+    let vertices = shared
+        .iter()
+        .map(|(position, normal)| mesh::vertex(*position, *normal, index))
+        .collect();
+    components.mesh_vertices.extend(vertices);
+    let indices: Vec<u32> = indices.iter().map(|i| i + base as u32).collect();
+    let first = components.mesh_indices.append(&indices)?;
+    return Ok(components.meshes.push(mesh::new(first, indices.len() as u32)));
+}
@@ -0,0 +1,5 @@
+pub mod mesh;
+pub mod extrude;
+pub mod revolve;
+pub mod sweep;
+pub mod loft;
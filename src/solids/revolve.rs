@@ -0,0 +1,165 @@
+use glam::{Quat, Vec3};
+
+use crate::{
+    core::result::{Error, Result},
+    topology::{
+        profile::{from_plane, Profile},
+        tessellation::arc_segments,
+        triangulation::triangulate,
+    },
+};
+
+use super::mesh::{TriangleMesh, SMOOTH_ANGLE};
+
+const AXIS_TOLERANCE: f32 = 1e-3;
+
+pub struct Axis {
+    pub origin: Vec3,
+    pub direction: Vec3,
+}
+
+impl Axis {
+    pub fn new(a: Vec3, b: Vec3) -> Result<Axis> {
+        if a.distance(b) < AXIS_TOLERANCE {
+            return Err(Error::Geometry("the axis line has no length".into()));
+        }
+        return Ok(Axis {
+            origin: a,
+            direction: (b - a).normalize(),
+        });
+    }
+
+    fn radial(&self, point: Vec3) -> Vec3 {
+        let local = point - self.origin;
+        return local - self.direction * local.dot(self.direction);
+    }
+
+    fn rotate(&self, point: Vec3, angle: f32) -> Vec3 {
+        self.origin + Quat::from_axis_angle(self.direction, angle) * (point - self.origin)
+    }
+}
+
+fn is_full(angle: f32) -> bool {
+    angle.abs() >= std::f32::consts::TAU - 1e-4
+}
+
+// the side of the axis every point lies on, points on the axis don't count
+fn side(axis: &Axis, points: &[Vec3], normal: Vec3) -> Result<f32> {
+    let sides: Vec<f32> = points
+        .iter()
+        .map(|p| axis.direction.cross(axis.radial(*p)).dot(normal))
+        .collect();
+    let positive = sides.iter().any(|s| *s > AXIS_TOLERANCE);
+    let negative = sides.iter().any(|s| *s < -AXIS_TOLERANCE);
+    if positive && negative {
+        return Err(Error::Geometry("the profile crosses the revolve axis".into()));
+    }
+    if !positive && !negative {
+        return Err(Error::Geometry("the profile lies on the revolve axis".into()));
+    }
+    return Ok(if positive { 1. } else { -1. });
+}
+
+fn angles(axis: &Axis, points: &[Vec3], angle: f32, tolerance: f32) -> Vec<f32> {
+    let radius = points
+        .iter()
+        .map(|p| axis.radial(*p).length())
+        .fold(0., f32::max);
+    let segments = arc_segments(radius, angle, tolerance).max(if is_full(angle) { 8 } else { 1 });
+    let count = if is_full(angle) { segments } else { segments + 1 };
+    return (0..count)
+        .map(|i| angle * i as f32 / segments as f32)
+        .collect();
+}
+
+// stitches the rotated copies of the points, full turns wrap around
+fn sweep(
+    mesh: &mut TriangleMesh,
+    axis: &Axis,
+    points: &[Vec3],
+    angles: &[f32],
+    full: bool,
+    closed: bool,
+) {
+    let rings: Vec<Vec<Vec3>> = angles
+        .iter()
+        .map(|a| points.iter().map(|p| axis.rotate(*p, *a)).collect())
+        .collect();
+    let count = if full { rings.len() } else { rings.len() - 1 };
+    for k in 0..count {
+        mesh.stitch(&rings[k], &rings[(k + 1) % rings.len()], closed);
+    }
+}
+
+// solid of a closed profile turned around an axis in its plane, angle in radians
+pub fn revolve_profile(profile: &Profile, axis: &Axis, angle: f32, tolerance: f32) -> Result<TriangleMesh> {
+    let plane = &profile.plane;
+    let normal = plane.orientation.normalize();
+    if axis.direction.dot(normal).abs() > 1e-4
+        || (axis.origin - plane.position).dot(normal).abs() > AXIS_TOLERANCE
+    {
+        return Err(Error::Geometry("the axis has to lie in the plane of the profile".into()));
+    }
+    if angle.abs() < 1e-4 {
+        return Err(Error::Geometry("the revolve angle is zero".into()));
+    }
+
+    let outer: Vec<Vec3> = profile.outer.polygon.iter().map(|p| from_plane(plane, *p)).collect();
+    // with a positive side the profile moves along the plane normal, like an extrusion
+    let side = side(axis, &outer, normal)? * angle.signum();
+    let angles = angles(axis, &outer, angle, tolerance);
+    let full = is_full(angle);
+
+    let mut mesh = TriangleMesh::default();
+    for ring in [&profile.outer].into_iter().chain(profile.holes.iter()) {
+        let points: Vec<Vec3> = ring.polygon.iter().map(|p| from_plane(plane, *p)).collect();
+        sweep(&mut mesh, axis, &points, &angles, full, true);
+    }
+
+    if !full {
+        let holes: Vec<_> = profile.holes.iter().map(|h| h.polygon.clone()).collect();
+        let (points, triangles) = triangulate(&profile.outer.polygon, &holes);
+        let points: Vec<Vec3> = points.iter().map(|p| from_plane(plane, *p)).collect();
+        let end = |p: usize| axis.rotate(points[p], angle);
+        for [a, b, c] in triangles {
+            mesh.push(points[a], points[c], points[b]);
+            mesh.push(end(a), end(b), end(c));
+        }
+    }
+
+    if side < 0. {
+        mesh.flip();
+    }
+    mesh.smooth(SMOOTH_ANGLE);
+    return Ok(mesh);
+}
+
+// surface of an open path turned around an axis, angle in radians
+pub fn revolve_path(points: &[Vec3], axis: &Axis, angle: f32, tolerance: f32) -> Result<TriangleMesh> {
+    if points.len() < 2 {
+        return Err(Error::Geometry("the path is too short".into()));
+    }
+    if angle.abs() < 1e-4 {
+        return Err(Error::Geometry("the revolve angle is zero".into()));
+    }
+
+    // the plane through the axis and the point furthest away from it
+    let far = points
+        .iter()
+        .max_by(|a, b| {
+            let a = axis.radial(**a).length();
+            let b = axis.radial(**b).length();
+            a.partial_cmp(&b).unwrap()
+        })
+        .unwrap();
+    let normal = axis.direction.cross(axis.radial(*far)).normalize_or_zero();
+    if normal == Vec3::ZERO {
+        return Err(Error::Geometry("the profile lies on the revolve axis".into()));
+    }
+    side(axis, points, normal)?;
+
+    let mut mesh = TriangleMesh::default();
+    sweep(&mut mesh, axis, points, &angles(axis, points, angle, tolerance), is_full(angle), false);
+    mesh.smooth(SMOOTH_ANGLE);
+    return Ok(mesh);
+}
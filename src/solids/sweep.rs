@@ -0,0 +1,155 @@
+use glam::{Quat, Vec3};
+
+use crate::{
+    core::{
+        basics::orthogonal,
+        result::{Error, Result},
+    },
+    topology::{
+        profile::{from_plane, Profile},
+        triangulation::triangulate,
+    },
+};
+
+use super::mesh::{TriangleMesh, SMOOTH_ANGLE};
+
+// position, tangent and normal along a path, the binormal is tangent x normal
+#[derive(Clone, Copy)]
+pub struct Frame {
+    pub position: Vec3,
+    pub tangent: Vec3,
+    pub normal: Vec3,
+}
+
+impl Frame {
+    fn to_local(&self, point: Vec3) -> Vec3 {
+        let d = point - self.position;
+        let binormal = self.tangent.cross(self.normal);
+        Vec3::new(d.dot(self.normal), d.dot(binormal), d.dot(self.tangent))
+    }
+
+    fn to_world(&self, local: Vec3) -> Vec3 {
+        let binormal = self.tangent.cross(self.normal);
+        self.position + self.normal * local.x + binormal * local.y + self.tangent * local.z
+    }
+}
+
+fn is_closed(points: &[Vec3]) -> bool {
+    points.len() > 3 && points[0].distance(points[points.len() - 1]) < 1e-4
+}
+
+// rotation minimizing frames by double reflection (Wang, Jüttler, Zheng, Liu 2008)
+pub fn rotation_minimizing_frames(points: &[Vec3]) -> Vec<Frame> {
+    let n = points.len();
+    let closed = is_closed(points);
+    // central differences, the ends of a closed path share their neighbours
+    let tangents: Vec<Vec3> = (0..n)
+        .map(|i| {
+            let (prev, next) = if closed && (i == 0 || i == n - 1) {
+                (points[n - 2], points[1])
+            } else {
+                (points[i.saturating_sub(1)], points[(i + 1).min(n - 1)])
+            };
+            (next - prev).normalize_or_zero()
+        })
+        .collect();
+
+    let mut frames = vec![Frame {
+        position: points[0],
+        tangent: tangents[0],
+        normal: orthogonal(tangents[0]).normalize(),
+    }];
+    for i in 0..n - 1 {
+        let frame = frames[i];
+        let v1 = points[i + 1] - points[i];
+        let c1 = v1.dot(v1);
+        let (r, t) = if c1 > 1e-12 {
+            (
+                frame.normal - v1 * (2. / c1) * v1.dot(frame.normal),
+                frame.tangent - v1 * (2. / c1) * v1.dot(frame.tangent),
+            )
+        } else {
+            (frame.normal, frame.tangent)
+        };
+        let v2 = tangents[i + 1] - t;
+        let c2 = v2.dot(v2);
+        let normal = if c2 > 1e-12 { r - v2 * (2. / c2) * v2.dot(r) } else { r };
+        frames.push(Frame {
+            position: points[i + 1],
+            tangent: tangents[i + 1],
+            normal: normal.normalize(),
+        });
+    }
+
+    // a closed path ends with a twist against its start, it is spread evenly along the path
+    if closed {
+        let last = frames[n - 1];
+        let first = frames[0];
+        let angle = last
+            .normal
+            .cross(first.normal)
+            .dot(first.tangent)
+            .atan2(last.normal.dot(first.normal));
+        for (i, frame) in frames.iter_mut().enumerate() {
+            let rotation = Quat::from_axis_angle(frame.tangent, angle * i as f32 / (n - 1) as f32);
+            frame.normal = rotation * frame.normal;
+        }
+    }
+    return frames;
+}
+
+// moves a closed profile along a path, the profile keeps its placement relative to the path start
+pub fn sweep(profile: &Profile, path: &[Vec3]) -> Result<TriangleMesh> {
+    if path.len() < 2 {
+        return Err(Error::Geometry("the path is too short".into()));
+    }
+    let frames = rotation_minimizing_frames(path);
+    let plane = &profile.plane;
+    let normal = plane.orientation.normalize();
+    let direction = normal.dot(frames[0].tangent);
+    if direction.abs() < 1e-3 {
+        return Err(Error::Geometry("the path has to leave the plane of the profile".into()));
+    }
+    let closed = is_closed(path);
+    // the closing frame is the first one again
+    let sections = if closed { &frames[..frames.len() - 1] } else { &frames[..] };
+
+    let mut mesh = TriangleMesh::default();
+    for ring in [&profile.outer].into_iter().chain(profile.holes.iter()) {
+        let local: Vec<Vec3> = ring
+            .polygon
+            .iter()
+            .map(|p| frames[0].to_local(from_plane(plane, *p)))
+            .collect();
+        let rings: Vec<Vec<Vec3>> = sections
+            .iter()
+            .map(|f| local.iter().map(|l| f.to_world(*l)).collect())
+            .collect();
+        let count = if closed { rings.len() } else { rings.len() - 1 };
+        for k in 0..count {
+            mesh.stitch(&rings[k], &rings[(k + 1) % rings.len()], true);
+        }
+    }
+
+    if !closed {
+        let holes: Vec<_> = profile.holes.iter().map(|h| h.polygon.clone()).collect();
+        let (points, triangles) = triangulate(&profile.outer.polygon, &holes);
+        let local: Vec<Vec3> = points
+            .iter()
+            .map(|p| frames[0].to_local(from_plane(plane, *p)))
+            .collect();
+        let end = frames[frames.len() - 1];
+        let start = frames[0];
+        for [a, b, c] in triangles {
+            mesh.push(start.to_world(local[a]), start.to_world(local[c]), start.to_world(local[b]));
+            mesh.push(end.to_world(local[a]), end.to_world(local[b]), end.to_world(local[c]));
+        }
+    }
+
+    // like an extrusion when the path leaves along the plane normal
+    if direction < 0. {
+        mesh.flip();
+    }
+    mesh.smooth(SMOOTH_ANGLE);
+    return Ok(mesh);
+}
@@ -0,0 +1,102 @@
+use glam::Vec3;
+
+use crate::{
+    component_collection::ComponentCollection,
+    components::component::{
+        ComponentIdentifier, ComponentType, IndexPosition, WalkDirection,
+    },
+    core::result::{Error, Result},
+};
+
+use super::{profile::LoopEdge, tessellation::tessellate};
+
+// orders the curves so each one starts where the previous ended, open ends come first
+pub fn chain(
+    components: &ComponentCollection,
+    curves: &[ComponentIdentifier],
+) -> Result<Vec<LoopEdge>> {
+    let ends: Vec<(Option<u32>, Option<u32>)> = curves
+        .iter()
+        .map(|c| {
+            components.get_c(c.ctype, c.index as usize, |component| match component {
+                Some(component) => (
+                    component.get_index(WalkDirection::Up, IndexPosition::First, c.index),
+                    component.get_index(WalkDirection::Up, IndexPosition::Last, c.index),
+                ),
+                None => (None, None),
+            })
+        })
+        .collect();
+
+    if curves.is_empty() {
+        return Err(Error::Geometry("select the curves of the profile".into()));
+    }
+    // closed curves like circles have no ends and can only stand alone
+    if ends.iter().any(|(a, b)| a.is_none() || b.is_none()) {
+        if curves.len() == 1 && matches!(curves[0].ctype, ComponentType::Circle | ComponentType::Ellipse) {
+            return Ok(vec![LoopEdge {
+                ctype: curves[0].ctype,
+                index: curves[0].index,
+                direction: WalkDirection::Up,
+            }]);
+        }
+        return Err(Error::Geometry("only lines, beziers, arcs, nurbs or a single circle or ellipse can form a path".into()));
+    }
+
+    let count = |point: u32| {
+        ends.iter()
+            .map(|(a, b)| (*a == Some(point)) as usize + (*b == Some(point)) as usize)
+            .sum::<usize>()
+    };
+    let mut current = ends
+        .iter()
+        .flat_map(|(a, b)| [a.unwrap(), b.unwrap()])
+        .find(|p| count(*p) == 1)
+        .unwrap_or(ends[0].0.unwrap());
+
+    let mut used = vec![false; curves.len()];
+    let mut edges = vec![];
+    while let Some(i) = (0..curves.len()).find(|i| {
+        !used[*i] && (ends[*i].0 == Some(current) || ends[*i].1 == Some(current))
+    }) {
+        used[i] = true;
+        let direction = if ends[i].0 == Some(current) {
+            WalkDirection::Up
+        } else {
+            WalkDirection::Down
+        };
+        current = if direction == WalkDirection::Up {
+            ends[i].1.unwrap()
+        } else {
+            ends[i].0.unwrap()
+        };
+        edges.push(LoopEdge {
+            ctype: curves[i].ctype,
+            index: curves[i].index,
+            direction,
+        });
+    }
+
+    if used.iter().any(|u| !u) {
+        return Err(Error::Geometry("the selected curves are not connected to one path".into()));
+    }
+    return Ok(edges);
+}
+
+// polyline along the chained curves, closed chains repeat their first point at the end
+pub fn chain_points(components: &ComponentCollection, edges: &[LoopEdge], tolerance: f32) -> Vec<Vec3> {
+    let mut points: Vec<Vec3> = vec![];
+    for edge in edges.iter() {
+        components.get_c(edge.ctype, edge.index as usize, |c| {
+            let c = c.unwrap();
+            let position = |t: f32| c.get_position(components, edge.direction, t);
+            let parameters = match edge.ctype {
+                ComponentType::Line => vec![0., 1.],
+                _ => tessellate(position, tolerance),
+            };
+            let skip = if points.is_empty() { 0 } else { 1 };
+            points.extend(parameters.iter().skip(skip).map(|t| position(*t)));
+        });
+    }
+    return points;
+}
@@ -0,0 +1,132 @@
+use crate::{
+    component_collection::ComponentCollection,
+    components::{
+        component::{ComponentFlags, ComponentType},
+        face,
+    },
+    core::basics::Plane,
+};
+
+use super::{
+    profile::{find_profiles, from_plane, LoopEdge, Profile},
+    triangulation::triangulate,
+};
+
+// the filled faces of the closed profiles in the active plane
+#[derive(Default)]
+pub struct Fills {
+    pub profiles: Vec<Profile>,
+    fingerprint: Vec<u32>,
+}
+
+impl Fills {
+    // rebuilds the faces when the curves or the plane changed
+    pub fn update(&mut self, components: &mut ComponentCollection, plane: &Plane, tolerance: f32) {
+        let mut fingerprint = fingerprint(components, plane);
+        fingerprint.push(tolerance.to_bits());
+        if fingerprint == self.fingerprint {
+            return;
+        }
+        self.fingerprint = fingerprint;
+
+        let profiles = find_profiles(components, plane, tolerance);
+        let mut faces = vec![];
+        let mut vertices = vec![];
+        for (i, profile) in profiles.iter().enumerate() {
+            let holes: Vec<_> = profile.holes.iter().map(|h| h.polygon.clone()).collect();
+            let (points, triangles) = triangulate(&profile.outer.polygon, &holes);
+
+            let first = vertices.len() as u32;
+            for triangle in triangles.iter() {
+                for index in triangle {
+                    vertices.push(face::vertex(from_plane(plane, points[*index]), i as u32));
+                }
+            }
+            let mut face = face::new(first, vertices.len() as u32 - first);
+
+            // keep hover and selection on faces that still have the same boundary
+            if let Some(old) = self.find(&profile.outer.edges) {
+                face.flags = components.faces.array[old].flags;
+            }
+            faces.push(face);
+        }
+
+        let kept: Vec<Option<usize>> = (0..self.profiles.len())
+            .map(|old| {
+                profiles
+                    .iter()
+                    .position(|p| p.outer.edges == self.profiles[old].outer.edges)
+            })
+            .collect();
+        components.selected = components
+            .selected
+            .iter()
+            .filter_map(|s| {
+                let mut s = s.clone();
+                if s.ctype == ComponentType::Face {
+                    s.index = kept.get(s.index as usize).copied().flatten()? as u32;
+                }
+                Some(s)
+            })
+            .collect();
+
+        components.faces.replace(faces);
+        components.face_vertices.replace(vertices);
+        self.profiles = profiles;
+    }
+
+    fn find(&self, edges: &Vec<LoopEdge>) -> Option<usize> {
+        self.profiles.iter().position(|p| p.outer.edges == *edges)
+    }
+}
+
+// everything the profiles depend on, hover and selection flags are left out
+fn fingerprint(components: &ComponentCollection, plane: &Plane) -> Vec<u32> {
+    // construction curves don't bound profiles, toggling it changes them like deleting
+    let removed = ComponentFlags::Deleted as i32 | ComponentFlags::Construction as i32;
+    let deleted = |flags: i32| (flags & removed) as u32;
+    let mut data = vec![];
+    for v in [plane.position, plane.orientation] {
+        data.extend(v.to_array().map(f32::to_bits));
+    }
+    for p in components.points.array.iter() {
+        data.extend(p.data.position.to_array().map(f32::to_bits));
+        data.push(deleted(p.flags));
+    }
+    for l in components.lines.array.iter() {
+        data.extend([l.data.point_a, l.data.point_b, deleted(l.flags)]);
+    }
+    for b in components.beziers.array.iter() {
+        let b_data = &b.data;
+        data.extend([b_data.point_a, b_data.point_b, b_data.control_a, b_data.control_b]);
+        data.push(deleted(b.flags));
+    }
+    for c in components.circles.array.iter() {
+        data.extend([c.data.center, c.data.radius.to_bits(), c.data.heightfactor.to_bits()]);
+        data.extend([c.data.turns.to_bits(), c.data.growth.to_bits()]);
+        data.extend(c.data.orientation.to_array().map(f32::to_bits));
+        data.push(deleted(c.flags));
+    }
+    for a in components.arcs.array.iter() {
+        data.extend([a.data.center, a.data.start, a.data.end]);
+        data.extend(a.data.orientation.to_array().map(f32::to_bits));
+        data.push(deleted(a.flags));
+    }
+    for n in components.nurbs.array.iter() {
+        data.extend([n.data.first, n.data.count, n.data.first_knot, n.data.degree, deleted(n.flags)]);
+    }
+    for c in components.nurbs_controls.array.iter() {
+        data.extend([c.data.point, c.data.weight.to_bits()]);
+    }
+    for k in components.nurbs_knots.array.iter() {
+        data.push(k.data.value.to_bits());
+    }
+    for e in components.ellipses.array.iter() {
+        let e_data = &e.data;
+        data.extend([e_data.center, e_data.radius_a.to_bits(), e_data.radius_b.to_bits()]);
+        data.extend(e_data.orientation.to_array().map(f32::to_bits));
+        data.extend([e_data.rotation, e_data.start_angle, e_data.sweep_angle].map(f32::to_bits));
+        data.push(deleted(e.flags));
+    }
+    return data;
+}
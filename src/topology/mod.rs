@@ -0,0 +1,6 @@
+pub mod chain;
+pub mod fill;
+pub mod profile;
+pub mod tessellation;
+pub mod text;
+pub mod triangulation;
@@ -0,0 +1,421 @@
+use std::collections::HashMap;
+
+use glam::{vec2, Vec2, Vec3};
+
+use crate::{
+    component_collection::ComponentCollection,
+    components::component::{ComponentType, IComponent, IndexPosition, WalkDirection},
+    core::basics::Plane,
+};
+
+use super::tessellation::tessellate;
+
+const PLANE_TOLERANCE: f32 = 1e-3;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LoopEdge {
+    pub ctype: ComponentType,
+    pub index: u32,
+    pub direction: WalkDirection,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+}
+
+// a closed chain of curves, the polygon is in the local 2d frame of the sketch plane
+#[derive(Clone, Debug)]
+pub struct Loop {
+    pub edges: Vec<LoopEdge>,
+    pub polygon: Vec<Vec2>,
+    pub area: f32,
+}
+
+// an outer boundary (counter clockwise) with its holes (clockwise)
+#[derive(Clone, Debug)]
+pub struct Profile {
+    pub plane: Plane,
+    pub outer: Loop,
+    pub holes: Vec<Loop>,
+    pub depth: usize,
+}
+
+impl Loop {
+    fn new(
+        edges: Vec<LoopEdge>,
+        components: &ComponentCollection,
+        plane: &Plane,
+        tolerance: f32,
+    ) -> Loop {
+        let mut polygon = vec![];
+        for edge in edges.iter() {
+            let mut points = edge_points(components, plane, edge, tolerance);
+            points.pop();
+            polygon.extend(points);
+        }
+        let area = signed_area(&polygon);
+        return Loop {
+            edges,
+            polygon,
+            area,
+        };
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        if self.area < 0. {
+            Orientation::Clockwise
+        } else {
+            Orientation::CounterClockwise
+        }
+    }
+
+    pub fn reversed(&self) -> Loop {
+        Loop {
+            edges: self
+                .edges
+                .iter()
+                .rev()
+                .map(|e| LoopEdge {
+                    ctype: e.ctype,
+                    index: e.index,
+                    direction: e.direction.reverse(),
+                })
+                .collect(),
+            polygon: self.polygon.iter().rev().copied().collect(),
+            area: -self.area,
+        }
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        let mut inside = false;
+        let n = self.polygon.len();
+        for i in 0..n {
+            let a = self.polygon[i];
+            let b = self.polygon[(i + 1) % n];
+            if (a.y > point.y) != (b.y > point.y)
+                && point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x
+            {
+                inside = !inside;
+            }
+        }
+        return inside;
+    }
+
+    pub fn distance(&self, point: Vec2) -> f32 {
+        let n = self.polygon.len();
+        let mut distance = f32::MAX;
+        for i in 0..n {
+            let a = self.polygon[i];
+            let b = self.polygon[(i + 1) % n];
+            let ab = b - a;
+            let t = ((point - a).dot(ab) / ab.length_squared().max(1e-12)).clamp(0., 1.);
+            distance = distance.min(point.distance(a + ab * t));
+        }
+        return distance;
+    }
+
+    // true if this loop lies inside the other one, loops sharing edges are neighbours
+    pub fn is_inside(&self, other: &Loop) -> bool {
+        if self.area.abs() >= other.area.abs() {
+            return false;
+        }
+        for point in self.polygon.iter() {
+            if other.distance(*point) > PLANE_TOLERANCE {
+                return other.contains(*point);
+            }
+        }
+        return false;
+    }
+}
+
+pub fn to_plane(plane: &Plane, position: Vec3) -> Vec2 {
+    let (u, v) = plane.basis();
+    let local = position - plane.position;
+    return vec2(local.dot(u), local.dot(v));
+}
+
+pub fn from_plane(plane: &Plane, position: Vec2) -> Vec3 {
+    let (u, v) = plane.basis();
+    return plane.position + u * position.x + v * position.y;
+}
+
+pub fn signed_area(polygon: &[Vec2]) -> f32 {
+    let n = polygon.len();
+    let mut area = 0.;
+    for i in 0..n {
+        let a = polygon[i];
+        let b = polygon[(i + 1) % n];
+        area += a.x * b.y - b.x * a.y;
+    }
+    return area / 2.;
+}
+
+fn edge_points(
+    components: &ComponentCollection,
+    plane: &Plane,
+    edge: &LoopEdge,
+    tolerance: f32,
+) -> Vec<Vec2> {
+    components.get_c(edge.ctype, edge.index as usize, |c| {
+        let c = c.unwrap();
+        let position = |t: f32| c.get_position(components, edge.direction, t);
+        let parameters = match edge.ctype {
+            ComponentType::Line => vec![0., 1.],
+            _ => tessellate(position, tolerance),
+        };
+        parameters
+            .iter()
+            .map(|t| to_plane(plane, position(*t)))
+            .collect()
+    })
+}
+
+pub fn in_plane(plane: &Plane, position: Vec3) -> bool {
+    (position - plane.position).dot(plane.orientation.normalize()).abs() < PLANE_TOLERANCE
+}
+
+struct GraphEdge {
+    ctype: ComponentType,
+    index: u32,
+    start: u32,
+    end: u32,
+}
+
+fn curve_edges(components: &ComponentCollection, plane: &Plane) -> Vec<GraphEdge> {
+    let mut edges = vec![];
+    let point = |i: u32| components.points.array[i as usize].data.position;
+    let mut push = |c: &dyn IComponent, ctype: ComponentType, index: usize, defining: &[u32]| {
+        if c.is_deleted() || c.is_construction() || !defining.iter().all(|p| in_plane(plane, point(*p))) {
+            return;
+        }
+        let index = index as u32;
+        let start = c.get_index(WalkDirection::Up, IndexPosition::First, index);
+        let end = c.get_index(WalkDirection::Up, IndexPosition::Last, index);
+        if let (Some(start), Some(end)) = (start, end) {
+            edges.push(GraphEdge {
+                ctype,
+                index,
+                start,
+                end,
+            });
+        }
+    };
+
+    for (i, line) in components.lines.array.iter().enumerate() {
+        let l = &line.data;
+        push(line, ComponentType::Line, i, &[l.point_a, l.point_b]);
+    }
+    for (i, bezier) in components.beziers.array.iter().enumerate() {
+        let b = &bezier.data;
+        let defining = [b.point_a, b.point_b, b.control_a, b.control_b];
+        push(bezier, ComponentType::Bezier, i, &defining);
+    }
+    for (i, nurbs) in components.nurbs.array.iter().enumerate() {
+        let defining: Vec<u32> = nurbs.data.controls(components).iter().map(|c| c.data.point).collect();
+        push(nurbs, ComponentType::Nurbs, i, &defining);
+    }
+    let normal = plane.orientation.normalize();
+    for (i, arc) in components.arcs.array.iter().enumerate() {
+        let a = &arc.data;
+        if a.orientation.normalize().dot(normal).abs() > 1. - 1e-4 {
+            push(arc, ComponentType::Arc, i, &[a.center, a.start, a.end]);
+        }
+    }
+    return edges;
+}
+
+// full circles and ellipses are loops on their own
+fn circle_loops(components: &ComponentCollection, plane: &Plane, tolerance: f32) -> Vec<Loop> {
+    let normal = plane.orientation.normalize();
+    let mut closed = vec![];
+    for (i, circle) in components.circles.array.iter().enumerate() {
+        let c = &circle.data;
+        if !circle.is_deleted() && !circle.is_construction() && c.is_closed() {
+            closed.push((ComponentType::Circle, i, c.orientation, circle.get_center(components)));
+        }
+    }
+    for (i, ellipse) in components.ellipses.array.iter().enumerate() {
+        let e = &ellipse.data;
+        if !ellipse.is_deleted() && !ellipse.is_construction() && e.is_closed() {
+            closed.push((ComponentType::Ellipse, i, e.orientation, ellipse.get_center(components)));
+        }
+    }
+
+    let mut loops = vec![];
+    for (ctype, i, orientation, center) in closed {
+        let parallel = orientation.normalize().dot(normal).abs() > 1. - 1e-4;
+        if !parallel || !in_plane(plane, center) {
+            continue;
+        }
+        let edges = vec![LoopEdge {
+            ctype,
+            index: i as u32,
+            direction: WalkDirection::Up,
+        }];
+        let circle_loop = Loop::new(edges, components, plane, tolerance);
+        if circle_loop.area < 0. {
+            loops.push(circle_loop.reversed());
+        } else {
+            loops.push(circle_loop);
+        }
+    }
+    return loops;
+}
+
+struct Outgoing {
+    angle: f32,
+    bend: f32,
+    key: (u32, u32, bool),
+    half_edge: usize,
+}
+
+// closed loops of connected curves as faces of the planar curve graph, all counter clockwise
+pub fn find_loops(components: &ComponentCollection, plane: &Plane, tolerance: f32) -> Vec<Loop> {
+    let mut edges = curve_edges(components, plane);
+
+    // the in plane direction a curve leaves its start with, and how it bends away from it after half its length
+    let leaving = |ctype: ComponentType, index: u32, direction: WalkDirection| -> (Vec2, Vec2) {
+        return components.get_c(ctype, index as usize, |c| {
+            let c = c.unwrap();
+            let origin = to_plane(plane, Vec3::ZERO);
+            let tangent = to_plane(plane, c.get_orientation(components, direction)) - origin;
+            let start = to_plane(plane, c.get_position(components, direction, 0.));
+            let middle = to_plane(plane, c.get_position(components, direction, 0.5));
+            (tangent, middle - start)
+        });
+    };
+
+    // zero length or broken curves have no direction to sort them by
+    edges.retain(|e| {
+        [WalkDirection::Up, WalkDirection::Down].into_iter().all(|direction| {
+            let (tangent, chord) = leaving(e.ctype, e.index, direction);
+            tangent.is_finite() && chord.is_finite() && tangent.length() > 1e-6 && chord.length() > PLANE_TOLERANCE
+        })
+    });
+
+    // curves with a loose end can't be part of a loop
+    loop {
+        let mut degree: HashMap<u32, usize> = HashMap::new();
+        for edge in edges.iter() {
+            *degree.entry(edge.start).or_insert(0) += 1;
+            *degree.entry(edge.end).or_insert(0) += 1;
+        }
+        let count = edges.len();
+        edges.retain(|e| degree[&e.start] > 1 && degree[&e.end] > 1);
+        if edges.len() == count {
+            break;
+        }
+    }
+
+    // half edge 2 * i walks edge i up, 2 * i + 1 walks it down
+    let half_edge = |h: usize| -> (LoopEdge, u32, u32) {
+        let edge = &edges[h / 2];
+        let direction = if h % 2 == 0 {
+            WalkDirection::Up
+        } else {
+            WalkDirection::Down
+        };
+        let (start, end) = if h % 2 == 0 {
+            (edge.start, edge.end)
+        } else {
+            (edge.end, edge.start)
+        };
+        let loop_edge = LoopEdge {
+            ctype: edge.ctype,
+            index: edge.index,
+            direction,
+        };
+        (loop_edge, start, end)
+    };
+
+    // sorted counter clockwise by the leaving angle, curves leaving along the same tangent by how much they bend
+    // to the left, curves lying on top of each other by their component
+    let mut outgoing: HashMap<u32, Vec<Outgoing>> = HashMap::new();
+    for h in 0..edges.len() * 2 {
+        let (edge, start, _) = half_edge(h);
+        let (tangent, chord) = leaving(edge.ctype, edge.index, edge.direction);
+        outgoing.entry(start).or_insert(vec![]).push(Outgoing {
+            angle: tangent.y.atan2(tangent.x),
+            bend: tangent.perp_dot(chord).atan2(tangent.dot(chord)),
+            key: (edge.ctype as u32, edge.index, h % 2 == 1),
+            half_edge: h,
+        });
+    }
+    for list in outgoing.values_mut() {
+        list.sort_by(|a, b| {
+            a.angle
+                .total_cmp(&b.angle)
+                .then(a.bend.total_cmp(&b.bend))
+                .then(a.key.cmp(&b.key))
+        });
+    }
+
+    // the next half edge is the one clockwise before the twin, this keeps the face on the left
+    let next = |h: usize| -> usize {
+        let (_, _, end) = half_edge(h);
+        let twin = h ^ 1;
+        let list = &outgoing[&end];
+        let position = list.iter().position(|o| o.half_edge == twin).unwrap();
+        return list[(position + list.len() - 1) % list.len()].half_edge;
+    };
+
+    let mut visited = vec![false; edges.len() * 2];
+    let mut loops = vec![];
+    for h in 0..edges.len() * 2 {
+        if visited[h] {
+            continue;
+        }
+        let mut face = vec![];
+        let mut current = h;
+        while !visited[current] {
+            visited[current] = true;
+            face.push(half_edge(current).0);
+            current = next(current);
+        }
+        if current != h {
+            continue;
+        }
+        let face = Loop::new(face, components, plane, tolerance);
+        // the unbounded outside of each connected group is walked clockwise
+        if face.area > PLANE_TOLERANCE * PLANE_TOLERANCE {
+            loops.push(face);
+        }
+    }
+
+    loops.extend(circle_loops(components, plane, tolerance));
+    return loops;
+}
+
+pub fn find_profiles(components: &ComponentCollection, plane: &Plane, tolerance: f32) -> Vec<Profile> {
+    let loops = find_loops(components, plane, tolerance);
+
+    let parents: Vec<Vec<usize>> = loops
+        .iter()
+        .map(|inner| {
+            (0..loops.len())
+                .filter(|j| inner.is_inside(&loops[*j]))
+                .collect()
+        })
+        .collect();
+
+    return loops
+        .iter()
+        .enumerate()
+        .map(|(i, outer)| {
+            let depth = parents[i].len();
+            let holes = (0..loops.len())
+                .filter(|k| parents[*k].len() == depth + 1 && parents[*k].contains(&i))
+                .map(|k| loops[k].reversed())
+                .collect();
+            Profile {
+                plane: plane.clone(),
+                outer: outer.clone(),
+                holes,
+                depth,
+            }
+        })
+        .collect();
+}
+
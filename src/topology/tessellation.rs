@@ -0,0 +1,73 @@
+use glam::Vec3;
+
+// default chord tolerance in mm
+pub const TOLERANCE: f32 = 0.01;
+const START_SEGMENTS: usize = 4;
+const MAX_DEPTH: u32 = 8;
+
+#[derive(Clone)]
+pub struct Tessellation {
+    // largest distance between a curve and its polyline, in mm
+    pub tolerance: f32,
+}
+
+impl Default for Tessellation {
+    fn default() -> Self {
+        Tessellation {
+            tolerance: TOLERANCE,
+        }
+    }
+}
+
+// parameters along a curve so that no chord is further than the tolerance away from it
+pub fn tessellate(position: impl Fn(f32) -> Vec3, tolerance: f32) -> Vec<f32> {
+    let mut parameters = vec![0.];
+    for i in 0..START_SEGMENTS {
+        let t0 = i as f32 / START_SEGMENTS as f32;
+        let t1 = (i + 1) as f32 / START_SEGMENTS as f32;
+        subdivide(
+            &position,
+            t0,
+            t1,
+            position(t0),
+            position(t1),
+            tolerance,
+            0,
+            &mut parameters,
+        );
+    }
+    return parameters;
+}
+
+fn subdivide(
+    position: &impl Fn(f32) -> Vec3,
+    t0: f32,
+    t1: f32,
+    p0: Vec3,
+    p1: Vec3,
+    tolerance: f32,
+    depth: u32,
+    parameters: &mut Vec<f32>,
+) {
+    let tm = (t0 + t1) / 2.;
+    let pm = position(tm);
+    if depth < MAX_DEPTH && deviation(p0, p1, pm) > tolerance {
+        subdivide(position, t0, tm, p0, pm, tolerance, depth + 1, parameters);
+        subdivide(position, tm, t1, pm, p1, tolerance, depth + 1, parameters);
+    } else {
+        parameters.push(t1);
+    }
+}
+
+fn deviation(a: Vec3, b: Vec3, point: Vec3) -> f32 {
+    let ab = b - a;
+    let t = ((point - a).dot(ab) / ab.length_squared().max(1e-12)).clamp(0., 1.);
+    return point.distance(a + ab * t);
+}
+
+// segments for an arc of the given radius and angle in radians
+pub fn arc_segments(radius: f32, angle: f32, tolerance: f32) -> usize {
+    let step = 2. * (1. - (tolerance / radius.max(1e-6)).min(1.)).acos();
+    let segments = (angle.abs() / step.max(1e-3)).ceil() as usize;
+    return segments.clamp(3, 720);
+}
@@ -0,0 +1,207 @@
+use glam::{vec2, Vec2};
+
+use crate::{
+    component_collection::{ComponentArray, ComponentCollection},
+    components::{
+        bezier,
+        component::{Component, ComponentFlags},
+        line, point,
+    },
+    core::{basics::Plane, result::Result},
+    io::ttf::{cubic, Font, GlyphSegment},
+};
+
+use super::profile::from_plane;
+
+// end points closer than this in mm are the same point
+const JOIN_TOLERANCE: f32 = 1e-4;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+}
+
+impl TextAlign {
+    pub const ALL: [TextAlign; 3] = [TextAlign::Left, TextAlign::Center, TextAlign::Right];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TextAlign::Left => "Left",
+            TextAlign::Center => "Center",
+            TextAlign::Right => "Right",
+        }
+    }
+}
+
+// the outlines of a string in the frame of its plane, the curves index into the points
+#[derive(Default)]
+pub struct TextOutline {
+    pub points: Vec<Vec2>,
+    pub lines: Vec<[usize; 2]>,
+    // start, the two controls and the end
+    pub beziers: Vec<[usize; 4]>,
+}
+
+impl TextOutline {
+    fn point(&mut self, p: Vec2) -> usize {
+        self.points.push(p);
+        return self.points.len() - 1;
+    }
+
+    // the contours are closed loops, their last segment ends in the first point
+    fn contour(&mut self, segments: &[GlyphSegment], scale: f32, offset: Vec2) {
+        let map = |p: Vec2| p * scale + offset;
+        // fonts repeat the first point at the end of a contour
+        let segments: Vec<&GlyphSegment> = segments
+            .iter()
+            .filter(|s| map(s.start()).distance(map(s.end())) >= JOIN_TOLERANCE)
+            .collect();
+        let Some(segment) = segments.first() else {
+            return;
+        };
+        let first = self.point(map(segment.start()));
+        let mut current = first;
+        for (i, segment) in segments.iter().enumerate() {
+            let (start, end) = (map(segment.start()), map(segment.end()));
+            let index = if i == segments.len() - 1 { first } else { self.point(end) };
+            match segment {
+                GlyphSegment::Line(..) => self.lines.push([current, index]),
+                GlyphSegment::Quadratic(_, control, _) => {
+                    let [_, control_a, control_b, _] = cubic(start, map(*control), end);
+                    let (control_a, control_b) = (self.point(control_a), self.point(control_b));
+                    self.beziers.push([current, control_a, control_b, index]);
+                }
+            }
+            current = index;
+        }
+    }
+}
+
+// lines of text go down from the baseline of the first one at the origin, the size is the
+// height of the em square in mm
+pub fn outline(font: &Font<'_>, text: &str, size: f32, align: TextAlign) -> Result<TextOutline> {
+    let scale = size / font.units_per_em;
+    let line_height = (font.ascender - font.descender + font.line_gap) * scale;
+    let mut outline = TextOutline::default();
+    for (row, text_line) in text.lines().enumerate() {
+        let mut glyphs = vec![];
+        let mut width = 0.;
+        for c in text_line.chars() {
+            let glyph = font.glyph(c)?;
+            glyphs.push((width, glyph.contours));
+            width += glyph.advance * scale;
+        }
+        let x = match align {
+            TextAlign::Left => 0.,
+            TextAlign::Center => -width / 2.,
+            TextAlign::Right => -width,
+        };
+        for (advance, contours) in glyphs {
+            for contour in contours {
+                outline.contour(&contour, scale, vec2(x + advance, -(row as f32) * line_height));
+            }
+        }
+    }
+    return Ok(outline);
+}
+
+// a string on a plane, its curves are rebuilt when it is edited
+pub struct Text {
+    pub content: String,
+    pub size: f32,
+    pub align: TextAlign,
+    // the origin of the first baseline and the normal of the plane
+    pub plane: Plane,
+    // the components built for the text, the ones behind the current outline are deleted and
+    // reused on the next edit
+    points: Vec<u32>,
+    lines: Vec<u32>,
+    beziers: Vec<u32>,
+}
+
+impl Text {
+    pub fn new(content: String, size: f32, align: TextAlign, plane: Plane) -> Text {
+        Text {
+            content,
+            size,
+            align,
+            plane,
+            points: vec![],
+            lines: vec![],
+            beziers: vec![],
+        }
+    }
+}
+
+// writes into the i-th owned component or adds one, the style stays
+fn reuse<T>(array: &mut ComponentArray<T>, owned: &mut Vec<u32>, i: usize, component: Component<T>) -> u32 {
+    if let Some(index) = owned.get(i) {
+        array.update(*index as usize, |c| {
+            c.data = component.data;
+            c.flags = (c.flags & !(ComponentFlags::Deleted as i32)) | ComponentFlags::Visible as i32;
+        });
+        return *index;
+    }
+    let index = array.push(component) as u32;
+    owned.push(index);
+    return index;
+}
+
+fn delete_from<T>(array: &mut ComponentArray<T>, owned: &[u32], count: usize) {
+    for index in owned.iter().skip(count) {
+        array.update(*index as usize, |c| c.flags |= ComponentFlags::Deleted as i32);
+    }
+}
+
+#[derive(Default)]
+pub struct Texts {
+    pub texts: Vec<Text>,
+}
+
+impl Texts {
+    pub fn add(&mut self, components: &mut ComponentCollection, text: Text) -> Result<usize> {
+        self.texts.push(text);
+        let index = self.texts.len() - 1;
+        if let Err(error) = self.rebuild(components, index) {
+            self.texts.pop();
+            return Err(error);
+        }
+        return Ok(index);
+    }
+
+    // lays the text out again after its content, size, alignment or plane changed
+    pub fn rebuild(&mut self, components: &mut ComponentCollection, index: usize) -> Result<()> {
+        let text = &mut self.texts[index];
+        let outline = outline(&Font::bundled(), &text.content, text.size, text.align)?;
+        let points: Vec<u32> = outline
+            .points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let position = from_plane(&text.plane, *p);
+                reuse(&mut components.points, &mut text.points, i, point::new(position))
+            })
+            .collect();
+        for (i, [a, b]) in outline.lines.iter().enumerate() {
+            reuse(&mut components.lines, &mut text.lines, i, line::new(points[*a], points[*b]));
+        }
+        for (i, [a, control_a, control_b, b]) in outline.beziers.iter().enumerate() {
+            let curve = bezier::new(points[*a], points[*b], points[*control_a], points[*control_b]);
+            reuse(&mut components.beziers, &mut text.beziers, i, curve);
+        }
+        delete_from(&mut components.points, &text.points, outline.points.len());
+        delete_from(&mut components.lines, &text.lines, outline.lines.len());
+        delete_from(&mut components.beziers, &text.beziers, outline.beziers.len());
+        return Ok(());
+    }
+
+    // the curves are deleted, the text is gone from the list
+    pub fn remove(&mut self, components: &mut ComponentCollection, index: usize) {
+        let text = self.texts.remove(index);
+        delete_from(&mut components.points, &text.points, 0);
+        delete_from(&mut components.lines, &text.lines, 0);
+        delete_from(&mut components.beziers, &text.beziers, 0);
+    }
+}
@@ -0,0 +1,128 @@
+use glam::Vec2;
+
+// ear clipping of a counter clockwise outline with clockwise holes,
+// returns the merged points and the triangles as indices into them
+pub fn triangulate(outer: &[Vec2], holes: &[Vec<Vec2>]) -> (Vec<Vec2>, Vec<[usize; 3]>) {
+    let mut points = outer.to_vec();
+    let mut polygon: Vec<usize> = (0..points.len()).collect();
+
+    // holes are bridged into the outline from their rightmost point, rightmost hole first
+    let mut holes: Vec<&Vec<Vec2>> = holes.iter().filter(|h| h.len() >= 3).collect();
+    holes.sort_by(|a, b| max_x(b).partial_cmp(&max_x(a)).unwrap());
+    for hole in holes {
+        let offset = points.len();
+        points.extend(hole.iter());
+        let hole: Vec<usize> = (offset..points.len()).collect();
+        bridge(&points, &mut polygon, &hole);
+    }
+
+    return (points.clone(), clip_ears(&points, polygon));
+}
+
+fn max_x(polygon: &[Vec2]) -> f32 {
+    polygon.iter().map(|p| p.x).fold(f32::MIN, f32::max)
+}
+
+fn cross(o: Vec2, a: Vec2, b: Vec2) -> f32 {
+    (a - o).perp_dot(b - o)
+}
+
+fn in_triangle(a: Vec2, b: Vec2, c: Vec2, p: Vec2) -> bool {
+    cross(a, b, p) >= 0. && cross(b, c, p) >= 0. && cross(c, a, p) >= 0.
+}
+
+fn bridge(points: &[Vec2], polygon: &mut Vec<usize>, hole: &[usize]) {
+    let m = *hole
+        .iter()
+        .max_by(|a, b| points[**a].x.partial_cmp(&points[**b].x).unwrap())
+        .unwrap();
+    let mp = points[m];
+
+    // closest edge hit by a ray from m to the right
+    let mut hit: Option<(f32, usize)> = None;
+    for i in 0..polygon.len() {
+        let a = points[polygon[i]];
+        let b = points[polygon[(i + 1) % polygon.len()]];
+        if (a.y > mp.y) == (b.y > mp.y) {
+            continue;
+        }
+        let x = a.x + (mp.y - a.y) / (b.y - a.y) * (b.x - a.x);
+        if x >= mp.x && hit.map_or(true, |(hx, _)| x < hx) {
+            let candidate = if a.x > b.x { i } else { (i + 1) % polygon.len() };
+            hit = Some((x, candidate));
+        }
+    }
+    let Some((x, mut candidate)) = hit else {
+        return;
+    };
+
+    // a reflex vertex inside the triangle m, hit point, candidate would block the bridge,
+    // the one with the smallest angle to the ray is visible
+    let intersection = Vec2::new(x, mp.y);
+    let cp = points[polygon[candidate]];
+    let (a, b, c) = if cp.y < mp.y {
+        (mp, cp, intersection)
+    } else {
+        (mp, intersection, cp)
+    };
+    let mut best_angle = f32::MAX;
+    for i in 0..polygon.len() {
+        let p = points[polygon[i]];
+        if i == candidate || p == cp || !in_triangle(a, b, c, p) {
+            continue;
+        }
+        let prev = points[polygon[(i + polygon.len() - 1) % polygon.len()]];
+        let next = points[polygon[(i + 1) % polygon.len()]];
+        if cross(prev, p, next) > 0. {
+            continue;
+        }
+        let d = p - mp;
+        let angle = (d.y / d.length().max(1e-12)).abs();
+        if angle < best_angle {
+            best_angle = angle;
+            candidate = i;
+        }
+    }
+
+    let start = hole.iter().position(|h| *h == m).unwrap();
+    let mut merged: Vec<usize> = polygon[..=candidate].to_vec();
+    for k in 0..=hole.len() {
+        merged.push(hole[(start + k) % hole.len()]);
+    }
+    merged.extend_from_slice(&polygon[candidate..]);
+    *polygon = merged;
+}
+
+fn clip_ears(points: &[Vec2], mut polygon: Vec<usize>) -> Vec<[usize; 3]> {
+    let mut triangles = vec![];
+    while polygon.len() > 3 {
+        let n = polygon.len();
+        let mut ear = None;
+        let mut convex = None;
+        for i in 0..n {
+            let (ia, ib, ic) = (polygon[(i + n - 1) % n], polygon[i], polygon[(i + 1) % n]);
+            let (a, b, c) = (points[ia], points[ib], points[ic]);
+            if cross(a, b, c) <= 0. {
+                continue;
+            }
+            convex.get_or_insert(i);
+            // bridge points exist twice, only points at other positions can block an ear
+            let blocked = polygon.iter().any(|j| {
+                let p = points[*j];
+                p != a && p != b && p != c && in_triangle(a, b, c, p)
+            });
+            if !blocked {
+                ear = Some(i);
+                break;
+            }
+        }
+        // degenerate input, cut something off so the loop terminates
+        let i = ear.or(convex).unwrap_or(0);
+        triangles.push([polygon[(i + n - 1) % n], polygon[i], polygon[(i + 1) % n]]);
+        polygon.remove(i);
+    }
+    if polygon.len() == 3 {
+        triangles.push([polygon[0], polygon[1], polygon[2]]);
+    }
+    return triangles;
+}
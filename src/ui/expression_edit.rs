@@ -0,0 +1,64 @@
+use egui::{Color32, Id, TextEdit, Ui};
+
+use crate::core::{result::Result, units::UnitSystem};
+
+#[derive(Clone)]
+struct PendingInput {
+    text: String,
+    error: Option<String>,
+}
+
+// text field that keeps the typed expression while editing and commits it on enter or focus loss
+pub fn expression_edit<T>(
+    ui: &mut Ui,
+    id: Id,
+    display: String,
+    parse: impl Fn(&str) -> Result<T>,
+) -> Option<(String, T)> {
+    let mut pending = ui
+        .data(|d| d.get_temp::<PendingInput>(id))
+        .unwrap_or(PendingInput {
+            text: display,
+            error: None,
+        });
+
+    let mut edit = TextEdit::singleline(&mut pending.text)
+        .id(id)
+        .desired_width(100.);
+    if pending.error.is_some() {
+        edit = edit.text_color(Color32::RED);
+    }
+    let mut response = ui.add(edit);
+    if let Some(error) = pending.error.clone() {
+        response = response.on_hover_text(error);
+    }
+
+    if response.lost_focus() {
+        match parse(&pending.text) {
+            Ok(value) => {
+                ui.data_mut(|d| d.remove::<PendingInput>(id));
+                return Some((pending.text, value));
+            }
+            Err(error) => pending.error = Some(error.to_string()),
+        }
+    } else if response.changed() {
+        pending.error = None;
+    }
+
+    if response.has_focus() || pending.error.is_some() {
+        ui.data_mut(|d| d.insert_temp(id, pending));
+    } else {
+        ui.data_mut(|d| d.remove::<PendingInput>(id));
+    }
+    return None;
+}
+
+pub fn length_edit(ui: &mut Ui, id: Id, units: &UnitSystem, value: &mut f32) -> bool {
+    if let Some((_, mm)) = expression_edit(ui, id, units.format_value(*value), |text| {
+        units.parse(text)
+    }) {
+        *value = mm;
+        return true;
+    }
+    return false;
+}
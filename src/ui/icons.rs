@@ -23,6 +23,25 @@ pub struct IconCollection {
     pub left_view: TextureHandle,
     pub right_view: TextureHandle,
     pub top_view: TextureHandle,
+
+    pub grid: TextureHandle,
+    pub settings: TextureHandle,
+    pub parameters: TextureHandle,
+
+    pub arc_center: TextureHandle,
+    pub arc_three_points: TextureHandle,
+    pub arc_tangent: TextureHandle,
+    pub ellipse: TextureHandle,
+    pub spline: TextureHandle,
+    pub convert_curves: TextureHandle,
+    pub construction: TextureHandle,
+    pub extrude: TextureHandle,
+    pub revolve: TextureHandle,
+    pub sweep: TextureHandle,
+    pub loft: TextureHandle,
+    pub export: TextureHandle,
+    pub import: TextureHandle,
+    pub text: TextureHandle,
 }
 
 static ICONS: Mutex<Option<IconCollection>> = Mutex::new(None);
@@ -51,6 +70,25 @@ pub fn get_icons(ui: &mut Ui) -> IconCollection {
             left_view: load_svg(ui, "invisible", include_bytes!("../../assets/icons/views/left_view.svg")),
             right_view: load_svg(ui, "invisible", include_bytes!("../../assets/icons/views/right_view.svg")),
             top_view: load_svg(ui, "invisible", include_bytes!("../../assets/icons/views/top_view.svg")),
+
+            grid: load_svg(ui, "grid", include_bytes!("../../assets/icons/grid.svg")),
+            settings: load_svg(ui, "settings", include_bytes!("../../assets/icons/settings.svg")),
+            parameters: load_svg(ui, "parameters", include_bytes!("../../assets/icons/parameters.svg")),
+
+            arc_center: load_svg(ui, "arc_center", include_bytes!("../../assets/icons/arc_center.svg")),
+            arc_three_points: load_svg(ui, "arc_three_points", include_bytes!("../../assets/icons/arc_three_points.svg")),
+            arc_tangent: load_svg(ui, "arc_tangent", include_bytes!("../../assets/icons/arc_tangent.svg")),
+            ellipse: load_svg(ui, "ellipse", include_bytes!("../../assets/icons/ellipse.svg")),
+            spline: load_svg(ui, "spline", include_bytes!("../../assets/icons/spline.svg")),
+            convert_curves: load_svg(ui, "convert_curves", include_bytes!("../../assets/icons/convert_curves.svg")),
+            construction: load_svg(ui, "construction", include_bytes!("../../assets/icons/construction.svg")),
+            extrude: load_svg(ui, "extrude", include_bytes!("../../assets/icons/extrude.svg")),
+            revolve: load_svg(ui, "revolve", include_bytes!("../../assets/icons/revolve.svg")),
+            sweep: load_svg(ui, "sweep", include_bytes!("../../assets/icons/sweep.svg")),
+            loft: load_svg(ui, "loft", include_bytes!("../../assets/icons/loft.svg")),
+            export: load_svg(ui, "export", include_bytes!("../../assets/icons/export.svg")),
+            import: load_svg(ui, "import", include_bytes!("../../assets/icons/import.svg")),
+            text: load_svg(ui, "text", include_bytes!("../../assets/icons/text.svg")),
         });
     }
    
@@ -1,3 +1,5 @@
 pub mod tabcontrol;
 pub mod icons;
-pub mod main_menu;
\ No newline at end of file
+pub mod main_menu;
+pub mod expression_edit;
+pub mod properties;
\ No newline at end of file
@@ -0,0 +1,279 @@
+use egui::{Context, Ui};
+
+use crate::{
+    component_collection::ComponentCollection,
+    components::{
+        component::{ComponentType, IComponent},
+        style::{self, PointShape, Style},
+    },
+    core::units::UnitSystem,
+    parameters::{BindingTarget, Parameters},
+    project::ProjectState,
+};
+
+use super::expression_edit::{expression_edit, length_edit};
+
+pub fn show_properties(ctx: &Context, state: &mut ProjectState) {
+    if state.components.selected.is_empty() {
+        return;
+    }
+
+    egui::Window::new("Properties")
+        .scroll2([false, true])
+        .show(ctx, |ui| {
+            for identifier in state.components.selected.clone().iter() {
+                ui.push_id((identifier.ctype as u32, identifier.index), |ui| {
+                    show_component(ui, state, identifier.ctype, identifier.index as usize);
+                    show_style(ui, &mut state.components, identifier.ctype, identifier.index as usize);
+                });
+                ui.separator();
+            }
+        });
+}
+
+// length field that also accepts parameter expressions, which bind the value to the parameters
+fn bound_edit(
+    ui: &mut Ui,
+    parameters: &mut Parameters,
+    units: &UnitSystem,
+    target: BindingTarget,
+    value: f32,
+) -> Option<f32> {
+    let display = match parameters.binding(target) {
+        Some(binding) => binding.expression.clone(),
+        None => units.format_value(value),
+    };
+    let id = ui.id().with(format!("{:?}", target));
+    let committed = expression_edit(ui, id, display, |text| {
+        parameters.evaluate_text(text, units.unit)
+    });
+    if let Some((text, _)) = committed {
+        return parameters.bind(target, &text, units.unit).ok();
+    }
+    return None;
+}
+
+fn show_component(ui: &mut Ui, state: &mut ProjectState, ctype: ComponentType, index: usize) {
+    let units = state.units.clone();
+    let parameters = &mut state.parameters;
+    let components = &mut state.components;
+    match ctype {
+        ComponentType::Point => {
+            ui.label(format!("Point {}", index));
+            let mut position = components.points.array[index].data.position;
+            let mut changed = false;
+            let i = index as u32;
+            ui.horizontal(|ui| {
+                ui.label("x");
+                if let Some(x) = bound_edit(ui, parameters, &units, BindingTarget::PointX(i), position.x) {
+                    position.x = x;
+                    changed = true;
+                }
+                ui.label("y");
+                if let Some(y) = bound_edit(ui, parameters, &units, BindingTarget::PointY(i), position.y) {
+                    position.y = y;
+                    changed = true;
+                }
+                ui.label("z");
+                if let Some(z) = bound_edit(ui, parameters, &units, BindingTarget::PointZ(i), position.z) {
+                    position.z = z;
+                    changed = true;
+                }
+            });
+            if changed {
+                components.points.update(index, |p| p.data.position = position);
+            }
+        }
+        ComponentType::Line => {
+            ui.label(format!("Line {}", index));
+            let line = &components.lines.array[index].data;
+            let a = components.points.array[line.point_a as usize].get_center(components);
+            let b = components.points.array[line.point_b as usize].get_center(components);
+            ui.label(format!("length {}", units.format(a.distance(b))));
+        }
+        ComponentType::Circle => {
+            ui.label(format!("Circle {}", index));
+            let radius = components.circles.array[index].data.radius;
+            ui.horizontal(|ui| {
+                ui.label("radius");
+                let target = BindingTarget::CircleRadius(index as u32);
+                if let Some(radius) = bound_edit(ui, parameters, &units, target, radius) {
+                    components.circles.update(index, |c| c.data.radius = radius);
+                }
+            });
+
+            // helices rise by the pitch and spirals grow by the growth on every turn
+            let mut circle = components.circles.array[index].data.clone();
+            let mut changed = false;
+            egui::Grid::new("helix").show(ui, |ui| {
+                ui.label("turns");
+                changed |= ui
+                    .add(egui::DragValue::new(&mut circle.turns).speed(0.05).clamp_range(0.05..=20.0))
+                    .changed();
+                ui.end_row();
+                ui.label("pitch");
+                changed |= length_edit(ui, ui.id().with("pitch"), &units, &mut circle.heightfactor);
+                ui.end_row();
+                ui.label("growth");
+                changed |= length_edit(ui, ui.id().with("growth"), &units, &mut circle.growth);
+                ui.end_row();
+            });
+            if changed {
+                components.circles.update(index, |c| c.data = circle.clone());
+            }
+        }
+        ComponentType::Bezier => {
+            ui.label(format!("Bezier {}", index));
+        }
+        ComponentType::Arc => {
+            ui.label(format!("Arc {}", index));
+            let (_, radius, _, _, angle) = components.arcs.array[index].data.geometry(components);
+            ui.label(format!("radius {}", units.format(radius)));
+            ui.label(format!("angle {:.1}°", angle.to_degrees()));
+        }
+        ComponentType::Ellipse => {
+            ui.label(format!("Ellipse {}", index));
+            let mut ellipse = components.ellipses.array[index].data.clone();
+            let mut changed = false;
+            let mut start = ellipse.start_angle.to_degrees();
+            let mut sweep = ellipse.sweep_angle.to_degrees();
+            egui::Grid::new("ellipse").show(ui, |ui| {
+                ui.label("major radius");
+                changed |= length_edit(ui, ui.id().with("radius_a"), &units, &mut ellipse.radius_a);
+                ui.end_row();
+                ui.label("minor radius");
+                changed |= length_edit(ui, ui.id().with("radius_b"), &units, &mut ellipse.radius_b);
+                ui.end_row();
+                ui.label("start");
+                changed |= ui.add(egui::DragValue::new(&mut start).suffix("°")).changed();
+                ui.end_row();
+                ui.label("sweep");
+                changed |= ui
+                    .add(egui::DragValue::new(&mut sweep).clamp_range(-360.0..=360.0).suffix("°"))
+                    .changed();
+                ui.end_row();
+            });
+            if changed {
+                ellipse.start_angle = start.to_radians();
+                ellipse.sweep_angle = sweep.to_radians();
+                components.ellipses.update(index, |e| e.data = ellipse.clone());
+            }
+        }
+        ComponentType::Nurbs => {
+            ui.label(format!("NURBS {}", index));
+            let nurbs = components.nurbs.array[index].data.clone();
+            ui.label(format!("degree {}", nurbs.degree));
+            // the control points are moved through their points, the weights are edited here
+            egui::Grid::new("nurbs").show(ui, |ui| {
+                for i in nurbs.first as usize..(nurbs.first + nurbs.count) as usize {
+                    let control = components.nurbs_controls.array[i].data.clone();
+                    ui.label(format!("point {}", control.point));
+                    let mut weight = control.weight;
+                    if ui
+                        .add(egui::DragValue::new(&mut weight).speed(0.01).clamp_range(0.01..=100.0))
+                        .changed()
+                    {
+                        components.nurbs_controls.update(i, |c| c.data.weight = weight);
+                    }
+                    ui.end_row();
+                }
+            });
+        }
+        ComponentType::Face => {
+            ui.label(format!("Face {}", index));
+            if let Some(profile) = state.fills.profiles.get(index) {
+                let holes: f32 = profile.holes.iter().map(|h| h.area).sum();
+                let area = (profile.outer.area + holes) / units.unit.to_mm().powi(2) as f32;
+                ui.label(format!("area {:.*} {}²", units.precision, area, units.unit.suffix()));
+                ui.label(format!("holes {}", profile.holes.len()));
+            }
+        }
+        ComponentType::Mesh => {
+            ui.label(format!("Mesh {}", index));
+            let mesh = &components.meshes.array[index].data;
+            ui.label(format!("triangles {}", mesh.count / 3));
+            let mut wireframe = mesh.wireframe != 0;
+            if ui.checkbox(&mut wireframe, "wireframe").changed() {
+                components.meshes.update(index, |m| m.data.wireframe = wireframe as u32);
+            }
+        }
+        ComponentType::Arrow | ComponentType::ArrowPlane => {}
+    }
+}
+
+// components share style 0 until they are changed here, then they get a style of their own
+fn show_style(ui: &mut Ui, components: &mut ComponentCollection, ctype: ComponentType, index: usize) {
+    let is_point = match ctype {
+        ComponentType::Point => true,
+        ComponentType::Line
+        | ComponentType::Circle
+        | ComponentType::Bezier
+        | ComponentType::Arc
+        | ComponentType::Ellipse
+        | ComponentType::Nurbs
+        | ComponentType::Mesh => false,
+        ComponentType::Face | ComponentType::Arrow | ComponentType::ArrowPlane => return,
+    };
+    let Some(style_index) = components.get_c(ctype, index, |c| c.map(|c| c.get_style())) else {
+        return;
+    };
+    let mut style: Style = components.styles.array[style_index as usize].data.clone();
+
+    ui.collapsing("Style", |ui| {
+        egui::Grid::new("style").show(ui, |ui| {
+            // alpha 0 keeps the theme color
+            let mut custom = style.color[3] > 0.;
+            ui.label("color");
+            ui.horizontal(|ui| {
+                if ui.checkbox(&mut custom, "").changed() {
+                    style.color = if custom { [1., 1., 1., 1.] } else { [0.; 4] };
+                }
+                if custom {
+                    ui.color_edit_button_rgba_unmultiplied(&mut style.color);
+                }
+            });
+            ui.end_row();
+
+            if is_point {
+                ui.label("size");
+                ui.add(egui::DragValue::new(&mut style.point_size).speed(0.1).clamp_range(0.0..=50.0).suffix(" px"));
+                ui.end_row();
+                ui.label("shape");
+                egui::ComboBox::from_id_source("point_shape")
+                    .selected_text(style.point_shape.name())
+                    .show_ui(ui, |ui| {
+                        for shape in PointShape::ALL {
+                            ui.selectable_value(&mut style.point_shape, shape, shape.name());
+                        }
+                    });
+                ui.end_row();
+            } else if ctype != ComponentType::Mesh {
+                // 0 uses the line settings of the project
+                ui.label("width");
+                ui.add(egui::DragValue::new(&mut style.width).speed(0.1).clamp_range(0.0..=20.0).suffix(" px"));
+                ui.end_row();
+                ui.label("dash");
+                ui.add(egui::DragValue::new(&mut style.dash).speed(0.1).clamp_range(0.0..=100.0).suffix(" px"));
+                ui.end_row();
+                ui.label("gap");
+                ui.add(egui::DragValue::new(&mut style.gap).speed(0.1).clamp_range(0.0..=100.0).suffix(" px"));
+                ui.end_row();
+            }
+        });
+        if style_index != 0 && ui.button("Default style").clicked() {
+            components.update_c(ctype, index, |c| c.set_style(0));
+        }
+    });
+
+    if style == components.styles.array[style_index as usize].data {
+        return;
+    }
+    if style_index == 0 {
+        let mut component = style::default();
+        component.data = style;
+        let new = components.styles.push(component) as u32;
+        components.update_c(ctype, index, move |c| c.set_style(new));
+    } else {
+        components.styles.update(style_index as usize, |s| s.data = style.clone());
+    }
+}